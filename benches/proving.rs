@@ -0,0 +1,139 @@
+// Compares the cost of the MockProver with the one of a real KZG proof for some of the circuits.
+// The srs and the keys are generated once per circuit, only `gen_proof` is timed for the KZG case.
+//
+// `cargo bench --bench proving`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use halo2_experiments::chips::poseidon::spec::MySpec;
+use halo2_experiments::circuits::hash_v2::Hash2Circuit;
+use halo2_experiments::circuits::merkle_sum_tree::MerkleSumTreeCircuit;
+use halo2_experiments::circuits::merkle_v3::MerkleTreeV3Circuit;
+use halo2_experiments::circuits::safe_accumulator::SafeAccumulatorCircuit;
+use halo2_experiments::circuits::utils::{gen_proof, gen_srs};
+use halo2_experiments::merkle_sum_tree::{MerkleSumTree, Node};
+use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    halo2curves::bn256::Fr as Fp,
+    plonk::{keygen_pk, keygen_vk, Circuit},
+};
+
+// Times the MockProver and the KZG prover of the circuit returned by `build` at the given k
+fn bench_circuit<C: Circuit<Fp>>(
+    c: &mut Criterion,
+    name: &str,
+    k: u32,
+    build: impl Fn() -> C,
+    public_input: Vec<Fp>,
+) {
+    let mut group = c.benchmark_group(name);
+    // a real proof takes seconds, the default 100 samples would take too long
+    group.sample_size(10);
+
+    group.bench_function("mock_prover", |b| {
+        b.iter(|| {
+            let prover = MockProver::run(k, &build(), vec![public_input.clone()]).unwrap();
+            prover.assert_satisfied();
+        })
+    });
+
+    let params = gen_srs(k);
+    let vk = keygen_vk(&params, &build()).unwrap();
+    let pk = keygen_pk(&params, vk, &build()).unwrap();
+
+    group.bench_function("kzg_prover", |b| {
+        b.iter(|| gen_proof(&params, &pk, build(), &public_input))
+    });
+
+    group.finish();
+}
+
+fn bench_hash_v2(c: &mut Criterion) {
+    let build = || Hash2Circuit {
+        a: Value::known(Fp::from(2)),
+        b: Value::known(Fp::from(7)),
+    };
+
+    bench_circuit(c, "hash_v2", 4, build, vec![Fp::from(9)]);
+}
+
+fn bench_merkle_v3(c: &mut Criterion) {
+    let leaf = Fp::from(99u64);
+    let elements = [1u64, 5u64, 6u64, 9u64, 9u64].map(Fp::from);
+    let indices = [0u64, 1u64, 0u64, 1u64, 0u64].map(Fp::from);
+
+    let mut root = leaf;
+    for (element, index) in elements.iter().zip(indices.iter()) {
+        let message = if *index == Fp::from(0) {
+            [root, *element]
+        } else {
+            [*element, root]
+        };
+        root = poseidon::Hash::<_, MySpec<Fp, 3, 2>, ConstantLength<2>, 3, 2>::init().hash(message);
+    }
+
+    let build = || MerkleTreeV3Circuit {
+        leaf: Value::known(leaf),
+        path_elements: elements.map(Value::known).to_vec(),
+        path_indices: indices.map(Value::known).to_vec(),
+    };
+
+    bench_circuit(c, "merkle_v3", 10, build, vec![leaf, root]);
+}
+
+fn bench_merkle_sum_tree(c: &mut Criterion) {
+    let leaves = (1..=16u64)
+        .map(|i| Node {
+            hash: Fp::from(i),
+            balance: Fp::from(i * 100),
+        })
+        .collect::<Vec<Node>>();
+    let tree = MerkleSumTree::new(leaves);
+    let proof = tree.generate_proof(3);
+
+    // greater than the liabilities sum (13600)
+    let assets_sum = Fp::from(20000u64);
+
+    let build = || {
+        MerkleSumTreeCircuit::new(
+            proof.leaf.hash,
+            proof.leaf.balance,
+            proof.path_elements.iter().map(|node| node.hash).collect(),
+            proof
+                .path_elements
+                .iter()
+                .map(|node| node.balance)
+                .collect(),
+            proof.path_indices.clone(),
+            assets_sum,
+        )
+    };
+    let public_input = build().public_inputs();
+
+    bench_circuit(c, "merkle_sum_tree", 10, build, public_input);
+}
+
+// The range checks of the safe accumulator are lookups into a table of 2^MAX_BITS rows, while the former polynomial
+// range checks had a degree of 2^MAX_BITS + 1, namely 17 for these 4 bits columns
+fn bench_safe_accumulator(c: &mut Criterion) {
+    let build = || SafeAccumulatorCircuit::<4, 4, Fp> {
+        values: vec![Value::known(Fp::from(4))],
+        accumulated_value: [0u64, 0x3, 0xe, 0xd].map(|v| Value::known(Fp::from(v))),
+        packed: false,
+    };
+
+    // 0x3ed + 0x4 = 0x3f1, the accumulate columns followed by the recomposed total
+    let public_input = [0u64, 0x3, 0xf, 0x1, 0x3f1].map(Fp::from).to_vec();
+
+    bench_circuit(c, "safe_accumulator", 8, build, public_input);
+}
+
+criterion_group!(
+    benches,
+    bench_hash_v2,
+    bench_merkle_v3,
+    bench_merkle_sum_tree,
+    bench_safe_accumulator
+);
+criterion_main!(benches);
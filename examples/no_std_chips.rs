@@ -0,0 +1,59 @@
+// Builds the core chips without the standard library, only `alloc` is required.
+// It is a library example so that no panic handler or entry point is needed:
+// `cargo build --example no_std_chips --no-default-features`
+#![no_std]
+
+use eth_types::Field;
+use halo2_experiments::chips::{
+    is_zero::{IsZeroChip, IsZeroConfig},
+    less_than_v2::{LessThanV2Chip, LessThanV2Config},
+    merkle_v1::{MerkleTreeV1Chip, MerkleTreeV1Config},
+    merkle_v2::{MerkleTreeV2Chip, MerkleTreeV2Config},
+    merkle_v3::{MerkleTreeV3Chip, MerkleTreeV3Config},
+};
+use halo2_proofs::{plonk::ConstraintSystem, poly::Rotation};
+
+// the hex helper only relies on `alloc`
+pub use halo2_experiments::chips::utils::f_to_hex;
+
+pub struct CoreChipsConfig<F: Field> {
+    pub is_zero: IsZeroConfig<F>,
+    pub less_than: LessThanV2Config<F, 8>,
+    pub merkle_v1: MerkleTreeV1Config,
+    pub merkle_v2: MerkleTreeV2Config,
+    pub merkle_v3: MerkleTreeV3Config<F>,
+}
+
+// Configures each core chip on its own columns, as a keygen would do
+pub fn configure_core_chips<F: Field>(meta: &mut ConstraintSystem<F>) -> CoreChipsConfig<F> {
+    let value = meta.advice_column();
+    let value_inv = meta.advice_column();
+    let q_enable = meta.selector();
+    let is_zero = IsZeroChip::configure(
+        meta,
+        |meta| meta.query_selector(q_enable),
+        |meta| meta.query_advice(value, Rotation::cur()),
+        value_inv,
+    );
+
+    let lhs = meta.advice_column();
+    let rhs = meta.fixed_column();
+    let check = meta.advice_column();
+    let less_than = LessThanV2Chip::configure(meta, lhs, rhs, check);
+
+    let instance = meta.instance_column();
+    let advice = [(); 3].map(|_| meta.advice_column());
+    let merkle_v1 = MerkleTreeV1Chip::<F>::configure(meta, advice, instance);
+    let advice = [(); 3].map(|_| meta.advice_column());
+    let merkle_v2 = MerkleTreeV2Chip::<F>::configure(meta, advice, instance);
+    let advice = [(); 3].map(|_| meta.advice_column());
+    let merkle_v3 = MerkleTreeV3Chip::configure(meta, advice, instance);
+
+    CoreChipsConfig {
+        is_zero,
+        less_than,
+        merkle_v1,
+        merkle_v2,
+        merkle_v3,
+    }
+}
@@ -1,18 +1,49 @@
+#[cfg(feature = "std")]
 pub mod hash_v1;
 pub mod hash_v2;
+#[cfg(feature = "std")]
 pub mod inclusion_check;
+#[cfg(feature = "std")]
 pub mod inclusion_check_v2;
+#[cfg(feature = "std")]
 pub mod merkle_sum_tree;
 pub mod merkle_v1;
 pub mod merkle_v2;
 pub mod merkle_v3;
+#[cfg(feature = "std")]
 pub mod add_carry_v1;
+#[cfg(feature = "std")]
 pub mod add_carry_v2;
 pub mod is_zero;
+#[cfg(feature = "std")]
 pub mod overflow_check;
+#[cfg(feature = "std")]
 pub mod overflow_check_v2;
+#[cfg(feature = "std")]
 pub mod safe_accumulator;
 pub mod utils;
 pub mod poseidon;
+#[cfg(feature = "std")]
 pub mod less_than;
+#[cfg(feature = "std")]
 pub mod util;
+#[cfg(feature = "std")]
+pub mod range_proof;
+#[cfg(feature = "std")]
+pub mod adjacent_leaves;
+pub mod less_than_v2;
+#[cfg(feature = "std")]
+pub mod commitment;
+#[cfg(feature = "std")]
+pub mod range_check;
+#[cfg(feature = "std")]
+pub mod signed_accumulator;
+#[cfg(feature = "std")]
+pub mod hash_n;
+pub mod gadgets;
+#[cfg(feature = "std")]
+pub mod committed_value;
+#[cfg(feature = "std")]
+pub mod committed_sum;
+#[cfg(feature = "std")]
+pub mod balance_decrease;
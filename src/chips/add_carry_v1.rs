@@ -149,6 +149,23 @@ impl<F: Field> AddCarryChip<F> {
         )
     }
 
+    // Continues an accumulation from the accumulator cells of a previous region, e.g. the cells returned by
+    // `assign_first_row` or by a previous segment. Returns the accumulator cells after adding all the values
+    pub fn continue_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        prev_b: AssignedCell<F, F>,
+        prev_c: AssignedCell<F, F>,
+        values: &[Value<F>],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let (mut b, mut c) = (prev_b, prev_c);
+        for (i, a) in values.iter().enumerate() {
+            (b, c) =
+                self.assign_advice_row(layouter.namespace(|| format!("row {}", i)), *a, b, c)?;
+        }
+        Ok((b, c))
+    }
+
     // Enforce permutation check between b & cell and instance column
     pub fn expose_public(
         &self,
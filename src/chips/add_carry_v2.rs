@@ -2,9 +2,14 @@ use eth_types::Field;
 use std::marker::PhantomData;
 use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
+use super::utils::{decompose_bigInt_to_ubits, value_f_to_big_uint};
+
 #[derive(Debug, Clone)]
 pub struct AddCarryV2Config {
     pub advice: [Column<Advice>; 4],
+    pub a_bytes: [Column<Advice>; 2],
+    pub u8_table: Column<Fixed>,
+    pub constant: Column<Fixed>,
     pub instance: Column<Instance>,
     pub selector: Selector,
 }
@@ -23,6 +28,7 @@ impl<F: Field> AddCarryV2Chip<F> {
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         advice: [Column<Advice>; 4],
+        constant: Column<Fixed>,
         selector: Selector,
         instance: Column<Instance>,
     ) -> AddCarryV2Config {
@@ -32,15 +38,41 @@ impl<F: Field> AddCarryV2Chip<F> {
         let col_c = advice[3];
         let add_carry_selector = selector;
 
+        // 'a' is decomposed into two bytes, each of them is looked up in the u8 table
+        let a_bytes = [meta.advice_column(), meta.advice_column()];
+        let u8_table = meta.fixed_column();
+
         // Enable equality on the advice and instance column to enable permutation check
         meta.enable_equality(col_b);
         meta.enable_equality(col_c);
         meta.enable_equality(instance);
 
+        // Enable constant column, used to seed the accumulator from zero
+        meta.enable_constant(constant);
+
+        // Range check on 'a', the chip could not get the correct result if 'a' is more than 16bits
+        // a = a_byte_0 + a_byte_1 * 2^8, where both bytes are in the u8 table
+        meta.create_gate("a decomposition constraint", |meta| {
+            let s = meta.query_selector(add_carry_selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let a_byte_0 = meta.query_advice(a_bytes[0], Rotation::cur());
+            let a_byte_1 = meta.query_advice(a_bytes[1], Rotation::cur());
+
+            vec![s * (a - (a_byte_0 + a_byte_1 * Expression::Constant(F::from(1 << 8))))]
+        });
+
+        for byte in a_bytes {
+            meta.lookup_any("range check for a bytes", |meta| {
+                let s = meta.query_selector(add_carry_selector);
+                let byte = meta.query_advice(byte, Rotation::cur());
+                vec![(s * byte, meta.query_fixed(u8_table, Rotation::cur()))]
+            });
+        }
+        meta.annotate_lookup_any_column(u8_table, || "LOOKUP_u8");
+
         // This custom gate has two constraints:
         // 1. for each row, the previous accumulator amount + new value from a_cell
         // 2. left most accumulator bit is zero for checking overflow
-        // Note that, if the value 'a' is more than 16bits, this chip could not get the correct result
         meta.create_gate("accumulate constraint", |meta| {
             let s = meta.query_selector(add_carry_selector);
             let prev_b = meta.query_advice(col_b, Rotation::prev());
@@ -63,11 +95,32 @@ impl<F: Field> AddCarryV2Chip<F> {
 
         AddCarryV2Config {
             advice: [col_a, col_b_inv, col_b, col_c],
+            a_bytes,
+            u8_table,
+            constant,
             instance,
             selector: add_carry_selector,
         }
     }
 
+    // Load the u8 table used for the range check on 'a'
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "load u8 table",
+            |mut region| {
+                for i in 0..256 {
+                    region.assign_fixed(
+                        || "u8 table",
+                        self.config.u8_table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
     // Initial accumulator values from instance for expreiment
     pub fn assign_first_row(
         &self,
@@ -97,6 +150,33 @@ impl<F: Field> AddCarryV2Chip<F> {
         )
     }
 
+    // Initial accumulator values set to zero from the constant column, no public input is needed
+    pub fn assign_first_row_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "first row zero",
+            |mut region| {
+                let b_cell = region.assign_advice_from_constant(
+                    || "first acc[1]",
+                    self.config.advice[2],
+                    0,
+                    F::zero(),
+                )?;
+
+                let c_cell = region.assign_advice_from_constant(
+                    || "first acc[2]",
+                    self.config.advice[3],
+                    0,
+                    F::zero(),
+                )?;
+
+                Ok((b_cell, c_cell))
+            },
+        )
+    }
+
     pub fn assign_advice_row(
         &self,
         mut layouter: impl Layouter<F>,
@@ -116,6 +196,17 @@ impl<F: Field> AddCarryV2Chip<F> {
                 // Assign new amount to the cell inside the region
                 region.assign_advice(|| "a", self.config.advice[0], 1, || a)?;
 
+                // Assign the bytes of 'a', if 'a' is more than 16bits they don't recompose to 'a'
+                let a_bytes: Vec<F> = decompose_bigInt_to_ubits(&value_f_to_big_uint(a), 2, 8);
+                for (i, byte) in a_bytes.iter().enumerate() {
+                    region.assign_advice(
+                        || format!("a byte {}", i),
+                        self.config.a_bytes[i],
+                        1,
+                        || Value::known(*byte),
+                    )?;
+                }
+
                 // combine accumulated value and new
                 let mut sum = F::zero();
                 a.as_ref().map(|f| sum = sum.add(f));
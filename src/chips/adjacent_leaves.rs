@@ -0,0 +1,128 @@
+use super::merkle_sum_tree::{MerkleSumTreeChip, MerkleSumTreeConfig, L, RATE, WIDTH};
+use super::poseidon::hash::PoseidonChip;
+use super::poseidon::spec::MySpec;
+use super::range_check::RangeCheckChip;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+// The chip proves that two leaves are the left and right children of the same parent node of a Merkle Sum Tree.
+// The parent is computed directly from the two leaf cells, so that the left leaf is always hashed as left child
+// and the right leaf as right child. The parent is then carried up to the root via `merkle_prove_layer`,
+// meaning that the two leaves share every path element above the lowest level.
+#[derive(Debug, Clone)]
+pub struct AdjacentLeavesChip<F: Field> {
+    config: MerkleSumTreeConfig<F>,
+}
+
+impl<F: Field> AdjacentLeavesChip<F> {
+    pub fn construct(config: MerkleSumTreeConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        instance: Column<Instance>,
+    ) -> MerkleSumTreeConfig<F> {
+        MerkleSumTreeChip::configure(meta, advice, instance)
+    }
+
+    // `path_element_hashes`, `path_element_balances` and `path_indices` describe the shared path starting from the parent level.
+    // Returns the root hash and the root balance cells
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_adjacent_leaves(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left_hash: &AssignedCell<F, F>,
+        left_balance: &AssignedCell<F, F>,
+        right_hash: &AssignedCell<F, F>,
+        right_balance: &AssignedCell<F, F>,
+        path_element_hashes: &[F],
+        path_element_balances: &[F],
+        path_indices: &[F],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let range_check_chip = RangeCheckChip::construct(self.config.range_check_config.clone());
+
+        let (left_hash, left_balance, right_hash, right_balance, parent_balance) = layouter
+            .assign_region(
+                || "adjacent leaves parent",
+                |mut region| {
+                    // the sum gate enforces left_balance + right_balance = parent_balance
+                    self.config.sum_selector.enable(&mut region, 0)?;
+
+                    let l1 = left_hash.copy_advice(
+                        || "copy left leaf hash",
+                        &mut region,
+                        self.config.advice[0],
+                        0,
+                    )?;
+                    let l2 = left_balance.copy_advice(
+                        || "copy left leaf balance",
+                        &mut region,
+                        self.config.advice[1],
+                        0,
+                    )?;
+                    let r1 = right_hash.copy_advice(
+                        || "copy right leaf hash",
+                        &mut region,
+                        self.config.advice[2],
+                        0,
+                    )?;
+                    let r2 = right_balance.copy_advice(
+                        || "copy right leaf balance",
+                        &mut region,
+                        self.config.advice[3],
+                        0,
+                    )?;
+
+                    let parent_balance = region.assign_advice(
+                        || "assign sum of left and right balance",
+                        self.config.advice[4],
+                        0,
+                        || l2.value().zip(r2.value()).map(|(a, b)| *a + b),
+                    )?;
+
+                    // the parent balance is range checked as every computed sum of the upper levels
+                    range_check_chip.assign(&mut region, 0, parent_balance.value().copied())?;
+
+                    Ok((l1, l2, r1, r2, parent_balance))
+                },
+            )?;
+
+        let poseidon_chip = PoseidonChip::<F, MySpec<F, WIDTH, RATE>, WIDTH, RATE, L>::construct(
+            self.config.poseidon_config.clone(),
+        );
+
+        let parent_hash = poseidon_chip.hash(
+            layouter.namespace(|| "hash adjacent leaves"),
+            [left_hash, left_balance, right_hash, right_balance],
+        )?;
+
+        // from the parent level the path is the same for both leaves
+        let merkle_chip = MerkleSumTreeChip::construct(self.config.clone());
+        let (mut next_hash, mut next_sum) = (parent_hash, parent_balance);
+        for i in 0..path_element_hashes.len() {
+            (next_hash, next_sum) = merkle_chip.merkle_prove_layer(
+                layouter.namespace(|| format!("level {} merkle proof", i + 1)),
+                i + 1,
+                &next_hash,
+                &next_sum,
+                path_element_hashes[i],
+                path_element_balances[i],
+                path_indices[i],
+            )?;
+        }
+
+        Ok((next_hash, next_sum))
+    }
+
+    // Enforce permutation check between input cell and instance column at row passed as input
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
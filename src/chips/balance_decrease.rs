@@ -0,0 +1,185 @@
+use eth_types::Field;
+use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
+
+use super::commitment::{CommitmentChip, CommitmentConfig};
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+// Proves that a balance decreased between two committed states, without revealing the balances:
+// commit_old = Poseidon(old_balance, blind_old), commit_new = Poseidon(new_balance, blind_new) and
+// new_balance < old_balance. Both balances are range checked to be less than 2^MAX_BITS before being committed,
+// otherwise a balance out of the N_BYTES range of LtChip would make the diff wrap, so MAX_BITS must not be
+// greater than 8 * N_BYTES. The balance cells are then copied into two consecutive rows of the value column
+// to be compared by LtChip.
+//
+// | value       | blinding  | bytes (RangeCheckChip) | lt diff (LtChip) | lt_selector | poseidon (CommitmentChip) |
+// | --          | --        | --                     | --               | --          | --                        |
+// | old_balance | blind_old | b_0, b_1, ...          |                  | 0           |                           |
+// |             |           |                        |                  |             | Poseidon(old, blind_old)  |
+// | new_balance | blind_new | b_0, b_1, ...          |                  | 0           |                           |
+// |             |           |                        |                  |             | Poseidon(new, blind_new)  |
+// | new_balance |           |                        | ...              | 1           |                           |
+// | old_balance |           |                        |                  | 0           |                           |
+#[derive(Debug, Clone)]
+pub struct BalanceDecreaseConfig<F: Field, const MAX_BITS: usize = 64, const N_BYTES: usize = 8> {
+    pub commitment_config: CommitmentConfig<F>,
+    pub range_check_config: RangeCheckConfig<F, MAX_BITS>,
+    pub lt_config: LtConfig<F, N_BYTES>,
+    pub lt_selector: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BalanceDecreaseChip<F: Field, const MAX_BITS: usize = 64, const N_BYTES: usize = 8> {
+    config: BalanceDecreaseConfig<F, MAX_BITS, N_BYTES>,
+}
+
+impl<F: Field, const MAX_BITS: usize, const N_BYTES: usize>
+    BalanceDecreaseChip<F, MAX_BITS, N_BYTES>
+{
+    pub fn construct(config: BalanceDecreaseConfig<F, MAX_BITS, N_BYTES>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        blinding: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> BalanceDecreaseConfig<F, MAX_BITS, N_BYTES> {
+        assert!(
+            MAX_BITS <= 8 * N_BYTES,
+            "the balances of MAX_BITS = {} don't fit into the N_BYTES = {} of LtChip",
+            MAX_BITS,
+            N_BYTES
+        );
+
+        // the value column is the first input of the hash and the column range checked by RangeCheckChip
+        let commitment_config = CommitmentChip::configure(meta, [value, blinding], instance);
+        let range_check_config = RangeCheckChip::<F, MAX_BITS>::configure(meta, value);
+
+        let lt_selector = meta.selector();
+
+        // the new balance is on the current row and the old balance on the next one
+        let lt_config = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(lt_selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            |meta| meta.query_advice(value, Rotation::next()),
+        );
+
+        meta.create_gate("new balance less than old balance", |meta| {
+            let s = meta.query_selector(lt_selector);
+            vec![s * (lt_config.is_lt(meta, None) - Expression::Constant(F::one()))]
+        });
+
+        BalanceDecreaseConfig {
+            commitment_config,
+            range_check_config,
+            lt_config,
+            lt_selector,
+            instance,
+        }
+    }
+
+    // Load the u8 tables of RangeCheckChip and LtChip
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        RangeCheckChip::construct(self.config.range_check_config.clone()).load(layouter)?;
+        LtChip::construct(self.config.lt_config).load(layouter)
+    }
+
+    // Range checks the balance and returns the balance cell and the cell containing Poseidon(balance, blinding)
+    fn commit(
+        &self,
+        mut layouter: impl Layouter<F>,
+        balance: Value<F>,
+        blinding: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let commitment_chip = CommitmentChip::construct(self.config.commitment_config.clone());
+        let range_check_chip = RangeCheckChip::construct(self.config.range_check_config.clone());
+
+        let (balance_cell, blinding_cell) = layouter.assign_region(
+            || "assign balance and blinding",
+            |mut region| {
+                let balance_cell = region.assign_advice(
+                    || "balance",
+                    self.config.commitment_config.advice[0],
+                    0,
+                    || balance,
+                )?;
+                let blinding_cell = region.assign_advice(
+                    || "blinding",
+                    self.config.commitment_config.advice[1],
+                    0,
+                    || blinding,
+                )?;
+
+                range_check_chip.assign(&mut region, 0, balance)?;
+
+                Ok((balance_cell, blinding_cell))
+            },
+        )?;
+
+        let commitment = commitment_chip.commit(
+            layouter.namespace(|| "commit"),
+            &balance_cell,
+            &blinding_cell,
+        )?;
+
+        Ok((balance_cell, commitment))
+    }
+
+    // Commits to the old and the new balance and enforces the new balance to be strictly less than the old one.
+    // Returns the cells of the old and of the new commitment
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        old_balance: Value<F>,
+        old_blinding: Value<F>,
+        new_balance: Value<F>,
+        new_blinding: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let (old_balance_cell, old_commitment) = self.commit(
+            layouter.namespace(|| "commit old balance"),
+            old_balance,
+            old_blinding,
+        )?;
+        let (new_balance_cell, new_commitment) = self.commit(
+            layouter.namespace(|| "commit new balance"),
+            new_balance,
+            new_blinding,
+        )?;
+
+        let lt_chip = LtChip::construct(self.config.lt_config);
+
+        layouter.assign_region(
+            || "enforce new balance to be less than old balance",
+            |mut region| {
+                let value = self.config.commitment_config.advice[0];
+                let new_balance_cell =
+                    new_balance_cell.copy_advice(|| "copy new balance", &mut region, value, 0)?;
+                let old_balance_cell =
+                    old_balance_cell.copy_advice(|| "copy old balance", &mut region, value, 1)?;
+
+                self.config.lt_selector.enable(&mut region, 0)?;
+
+                let (mut lhs, mut rhs) = (F::zero(), F::zero());
+                new_balance_cell.value().map(|v| lhs = *v);
+                old_balance_cell.value().map(|v| rhs = *v);
+                lt_chip.assign(&mut region, 0, lhs, rhs)
+            },
+        )?;
+
+        Ok((old_commitment, new_commitment))
+    }
+
+    // Enforce permutation check between input cell and instance column at row passed as input
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
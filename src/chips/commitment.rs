@@ -0,0 +1,113 @@
+use super::poseidon::hash::{PoseidonChip, PoseidonConfig};
+use super::poseidon::spec::MySpec;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+const L: usize = 2;
+
+// Hiding commitment to a value built on top of the Poseidon hash: commit = Poseidon(value, blinding)
+// Without knowing the blinding factor, the commitment doesn't reveal anything about the committed value
+#[derive(Debug, Clone)]
+pub struct CommitmentConfig<F: Field> {
+    pub advice: [Column<Advice>; 2],
+    pub instance: Column<Instance>,
+    pub poseidon_config: PoseidonConfig<F, WIDTH, RATE, L>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitmentChip<F: Field> {
+    config: CommitmentConfig<F>,
+}
+
+impl<F: Field> CommitmentChip<F> {
+    pub fn construct(config: CommitmentConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> CommitmentConfig<F> {
+        // enable equality for copying value and blinding into the poseidon chip and for the commitment check
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        let hash_inputs = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+
+        let poseidon_config =
+            PoseidonChip::<F, MySpec<F, WIDTH, RATE>, WIDTH, RATE, L>::configure(meta, hash_inputs);
+
+        CommitmentConfig {
+            advice,
+            instance,
+            poseidon_config,
+        }
+    }
+
+    // Returns the value and blinding cells
+    pub fn assign_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        blinding: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "assign value and blinding",
+            |mut region| {
+                let value_cell =
+                    region.assign_advice(|| "value", self.config.advice[0], 0, || value)?;
+                let blinding_cell =
+                    region.assign_advice(|| "blinding", self.config.advice[1], 0, || blinding)?;
+
+                Ok((value_cell, blinding_cell))
+            },
+        )
+    }
+
+    // Returns the cell containing Poseidon(value, blinding)
+    pub fn commit(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value_cell: &AssignedCell<F, F>,
+        blinding_cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let poseidon_chip = PoseidonChip::<F, MySpec<F, WIDTH, RATE>, WIDTH, RATE, L>::construct(
+            self.config.poseidon_config.clone(),
+        );
+
+        poseidon_chip.hash(
+            layouter.namespace(|| "hash value and blinding"),
+            [value_cell.clone(), blinding_cell.clone()],
+        )
+    }
+
+    // Re-computes the commitment from value and blinding and constrains it to be equal to the claimed commitment
+    pub fn open(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value_cell: &AssignedCell<F, F>,
+        blinding_cell: &AssignedCell<F, F>,
+        commitment_cell: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let digest = self.commit(layouter.namespace(|| "commit"), value_cell, blinding_cell)?;
+
+        layouter.assign_region(
+            || "open commitment",
+            |mut region| region.constrain_equal(digest.cell(), commitment_cell.cell()),
+        )
+    }
+
+    // Enforce permutation check between input cell and instance column at row passed as input
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
@@ -0,0 +1,149 @@
+use arrayvec::ArrayVec;
+use eth_types::Field;
+
+use super::commitment::{CommitmentChip, CommitmentConfig};
+use super::safe_accumulator::{SafeACcumulatorChip, SafeAccumulatorConfig};
+use halo2_proofs::{circuit::*, plonk::*};
+
+// Proves that the openings of N commitments sum up to a total, without revealing the committed values.
+// Every (value, blinding) pair is committed by `CommitmentChip`, and the same value cell is accumulated by
+// `SafeACcumulatorChip`, so an accumulation overflowing MAX_BITS * (ACC_COLS - 1) bits fails.
+// The accumulation starts from zero cells taken from the constant column, every update copies the accumulate
+// cells of the previous one and its carries are constrained to be 0 or 1 by the bool gate of the accumulator,
+// so the total is bound to the committed values
+//
+// | value | blinding | accumulator (SafeACcumulatorChip) | poseidon (CommitmentChip) |
+// | --    | --       | --                                | --                        |
+// | v_i   | b_i      |                                   |                           |
+// |       |          |                                   | Poseidon(v_i, b_i)        |
+// |       |          | acc_i = acc_(i-1) + v_i           |                           |
+#[derive(Debug, Clone)]
+pub struct CommittedSumConfig<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
+    pub commitment_config: CommitmentConfig<F>,
+    pub accumulator_config: SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F>,
+    pub constant: Column<Fixed>,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommittedSumChip<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
+    config: CommittedSumConfig<MAX_BITS, ACC_COLS, F>,
+}
+
+impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> CommittedSumChip<MAX_BITS, ACC_COLS, F> {
+    pub fn construct(config: CommittedSumConfig<MAX_BITS, ACC_COLS, F>) -> Self {
+        Self { config }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        blinding: Column<Advice>,
+        update_value: Column<Advice>,
+        left_most_inv: Column<Advice>,
+        add_carries: [Column<Advice>; ACC_COLS],
+        accumulate: [Column<Advice>; ACC_COLS],
+        constant: Column<Fixed>,
+        instance: Column<Instance>,
+    ) -> CommittedSumConfig<MAX_BITS, ACC_COLS, F> {
+        let commitment_config = CommitmentChip::configure(meta, [value, blinding], instance);
+
+        let selectors = [(); 3].map(|_| meta.selector());
+        let accumulator_config = SafeACcumulatorChip::configure(
+            meta,
+            update_value,
+            left_most_inv,
+            add_carries,
+            accumulate,
+            selectors,
+            instance,
+        );
+
+        // the accumulation starts from zero cells copied from the constant column
+        meta.enable_constant(constant);
+
+        CommittedSumConfig {
+            commitment_config,
+            accumulator_config,
+            constant,
+            instance,
+        }
+    }
+
+    // Load the range check table of the accumulator
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        SafeACcumulatorChip::construct(self.config.accumulator_config.clone()).load(layouter)
+    }
+
+    // Commits to every (value, blinding) pair and accumulates the values.
+    // Returns the commitment cells, in the order of the openings, and the cell of the recomposed total
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        openings: &[(Value<F>, Value<F>)],
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
+        if openings.is_empty() {
+            return Err(Error::Synthesis);
+        }
+
+        let commitment_chip = CommitmentChip::construct(self.config.commitment_config.clone());
+        let accumulator_chip =
+            SafeACcumulatorChip::construct(self.config.accumulator_config.clone());
+
+        // little-endian like the accumulate cells returned by the accumulator
+        let mut accumulate_cells = layouter.assign_region(
+            || "zero accumulation",
+            |mut region| {
+                let mut cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
+                for i in 0..ACC_COLS {
+                    cells.push(region.assign_advice_from_constant(
+                        || format!("zero accumulate[{}]", ACC_COLS - 1 - i),
+                        self.config.accumulator_config.accumulate[ACC_COLS - 1 - i],
+                        0,
+                        F::zero(),
+                    )?);
+                }
+                Ok(cells)
+            },
+        )?;
+
+        let mut commitments = vec![];
+        for (i, (value, blinding)) in openings.iter().enumerate() {
+            let (value_cell, blinding_cell) = commitment_chip.assign_private(
+                layouter.namespace(|| format!("assign opening {}", i)),
+                *value,
+                *blinding,
+            )?;
+
+            commitments.push(commitment_chip.commit(
+                layouter.namespace(|| format!("commit value {}", i)),
+                &value_cell,
+                &blinding_cell,
+            )?);
+
+            (accumulate_cells, _) = accumulator_chip.assign_from_cell_chained(
+                layouter.namespace(|| format!("accumulate value {}", i)),
+                0,
+                &value_cell,
+                &accumulate_cells,
+            )?;
+        }
+
+        let total = accumulator_chip
+            .recompose_accumulator(layouter.namespace(|| "recompose total"), &accumulate_cells)?;
+
+        Ok((commitments, total))
+    }
+
+    // Enforce permutation check between input cell and instance column at row passed as input
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
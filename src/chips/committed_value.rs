@@ -0,0 +1,128 @@
+use eth_types::Field;
+
+use super::commitment::{CommitmentChip, CommitmentConfig};
+use super::overflow_check_v2::{OverflowCheckV2Config, OverflowChipV2};
+use super::utils::f_to_big_uint;
+use halo2_proofs::{circuit::*, plonk::*};
+
+// Proves "I committed to a value that fits in MAX_BITS * ACC_COLS bits".
+// The value is decomposed and range checked by `OverflowChipV2`, then the same value cell is hashed together
+// with a blinding factor by `CommitmentChip`, so the commitment is bound to the range checked value.
+//
+// | value | blinding | limbs and chunks (OverflowChipV2) | poseidon (CommitmentChip) |
+// | --    | --       | --                                | --                        |
+// | v     |          | l_0, l_1, ...                     |                           |
+// |       | b        |                                   |                           |
+// |       |          |                                   | Poseidon(v, b)            |
+#[derive(Debug, Clone)]
+pub struct CommittedValueConfig<
+    const MAX_BITS: u8,
+    const ACC_COLS: usize,
+    const TABLE_BITS: u8,
+    F: Field,
+> {
+    pub overflow_config: OverflowCheckV2Config<MAX_BITS, ACC_COLS, TABLE_BITS>,
+    pub commitment_config: CommitmentConfig<F>,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommittedValueChip<
+    const MAX_BITS: u8,
+    const ACC_COLS: usize,
+    const TABLE_BITS: u8,
+    F: Field,
+> {
+    config: CommittedValueConfig<MAX_BITS, ACC_COLS, TABLE_BITS, F>,
+}
+
+impl<const MAX_BITS: u8, const ACC_COLS: usize, const TABLE_BITS: u8, F: Field>
+    CommittedValueChip<MAX_BITS, ACC_COLS, TABLE_BITS, F>
+{
+    pub fn construct(config: CommittedValueConfig<MAX_BITS, ACC_COLS, TABLE_BITS, F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        blinding: Column<Advice>,
+        decomposed_values: [Column<Advice>; ACC_COLS],
+        range: Column<Fixed>,
+        instance: Column<Instance>,
+    ) -> CommittedValueConfig<MAX_BITS, ACC_COLS, TABLE_BITS, F> {
+        let selector = meta.selector();
+        let overflow_config = OverflowChipV2::<MAX_BITS, ACC_COLS, TABLE_BITS, F>::configure(
+            meta,
+            value,
+            decomposed_values,
+            range,
+            instance,
+            selector,
+        );
+
+        // the value cell of the decomposition is the first input of the hash
+        let commitment_config = CommitmentChip::configure(meta, [value, blinding], instance);
+
+        CommittedValueConfig {
+            overflow_config,
+            commitment_config,
+            instance,
+        }
+    }
+
+    // Load the range check table of the decomposition
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        OverflowChipV2::<MAX_BITS, ACC_COLS, TABLE_BITS, F>::construct(
+            self.config.overflow_config.clone(),
+        )
+        .load(layouter)
+    }
+
+    // Range checks the value and returns the cell containing Poseidon(value, blinding).
+    // A value that doesn't fit into MAX_BITS * ACC_COLS bits fails with `Error::Synthesis` before anything
+    // is committed, the decomposition gates reject it anyway if the witness is built by other means
+    pub fn commit(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        blinding: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let max_bits = MAX_BITS as u64 * ACC_COLS as u64;
+        value.error_if_known_and(|v| f_to_big_uint(v).bits() > max_bits)?;
+
+        let overflow_chip = OverflowChipV2::<MAX_BITS, ACC_COLS, TABLE_BITS, F>::construct(
+            self.config.overflow_config.clone(),
+        );
+        let (value_cell, _) =
+            overflow_chip.assign(layouter.namespace(|| "range check value"), value)?;
+
+        let blinding_cell = layouter.assign_region(
+            || "assign blinding",
+            |mut region| {
+                region.assign_advice(
+                    || "blinding",
+                    self.config.commitment_config.advice[1],
+                    0,
+                    || blinding,
+                )
+            },
+        )?;
+
+        CommitmentChip::construct(self.config.commitment_config.clone()).commit(
+            layouter.namespace(|| "commit"),
+            &value_cell,
+            &blinding_cell,
+        )
+    }
+
+    // Enforce permutation check between input cell and instance column at row passed as input
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
@@ -0,0 +1,285 @@
+// Constraints shared by several chips
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+// Returns x * (1 - x), which is 0 if and only if x is either 0 or 1
+pub fn assert_boolean<F: Field>(x: Expression<F>) -> Expression<F> {
+    x.clone() * (Expression::Constant(F::one()) - x)
+}
+
+// Enforces that the value of `column` is either a 0 or 1 when the selector is enabled
+// s * x * (1 - x) = 0
+pub fn bool_gate<F: Field>(
+    meta: &mut ConstraintSystem<F>,
+    selector: Selector,
+    column: Column<Advice>,
+) {
+    meta.create_gate("bool constraint", |meta| {
+        let s = meta.query_selector(selector);
+        let x = meta.query_advice(column, Rotation::cur());
+        vec![s * assert_boolean(x)]
+    });
+}
+
+// Returns the constraint that (left_out, right_out) is equal to (left_in, right_in) if the bit is 0,
+// and to (right_in, left_in) if the bit is 1. The bit and the inputs are queried at the current row,
+// the outputs at the next row. The bit is expected to be constrained by `bool_gate`.
+// s * (bit * 2 * (right_in - left_in) - (left_out - left_in) - (right_in - right_out)) = 0
+pub fn conditional_swap<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    selector: Selector,
+    bit: Column<Advice>,
+    left_in: Column<Advice>,
+    right_in: Column<Advice>,
+    left_out: Column<Advice>,
+    right_out: Column<Advice>,
+) -> Expression<F> {
+    let s = meta.query_selector(selector);
+    let bit = meta.query_advice(bit, Rotation::cur());
+    let left_in = meta.query_advice(left_in, Rotation::cur());
+    let right_in = meta.query_advice(right_in, Rotation::cur());
+    let left_out = meta.query_advice(left_out, Rotation::next());
+    let right_out = meta.query_advice(right_out, Rotation::next());
+
+    s * (bit * Expression::Constant(F::from(2)) * (right_in.clone() - left_in.clone())
+        - (left_out - left_in)
+        - (right_in - right_out))
+}
+
+// Same as `conditional_swap` for nodes made of several elements, e.g. the hash and the balance of a merkle sum
+// tree node. The i-th elements of the left and right nodes are swapped by the same bit, one constraint each
+pub fn conditional_swap_many<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    selector: Selector,
+    bit: Column<Advice>,
+    left_in: &[Column<Advice>],
+    right_in: &[Column<Advice>],
+    left_out: &[Column<Advice>],
+    right_out: &[Column<Advice>],
+) -> Vec<Expression<F>> {
+    assert!(
+        right_in.len() == left_in.len()
+            && left_out.len() == left_in.len()
+            && right_out.len() == left_in.len(),
+        "conditional swap requires the same number of elements in every node"
+    );
+
+    (0..left_in.len())
+        .map(|i| {
+            conditional_swap(
+                meta,
+                selector,
+                bit,
+                left_in[i],
+                right_in[i],
+                left_out[i],
+                right_out[i],
+            )
+        })
+        .collect()
+}
+
+// Packs little-endian limbs of `max_bits` bits into a single cell, e.g. to hash a balance decomposed by the overflow chips.
+// The limbs are copied from the most significant one and accumulated as acc = prev_acc * 2^max_bits + limb,
+// the shift is a fixed column so that `max_bits` doesn't have to be known when configuring the circuit.
+//
+// | limb      | acc                       | shift      | selector |
+// | --        | --                        | --         | --       |
+// |           | 0                         |            | 0        |
+// | limb_1    | limb_1                    | 2^max_bits | 1        |
+// | limb_0    | limb_1 * 2^max_bits + l_0 | 2^max_bits | 1        |
+#[derive(Debug, Clone)]
+pub struct PackLimbsConfig {
+    pub limb: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub shift: Column<Fixed>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct PackLimbsChip<F: Field> {
+    config: PackLimbsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> PackLimbsChip<F> {
+    pub fn construct(config: PackLimbsConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        limb: Column<Advice>,
+        acc: Column<Advice>,
+        shift: Column<Fixed>,
+    ) -> PackLimbsConfig {
+        let selector = meta.selector();
+
+        // the limbs are copied, the packed value is copied out, the initial accumulator is a constant
+        meta.enable_equality(limb);
+        meta.enable_equality(acc);
+        meta.enable_constant(shift);
+
+        // acc = prev_acc * shift + limb
+        meta.create_gate("pack limbs", |meta| {
+            let s = meta.query_selector(selector);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let prev_acc = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let shift = meta.query_fixed(shift, Rotation::cur());
+
+            vec![s * (acc - (prev_acc * shift + limb))]
+        });
+
+        PackLimbsConfig {
+            limb,
+            acc,
+            shift,
+            selector,
+        }
+    }
+
+    // Returns the cell containing limb_0 + limb_1 * 2^max_bits + ... + limb_n * 2^(max_bits * n)
+    pub fn pack_limbs_to_cell(
+        &self,
+        mut layouter: impl Layouter<F>,
+        limb_cells: &[AssignedCell<F, F>],
+        max_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let shift = F::from(2).pow_vartime([max_bits as u64]);
+
+        layouter.assign_region(
+            || "pack limbs",
+            |mut region| {
+                let mut acc = region.assign_advice_from_constant(
+                    || "initial acc",
+                    self.config.acc,
+                    0,
+                    F::zero(),
+                )?;
+
+                for (row, limb_cell) in limb_cells.iter().rev().enumerate().map(|(i, c)| (i + 1, c))
+                {
+                    self.config.selector.enable(&mut region, row)?;
+
+                    limb_cell.copy_advice(|| "copy limb", &mut region, self.config.limb, row)?;
+                    region.assign_fixed(
+                        || "shift",
+                        self.config.shift,
+                        row,
+                        || Value::known(shift),
+                    )?;
+
+                    let value =
+                        acc.value().copied() * Value::known(shift) + limb_cell.value().copied();
+                    acc = region.assign_advice(|| "acc", self.config.acc, row, || value)?;
+                }
+
+                Ok(acc)
+            },
+        )
+    }
+}
+
+// Selects one of two cells, out = cond ? a : b, where cond is a boolean cell.
+// The inputs are copied from previous regions, so the chip can be shared by the chips that pick a value by a bit
+//
+// | cond | a | b | out | selector |
+// | --   | --| --| --  | --       |
+// | 1    | 5 | 7 | 5   | 1        |
+#[derive(Debug, Clone)]
+pub struct MuxConfig {
+    pub cond: Column<Advice>,
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct MuxChip<F: Field> {
+    config: MuxConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MuxChip<F> {
+    pub fn construct(config: MuxConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        cond: Column<Advice>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        out: Column<Advice>,
+    ) -> MuxConfig {
+        let selector = meta.selector();
+
+        meta.enable_equality(cond);
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(out);
+
+        // cond * (1 - cond) = 0
+        // out - (cond * a + (1 - cond) * b) = 0
+        meta.create_gate("mux", |meta| {
+            let s = meta.query_selector(selector);
+            let cond = meta.query_advice(cond, Rotation::cur());
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+
+            vec![
+                s.clone() * assert_boolean(cond.clone()),
+                s * (out - (cond.clone() * a + (one - cond) * b)),
+            ]
+        });
+
+        MuxConfig {
+            cond,
+            a,
+            b,
+            out,
+            selector,
+        }
+    }
+
+    // Returns the cell containing a if cond is 1, b if cond is 0
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: &AssignedCell<F, F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "mux",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                cond.copy_advice(|| "copy cond", &mut region, self.config.cond, 0)?;
+                a.copy_advice(|| "copy a", &mut region, self.config.a, 0)?;
+                b.copy_advice(|| "copy b", &mut region, self.config.b, 0)?;
+
+                let out = cond
+                    .value()
+                    .zip(a.value().zip(b.value()))
+                    .map(|(cond, (a, b))| if *cond == F::one() { *a } else { *b });
+
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+}
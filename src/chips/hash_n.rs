@@ -0,0 +1,116 @@
+use std::marker::PhantomData;
+
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+// Generalization of `Hash2Chip` to N inputs, the dummy hash is the sum of the inputs
+#[derive(Debug, Clone)]
+pub struct HashNConfig<const N: usize> {
+    pub inputs: [Column<Advice>; N],
+    pub output: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct HashNChip<F: Field, const N: usize> {
+    config: HashNConfig<N>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const N: usize> HashNChip<F, N> {
+    pub fn construct(config: HashNConfig<N>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        inputs: [Column<Advice>; N],
+        output: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> HashNConfig<N> {
+        // create check selector
+        let hash_selector = meta.selector();
+
+        // Enable equality on the advice and instance column to enable permutation check
+        for input in inputs {
+            meta.enable_equality(input);
+        }
+        meta.enable_equality(output);
+        meta.enable_equality(instance);
+
+        // enforce dummy hash function by creating a custom gate
+        meta.create_gate("hash constraint", |meta| {
+            // enforce sum(inputs) = output, namely sum(inputs) - output = 0
+            let s = meta.query_selector(hash_selector);
+            let sum = inputs
+                .iter()
+                .fold(Expression::Constant(F::zero()), |acc, input| {
+                    acc + meta.query_advice(*input, Rotation::cur())
+                });
+            let output = meta.query_advice(output, Rotation::cur());
+
+            vec![s * (sum - output)]
+        });
+
+        HashNConfig {
+            inputs,
+            output,
+            instance,
+            selector: hash_selector,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| {
+                region.assign_advice(|| "private input", self.config.inputs[0], 0, || input)
+            },
+        )
+    }
+
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input_cells: [AssignedCell<F, F>; N],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "hash row",
+            |mut region| {
+                // enable hash selector
+                self.config.selector.enable(&mut region, 0)?;
+
+                let mut sum = Value::known(F::zero());
+                for (i, cell) in input_cells.iter().enumerate() {
+                    cell.copy_advice(
+                        || format!("input_{}", i),
+                        &mut region,
+                        self.config.inputs[i],
+                        0,
+                    )?;
+                    sum = sum + cell.value().copied();
+                }
+
+                region.assign_advice(|| "output", self.config.output, 0, || sum)
+            },
+        )
+    }
+
+    // Enforce permutation check between output cell and instance column
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
 #[derive(Debug, Clone)]
 pub struct Hash1Config {
@@ -10,12 +11,12 @@ pub struct Hash1Config {
 }
 
 #[derive(Debug, Clone)]
-pub struct Hash1Chip<F: FieldExt> {
+pub struct Hash1Chip<F: Field> {
     config: Hash1Config,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt> Hash1Chip<F> {
+impl<F: Field> Hash1Chip<F> {
     pub fn construct(config: Hash1Config) -> Self {
         Self {
             config,
@@ -82,6 +83,36 @@ impl<F: FieldExt> Hash1Chip<F> {
         )
     }
 
+    // Assigns one row per value in a single region, the hash selector is enabled on each row.
+    // Returns the b cells in the same order as the values
+    pub fn assign_rows(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "advice rows",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, a)| {
+                        self.config.selector.enable(&mut region, offset)?;
+
+                        region.assign_advice(|| "a", self.config.advice[0], offset, || *a)?;
+
+                        region.assign_advice(
+                            || "b",
+                            self.config.advice[1],
+                            offset,
+                            || *a * Value::known(F::from(2)),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
     // Enforce permutation check between b cell and instance column
     pub fn expose_public(
         &self,
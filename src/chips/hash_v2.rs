@@ -1,6 +1,9 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+use super::utils::SecretValue;
 
 #[derive(Debug, Clone)]
 pub struct Hash2Config {
@@ -10,12 +13,12 @@ pub struct Hash2Config {
 }
 
 #[derive(Debug, Clone)]
-pub struct Hash2Chip<F: FieldExt> {
+pub struct Hash2Chip<F: Field> {
     config: Hash2Config,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt> Hash2Chip<F> {
+impl<F: Field> Hash2Chip<F> {
     pub fn construct(config: Hash2Config) -> Self {
         Self {
             config,
@@ -73,6 +76,31 @@ impl<F: FieldExt> Hash2Chip<F> {
         )
     }
 
+    // Same as `load_private`, but the secret is zeroized as soon as it is assigned.
+    // It doesn't protect the value already copied into the assignment, it only reduces the lingering copies.
+    pub fn load_private_zeroizing(
+        &self,
+        mut layouter: impl Layouter<F>,
+        secret: SecretValue<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let cell = layouter.assign_region(
+            || "load private zeroizing",
+            |mut region| {
+                region.assign_advice(
+                    || "private input",
+                    self.config.advice[0],
+                    0,
+                    || secret.value(),
+                )
+            },
+        );
+
+        // the secret is zeroized on drop
+        drop(secret);
+
+        cell
+    }
+
     pub fn hash(
         &self,
         mut layouter: impl Layouter<F>,
@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Debug, Clone)]
 pub struct InclusionCheckConfig {
@@ -8,12 +9,12 @@ pub struct InclusionCheckConfig {
     pub instance: Column<Instance>,
 }
 #[derive(Debug, Clone)]
-pub struct InclusionCheckChip<F: FieldExt> {
+pub struct InclusionCheckChip<F: Field> {
     config: InclusionCheckConfig,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt> InclusionCheckChip<F> {
+impl<F: Field> InclusionCheckChip<F> {
     pub fn construct(config: InclusionCheckConfig) -> Self {
         Self {
             config,
@@ -87,15 +88,37 @@ impl<F: FieldExt> InclusionCheckChip<F> {
     }
 
     pub fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        public_username_cell: &AssignedCell<F, F>,
+        public_balance_cell: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        // username at row 0 and balance at row 1 of the instance column
+        self.expose_public_at(layouter, public_username_cell, public_balance_cell, 0, 1)
+    }
+
+    // Same as `expose_public` with the rows of the instance column passed as input,
+    // so the username and the balance can be composed with other public outputs
+    pub fn expose_public_at(
         &self,
         mut layouter: impl Layouter<F>,
         public_username_cell: &AssignedCell<F, F>,
         public_balance_cell: &AssignedCell<F, F>,
+        username_row: usize,
+        balance_row: usize,
     ) -> Result<(), Error> {
-        // enforce equality between public_username_cell and instance column at row 0
-        layouter.constrain_instance(public_username_cell.cell(), self.config.instance, 0)?;
-        // enforce equality between balance_username_cell and instance column at row 1
-        layouter.constrain_instance(public_balance_cell.cell(), self.config.instance, 1)?;
+        // enforce equality between public_username_cell and instance column at username_row
+        layouter.constrain_instance(
+            public_username_cell.cell(),
+            self.config.instance,
+            username_row,
+        )?;
+        // enforce equality between balance_username_cell and instance column at balance_row
+        layouter.constrain_instance(
+            public_balance_cell.cell(),
+            self.config.instance,
+            balance_row,
+        )?;
 
         Ok(())
     }
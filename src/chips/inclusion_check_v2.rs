@@ -1,26 +1,26 @@
 use std::marker::PhantomData;
 
+use eth_types::Field;
 use halo2_proofs::{
-    arithmetic::FieldExt,
     circuit::*,
-    plonk::{Advice, Column, Fixed, ConstraintSystem, Error, Instance, Selector},
+    plonk::{Advice, Column, Fixed, ConstraintSystem, Error, Expression, Instance, Selector},
     poly::Rotation,
 };
 
 #[derive(Debug, Clone)]
 pub struct InclusionCheckV2Config {
-    pub advice: [Column<Advice>; 4],
+    pub advice: [Column<Advice>; 6],
     pub selector: Selector,
     pub instance: Column<Instance>,
     pub constant: Column<Fixed>,
 }
 #[derive(Debug, Clone)]
-pub struct InclusionCheckV2Chip<F: FieldExt> {
+pub struct InclusionCheckV2Chip<F: Field> {
     config: InclusionCheckV2Config,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt> InclusionCheckV2Chip<F> {
+impl<F: Field> InclusionCheckV2Chip<F> {
     pub fn construct(config: InclusionCheckV2Config) -> Self {
         Self {
             config,
@@ -30,7 +30,7 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
 
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 4],
+        advice: [Column<Advice>; 6],
         instance: Column<Instance>,
         constant: Column<Fixed>,
     ) -> InclusionCheckV2Config {
@@ -38,6 +38,8 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
         let balance_column = advice[1];
         let username_accumulator_column = advice[2];
         let balance_accumulator_column = advice[3];
+        let select_column = advice[4];
+        let found_column = advice[5];
 
         // create check selector
         let selector = meta.selector();
@@ -45,6 +47,7 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
         // Enable equality on the username_accumulator_column and balance_accumulator_column to enable permutation check
         meta.enable_equality(username_accumulator_column);
         meta.enable_equality(balance_accumulator_column);
+        meta.enable_equality(found_column);
 
         // Enable constant column. Api to enable constant column to be used for assignement
         meta.enable_constant(constant);
@@ -52,8 +55,16 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
         // Enable equality on the instance column to enable permutation check
         meta.enable_equality(instance);
 
+        // The selector is enabled on every row of the table, while the explicit bit `select` picks the included row.
+        // If select is 1 the accumulator is set to the value of the current row, otherwise it copies the previous accumulator
+        // select * (acc - value) + (1 - select) * (acc - prev_acc) = 0
+        // Compared to summing the values, two usernames adding up to a third one can't produce a false inclusion.
+        // The found flag is the OR of the select bits, found = prev_found + select - prev_found * select, so an entry
+        // equal to the initial accumulator, e.g. a zero balance, can be told apart from a miss
         meta.create_gate("accumulator constraint", |meta| {
             let s = meta.query_selector(selector);
+            let select = meta.query_advice(select_column, Rotation::cur());
+            let one = Expression::Constant(F::one());
             let username = meta.query_advice(username_column, Rotation::cur());
             let username_accumulator =
                 meta.query_advice(username_accumulator_column, Rotation::cur());
@@ -66,9 +77,20 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
             let prev_balance_accumulator =
                 meta.query_advice(balance_accumulator_column, Rotation::prev());
 
+            let found = meta.query_advice(found_column, Rotation::cur());
+            let prev_found = meta.query_advice(found_column, Rotation::prev());
+
             vec![
-                s.clone() * (username + prev_username_accumulator - username_accumulator),
-                s * (balance + prev_balance_accumulator - balance_accumulator),
+                s.clone() * select.clone() * (one.clone() - select.clone()),
+                s.clone()
+                    * (select.clone() * (username_accumulator.clone() - username)
+                        + (one.clone() - select.clone())
+                            * (username_accumulator - prev_username_accumulator)),
+                s.clone()
+                    * (select.clone() * (balance_accumulator.clone() - balance)
+                        + (one - select.clone())
+                            * (balance_accumulator - prev_balance_accumulator)),
+                s * (found - (prev_found.clone() + select.clone() - prev_found * select)),
             ]
         });
 
@@ -78,6 +100,8 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
                 balance_column,
                 username_accumulator_column,
                 balance_accumulator_column,
+                select_column,
+                found_column,
             ],
             selector,
             instance,
@@ -86,14 +110,31 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
     }
 
     // Assign rows for instance column passing the entry of the users
+    // The select bit is 1 only for the row at inclusion_index
+    // Returns the last rows of the username and balance accumulators and of the found flag
     pub fn assign_rows(
         &self,
-        mut layouter: impl Layouter<F>,
+        layouter: impl Layouter<F>,
         usernames: [Value<F>; 10],
         balances: [Value<F>; 10],
         constant: F,
         inclusion_index: u8,
-    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let mut select_bits: [Value<F>; 10] = [Value::known(F::zero()); 10];
+        select_bits[inclusion_index as usize] = Value::known(F::one());
+
+        self.assign_rows_with_select_bits(layouter, usernames, balances, constant, select_bits)
+    }
+
+    // Assign rows for instance column passing the entry of the users and the select bit of each row
+    pub fn assign_rows_with_select_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        usernames: [Value<F>; 10],
+        balances: [Value<F>; 10],
+        constant: F,
+        select_bits: [Value<F>; 10],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
 
         // For row 0, assign the zero value from constant to the accumulator
         layouter.assign_region(
@@ -101,91 +142,199 @@ impl<F: FieldExt> InclusionCheckV2Chip<F> {
             |mut region| {
 
                 // for the first row, assign the zero value to the accumulator
-                let mut username_acc_cell = region.assign_advice_from_constant(
+                let username_acc_cell = region.assign_advice_from_constant(
                     || "username accumulator init",
                     self.config.advice[2],
                     0,
                     constant,
                 )?;
 
-                let mut balance_acc_cell = region.assign_advice_from_constant(
+                let balance_acc_cell = region.assign_advice_from_constant(
                     || "balance accumulator init",
                     self.config.advice[3],
                     0,
                     constant,
                 )?;
 
+                let found_cell = region.assign_advice_from_constant(
+                    || "found init",
+                    self.config.advice[5],
+                    0,
+                    F::zero(),
+                )?;
+
                 // for the other rows loop over the username and balance arrays and assign the values to the table
-                // if the row is the inclusion index, enable the selector and assign the value to the accumulator
-                // if the row is not the inclusion index, copy the accumulator from the previous row
-                for _i in 0..usernames.len() {
-                    if (_i as u8) == inclusion_index {
-                        self.config.selector.enable(&mut region, _i + 1)?;
-
-                        region.assign_advice(
-                            || "username",
-                            self.config.advice[0],
-                            _i + 1,
-                            || usernames[_i],
-                        )?;
-
-                        region.assign_advice(
-                            || "balance",
-                            self.config.advice[1],
-                            _i + 1,
-                            || balances[_i],
-                        )?;
-
-                        username_acc_cell = region.assign_advice(
-                            || "username accumulator",
-                            self.config.advice[2],
-                            _i + 1,
-                            || usernames[_i],
-                        )?;
-
-                        balance_acc_cell = region.assign_advice(
-                            || "balance accumulator",
-                            self.config.advice[3],
-                            _i + 1,
-                            || balances[_i],
-                        )?;
-
-                    } else {
-                        region.assign_advice(
-                            || "username",
-                            self.config.advice[0],
-                            _i + 1,
-                            || usernames[_i],
-                        )?;
-
-                        region.assign_advice(
-                            || "balance",
-                            self.config.advice[1],
-                            _i + 1,
-                            || balances[_i],
-                        )?;
-
-                        username_acc_cell = username_acc_cell.copy_advice(
-                            || "copy username acc cell from prev row",
-                            &mut region,
-                            self.config.advice[2], 
-                            _i + 1
-                        )?;
-
-                        balance_acc_cell = balance_acc_cell.copy_advice(
-                            || "copy balance acc cell from prev row",
-                            &mut region,
-                            self.config.advice[3], 
-                            _i + 1
-                        )?;
-
-                    }
-                }
-                Ok((username_acc_cell, balance_acc_cell))
+                self.assign_entries(
+                    &mut region,
+                    &usernames,
+                    &balances,
+                    &select_bits,
+                    (username_acc_cell, balance_acc_cell, found_cell),
+                )
             },
         )
     }
 
+    // Assign rows splitting the table across regions of at most chunk_size entries, for tables that don't fit
+    // in the row budget of a single region. Row 0 of every region holds the accumulators and the found flag of
+    // the previous chunk, copied with `copy_advice`, so that the accumulation stays continuous at the chunk
+    // boundaries. The first chunk starts from zero values taken from the constant column.
+    // The select bit is 1 only for the row at inclusion_index.
+    // Returns the last rows of the username and balance accumulators and of the found flag
+    pub fn assign_rows_chunked(
+        &self,
+        mut layouter: impl Layouter<F>,
+        usernames: &[Value<F>],
+        balances: &[Value<F>],
+        chunk_size: usize,
+        inclusion_index: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        if usernames.is_empty()
+            || usernames.len() != balances.len()
+            || chunk_size == 0
+            || inclusion_index >= usernames.len()
+        {
+            return Err(Error::Synthesis);
+        }
+
+        let mut select_bits = vec![Value::known(F::zero()); usernames.len()];
+        select_bits[inclusion_index] = Value::known(F::one());
+
+        let mut last_cells = None;
+
+        for (chunk, ((chunk_usernames, chunk_balances), chunk_select_bits)) in usernames
+            .chunks(chunk_size)
+            .zip(balances.chunks(chunk_size))
+            .zip(select_bits.chunks(chunk_size))
+            .enumerate()
+        {
+            last_cells = Some(layouter.assign_region(
+                || format!("user and balance table chunk {}", chunk),
+                |mut region| {
+                    let init_cells = match &last_cells {
+                        // for the first chunk, assign the zero value to the accumulators and the found flag
+                        None => (
+                            region.assign_advice_from_constant(
+                                || "username accumulator init",
+                                self.config.advice[2],
+                                0,
+                                F::zero(),
+                            )?,
+                            region.assign_advice_from_constant(
+                                || "balance accumulator init",
+                                self.config.advice[3],
+                                0,
+                                F::zero(),
+                            )?,
+                            region.assign_advice_from_constant(
+                                || "found init",
+                                self.config.advice[5],
+                                0,
+                                F::zero(),
+                            )?,
+                        ),
+                        // for the next chunks, copy the last row of the previous one
+                        Some((username_acc_cell, balance_acc_cell, found_cell)) => (
+                            username_acc_cell.copy_advice(
+                                || "copy username accumulator",
+                                &mut region,
+                                self.config.advice[2],
+                                0,
+                            )?,
+                            balance_acc_cell.copy_advice(
+                                || "copy balance accumulator",
+                                &mut region,
+                                self.config.advice[3],
+                                0,
+                            )?,
+                            found_cell.copy_advice(
+                                || "copy found",
+                                &mut region,
+                                self.config.advice[5],
+                                0,
+                            )?,
+                        ),
+                    };
+
+                    self.assign_entries(
+                        &mut region,
+                        chunk_usernames,
+                        chunk_balances,
+                        chunk_select_bits,
+                        init_cells,
+                    )
+                },
+            )?);
+        }
+
+        Ok(last_cells.unwrap())
+    }
+
+    // Assign the entries from row 1 of the region, the row 0 holding the initial accumulators and found flag.
+    // The accumulator takes the value of the row if its select bit is on, otherwise the one of the previous row
+    fn assign_entries(
+        &self,
+        region: &mut Region<'_, F>,
+        usernames: &[Value<F>],
+        balances: &[Value<F>],
+        select_bits: &[Value<F>],
+        init_cells: (AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>),
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let (mut username_acc_cell, mut balance_acc_cell, mut found_cell) = init_cells;
+
+        for _i in 0..usernames.len() {
+            self.config.selector.enable(region, _i + 1)?;
+
+            region.assign_advice(
+                || "username",
+                self.config.advice[0],
+                _i + 1,
+                || usernames[_i],
+            )?;
+
+            region.assign_advice(|| "balance", self.config.advice[1], _i + 1, || balances[_i])?;
+
+            region.assign_advice(
+                || "select bit",
+                self.config.advice[4],
+                _i + 1,
+                || select_bits[_i],
+            )?;
+
+            let username_acc = select_bits[_i]
+                .zip(usernames[_i])
+                .zip(username_acc_cell.value().copied())
+                .map(|((s, value), prev)| s * value + (F::one() - s) * prev);
+
+            let balance_acc = select_bits[_i]
+                .zip(balances[_i])
+                .zip(balance_acc_cell.value().copied())
+                .map(|((s, value), prev)| s * value + (F::one() - s) * prev);
+
+            let found = select_bits[_i]
+                .zip(found_cell.value().copied())
+                .map(|(s, prev)| prev + s - prev * s);
+
+            username_acc_cell = region.assign_advice(
+                || "username accumulator",
+                self.config.advice[2],
+                _i + 1,
+                || username_acc,
+            )?;
+
+            balance_acc_cell = region.assign_advice(
+                || "balance accumulator",
+                self.config.advice[3],
+                _i + 1,
+                || balance_acc,
+            )?;
+
+            found_cell =
+                region.assign_advice(|| "found", self.config.advice[5], _i + 1, || found)?;
+        }
+        Ok((username_acc_cell, balance_acc_cell, found_cell))
+    }
+
     pub fn expose_public(
         &self,
         mut layouter: impl Layouter<F>,
@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
 // take an value in the `input` advice column
 // the goal is to check whether the value is less than target
@@ -14,15 +15,16 @@ pub struct LessThanConfig {
     input: Column<Advice>,
     table: Column<Instance>,
     advice_table: Column<Advice>,
+    found: Column<Advice>,
 }
 
 #[derive(Debug, Clone)]
-pub struct LessThanChip<F: FieldExt> {
+pub struct LessThanChip<F: Field> {
     config: LessThanConfig,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt> LessThanChip<F> {
+impl<F: Field> LessThanChip<F> {
     pub fn construct(config: LessThanConfig) -> Self {
         Self {
             config,
@@ -41,6 +43,12 @@ impl<F: FieldExt> LessThanChip<F> {
         meta.enable_equality(advice_table);
         meta.annotate_lookup_any_column(advice_table, || "Adv-table");
 
+        // the found flag is a constant that can be exposed as public input
+        let found = meta.advice_column();
+        let constant = meta.fixed_column();
+        meta.enable_equality(found);
+        meta.enable_constant(constant);
+
         // Dynamic lookup check
         // TO DO: does it mean that we looking up input inside advice_table?
         meta.lookup_any(
@@ -56,14 +64,17 @@ impl<F: FieldExt> LessThanChip<F> {
             input,
             table,
             advice_table,
+            found,
         }
     }
 
+    // Returns the cell of the found flag. The lookup already enforces that the input is inside the table,
+    // so the flag is the constant 1: the proof only verifies when the input is found
     pub fn assign(
         &self,
         mut layouter: impl Layouter<F>,
         input: Value<F>
-    ) -> Result<(), Error> {
+    ) -> Result<AssignedCell<F, F>, Error> {
         layouter.assign_region(
             || "less than assignment",
             |mut region| {
@@ -82,6 +93,87 @@ impl<F: FieldExt> LessThanChip<F> {
                 // assign input value to input column
                 region.assign_advice(|| "input", self.config.input, 0, || input)?;
 
+                region.assign_advice_from_constant(|| "found", self.config.found, 0, F::one())
+            },
+        )
+    }
+}
+
+// Same check as LessThanChip, but the table containing all the values from 0 to (target-1) is a fixed column,
+// so it is part of the circuit and the instance column doesn't have to contain the whole table.
+// The target is known when configuring the circuit, the table is assigned once with `load`
+#[derive(Debug, Clone)]
+pub struct LessThanFixedConfig {
+    pub input: Column<Advice>,
+    pub table: Column<Fixed>,
+    pub selector: Selector,
+    pub target: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LessThanFixedChip<F: Field> {
+    config: LessThanFixedConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> LessThanFixedChip<F> {
+    pub fn construct(config: LessThanFixedConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        input: Column<Advice>,
+        target: usize,
+    ) -> LessThanFixedConfig {
+        let table = meta.fixed_column();
+        let selector = meta.complex_selector();
+        meta.annotate_lookup_any_column(table, || "Fixed-table");
+
+        // the input is looked up inside the fixed table when the selector is on, 0 otherwise (0 is always in the table)
+        meta.lookup_any("fixed lookup check", |meta| {
+            let s = meta.query_selector(selector);
+            let input = meta.query_advice(input, Rotation::cur());
+            let table = meta.query_fixed(table, Rotation::cur());
+            vec![(s * input, table)]
+        });
+
+        LessThanFixedConfig {
+            input,
+            table,
+            selector,
+            target,
+        }
+    }
+
+    // Load the table with the values from 0 to (target-1)
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "load less than table",
+            |mut region| {
+                for i in 0..self.config.target {
+                    region.assign_fixed(
+                        || "less than table",
+                        self.config.table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub fn assign(&self, mut layouter: impl Layouter<F>, input: Value<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "less than fixed assignment",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "input", self.config.input, 0, || input)?;
+
                 Ok(())
             },
         )
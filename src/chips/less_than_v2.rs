@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+use eth_types::Field;
+use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+// Number of bytes N_BYTES that LtChip needs to compare values up to `max`, i.e. ceil(bits(max) / 8) and at least 1.
+// The comparison is only sound for lhs and rhs less than 2^(8 * N_BYTES), e.g. N_BYTES = 1 for max < 2^8,
+// 2 for max < 2^16, 8 for max < 2^64 and 16 for any u128
+pub const fn required_bytes(max: u128) -> usize {
+    let bits = (u128::BITS - max.leading_zeros()) as usize;
+    if bits == 0 {
+        1
+    } else {
+        (bits + 7) / 8
+    }
+}
+
+// Same as `LtChip::configure`, but checks at configure time that N_BYTES covers the values up to `max_value`,
+// so a too small N_BYTES panics instead of silently truncating the difference. The const generic can't be
+// derived from a runtime value, declare it with `required_bytes`, e.g. `LtConfig<F, { required_bytes(MAX) }>`
+pub fn configure_lt_for_max<F: Field, const N_BYTES: usize>(
+    meta: &mut ConstraintSystem<F>,
+    q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+    lhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+    rhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+    max_value: u128,
+) -> LtConfig<F, N_BYTES> {
+    assert!(
+        N_BYTES >= required_bytes(max_value),
+        "N_BYTES = {} is too small for values up to {}, at least {} bytes are required",
+        N_BYTES,
+        max_value,
+        required_bytes(max_value)
+    );
+
+    LtChip::configure(meta, q_enable, lhs, rhs)
+}
+
+// Wrapper around LtChip that compares a cell assigned in a previous region against a constant bound.
+// The lhs is copied into its column, so it can't be re-witnessed with a different value,
+// while the rhs is stored in a fixed column as it is known when building the circuit.
+#[derive(Debug, Clone)]
+pub struct LessThanV2Config<F: Field, const N_BYTES: usize> {
+    pub lhs: Column<Advice>,
+    pub rhs: Column<Fixed>,
+    pub check: Column<Advice>,
+    pub selector: Selector,
+    pub lt_config: LtConfig<F, N_BYTES>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LessThanV2Chip<F: Field, const N_BYTES: usize> {
+    config: LessThanV2Config<F, N_BYTES>,
+}
+
+impl<F: Field, const N_BYTES: usize> LessThanV2Chip<F, N_BYTES> {
+    pub fn construct(config: LessThanV2Config<F, N_BYTES>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        lhs: Column<Advice>,
+        rhs: Column<Fixed>,
+        check: Column<Advice>,
+    ) -> LessThanV2Config<F, N_BYTES> {
+        let selector = meta.complex_selector();
+
+        // lhs is copied from a previous region, check can be exposed
+        meta.enable_equality(lhs);
+        meta.enable_equality(check);
+        // the check of `assign_gte` is the constant 0, stored in the rhs column
+        meta.enable_constant(rhs);
+
+        let lt_config = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(lhs, Rotation::cur()),
+            |meta| meta.query_fixed(rhs, Rotation::cur()),
+        );
+
+        meta.create_gate(
+            "verifies that `check` is equal to is_lt from LtChip",
+            |meta| {
+                let q_enable = meta.query_selector(selector);
+                let check = meta.query_advice(check, Rotation::cur());
+
+                vec![q_enable * (lt_config.is_lt(meta, None) - check)]
+            },
+        );
+
+        LessThanV2Config {
+            lhs,
+            rhs,
+            check,
+            selector,
+            lt_config,
+        }
+    }
+
+    // Load the u8 lookup table used by LtChip
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        LtChip::construct(self.config.lt_config).load(layouter)
+    }
+
+    // Copies `lhs_cell` at `offset` and compares it against the constant `rhs`. Returns the check cell, 1 if lhs < rhs, 0 otherwise
+    pub fn assign_cell_vs_const(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs_cell: &AssignedCell<F, F>,
+        rhs: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.selector.enable(region, offset)?;
+
+        lhs_cell.copy_advice(|| "copy lhs", region, self.config.lhs, offset)?;
+        region.assign_fixed(|| "rhs", self.config.rhs, offset, || Value::known(rhs))?;
+
+        let mut lhs = F::zero();
+        lhs_cell.value().map(|f| lhs = *f);
+
+        let check_cell = region.assign_advice(
+            || "check",
+            self.config.check,
+            offset,
+            || Value::known(F::from((lhs < rhs) as u64)),
+        )?;
+
+        self.assign(region, offset, lhs, rhs)?;
+
+        Ok(check_cell)
+    }
+
+    // Copies `lhs_cell` at `offset` and proves that it is greater than or equal to the constant `rhs`.
+    // The check is the constant 0 instead of a witness, so the lt flag must be 0 and lhs - rhs must decompose
+    // into N_BYTES bytes, namely 0 <= lhs - rhs < 2^(8 * N_BYTES)
+    pub fn assign_gte(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs_cell: &AssignedCell<F, F>,
+        rhs: F,
+    ) -> Result<(), Error> {
+        self.config.selector.enable(region, offset)?;
+
+        lhs_cell.copy_advice(|| "copy lhs", region, self.config.lhs, offset)?;
+        region.assign_fixed(|| "rhs", self.config.rhs, offset, || Value::known(rhs))?;
+        region.assign_advice_from_constant(|| "check", self.config.check, offset, F::zero())?;
+
+        let mut lhs = F::zero();
+        lhs_cell.value().map(|f| lhs = *f);
+
+        self.assign(region, offset, lhs, rhs)?;
+
+        Ok(())
+    }
+
+    // Same witness as `LtChip::assign`, but returns the lt flag cell and the diff bytes cells, little-endian,
+    // so that the byte decomposition of `lhs - rhs + lt * 2^(8 * N_BYTES)` can be inspected
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<(AssignedCell<F, F>, [AssignedCell<F, F>; N_BYTES]), Error> {
+        let lt_config = &self.config.lt_config;
+        let lt = lhs < rhs;
+
+        let lt_cell = region.assign_advice(
+            || "lt chip: lt",
+            lt_config.lt,
+            offset,
+            || Value::known(F::from(lt as u64)),
+        )?;
+
+        let diff = (lhs - rhs) + (if lt { lt_config.range } else { F::zero() });
+        let diff_repr = diff.to_repr();
+        let diff_bytes = diff_repr.as_ref();
+
+        let diff_cells = lt_config
+            .diff
+            .iter()
+            .enumerate()
+            .map(|(idx, diff_column)| {
+                region.assign_advice(
+                    || format!("lt chip: diff byte {}", idx),
+                    *diff_column,
+                    offset,
+                    || Value::known(F::from(diff_bytes[idx] as u64)),
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok((
+            lt_cell,
+            diff_cells.try_into().map_err(|_| Error::Synthesis)?,
+        ))
+    }
+}
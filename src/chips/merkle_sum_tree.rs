@@ -1,31 +1,46 @@
+use super::gadgets::{bool_gate, conditional_swap_many};
+use super::is_zero::{IsZeroChip, IsZeroConfig};
 use super::poseidon::hash::{PoseidonChip, PoseidonConfig};
 use super::poseidon::spec::MySpec;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
 use eth_types::Field;
 use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
+use halo2_gadgets::poseidon::primitives::ConstantLength;
 use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
-const WIDTH: usize = 5;
-const RATE: usize = 4;
-const L: usize = 4;
+pub const WIDTH: usize = 5;
+pub const RATE: usize = 4;
+pub const L: usize = 4;
 
+// Domain separation tag of the leaves ("leaf" in ASCII), a leaf is hashed as H(LEAF_TAG, username, balance)
+// while a middle node is hashed from 4 elements, so a leaf can't be passed off as a middle node
+pub const LEAF_TAG: u64 = 0x6c656166;
+
+// MAX_BITS is the bound on every computed sum, a sum of 2^MAX_BITS or more fails the range check instead of wrapping.
+// N_BYTES is the size of the LtChip comparison of the total sum against the assets, it must fit MAX_BITS
 #[derive(Debug, Clone)]
-pub struct MerkleSumTreeConfig<F: Field> {
+pub struct MerkleSumTreeConfig<F: Field, const MAX_BITS: usize = 64, const N_BYTES: usize = 8> {
     pub advice: [Column<Advice>; 5],
     pub bool_selector: Selector,
     pub swap_selector: Selector,
     pub sum_selector: Selector,
     pub lt_selector: Selector,
+    pub zero_selector: Selector,
     pub instance: Column<Instance>,
     pub poseidon_config: PoseidonConfig<F, WIDTH, RATE, L>,
-    pub lt_config: LtConfig<F, 8>,
+    pub lt_config: LtConfig<F, N_BYTES>,
+    pub is_zero_config: IsZeroConfig<F>,
+    pub range_check_config: RangeCheckConfig<F, MAX_BITS>,
 }
 #[derive(Debug, Clone)]
-pub struct MerkleSumTreeChip<F: Field> {
-    config: MerkleSumTreeConfig<F>,
+pub struct MerkleSumTreeChip<F: Field, const MAX_BITS: usize = 64, const N_BYTES: usize = 8> {
+    config: MerkleSumTreeConfig<F, MAX_BITS, N_BYTES>,
 }
 
-impl<F: Field> MerkleSumTreeChip<F> {
-    pub fn construct(config: MerkleSumTreeConfig<F>) -> Self {
+impl<F: Field, const MAX_BITS: usize, const N_BYTES: usize>
+    MerkleSumTreeChip<F, MAX_BITS, N_BYTES>
+{
+    pub fn construct(config: MerkleSumTreeConfig<F, MAX_BITS, N_BYTES>) -> Self {
         Self { config }
     }
 
@@ -33,7 +48,14 @@ impl<F: Field> MerkleSumTreeChip<F> {
         meta: &mut ConstraintSystem<F>,
         advice: [Column<Advice>; 5],
         instance: Column<Instance>,
-    ) -> MerkleSumTreeConfig<F> {
+    ) -> MerkleSumTreeConfig<F, MAX_BITS, N_BYTES> {
+        assert!(
+            N_BYTES * 8 >= MAX_BITS,
+            "the sums of MAX_BITS = {} bits don't fit the comparison of N_BYTES = {} bytes",
+            MAX_BITS,
+            N_BYTES
+        );
+
         let col_a = advice[0];
         let col_b = advice[1];
         let col_c = advice[2];
@@ -45,6 +67,7 @@ impl<F: Field> MerkleSumTreeChip<F> {
         let swap_selector = meta.selector();
         let sum_selector = meta.selector();
         let lt_selector = meta.selector();
+        let zero_selector = meta.selector();
 
         // enable equality for leaf_hash copy constraint with instance column (col_a)
         // enable equality for balance_hash copy constraint with instance column (col_b)
@@ -59,35 +82,20 @@ impl<F: Field> MerkleSumTreeChip<F> {
 
         // Enforces that e is either a 0 or 1 when the bool selector is enabled
         // s * e * (1 - e) = 0
-        meta.create_gate("bool constraint", |meta| {
-            let s = meta.query_selector(bool_selector);
-            let e = meta.query_advice(col_e, Rotation::cur());
-            vec![s * e.clone() * (Expression::Constant(F::from(1)) - e)]
-        });
+        bool_gate(meta, bool_selector, col_e);
 
         // Enforces that if the swap bit (e) is on, l1=c, l2=d, r1=a, and r2=b. Otherwise, l1=a, l2=b, r1=c, and r2=d.
         // This applies only when the swap selector is enabled
         meta.create_gate("swap constraint", |meta| {
-            let s = meta.query_selector(swap_selector);
-            let a = meta.query_advice(col_a, Rotation::cur());
-            let b = meta.query_advice(col_b, Rotation::cur());
-            let c = meta.query_advice(col_c, Rotation::cur());
-            let d = meta.query_advice(col_d, Rotation::cur());
-            let e = meta.query_advice(col_e, Rotation::cur());
-            let l1 = meta.query_advice(col_a, Rotation::next());
-            let l2 = meta.query_advice(col_b, Rotation::next());
-            let r1 = meta.query_advice(col_c, Rotation::next());
-            let r2 = meta.query_advice(col_d, Rotation::next());
-
-            vec![
-                s.clone()
-                    * (e.clone() * Expression::Constant(F::from(2)) * (c.clone() - a.clone())
-                        - (l1 - a)
-                        - (c - r1)),
-                s * (e * Expression::Constant(F::from(2)) * (d.clone() - b.clone())
-                    - (l2 - b)
-                    - (d - r2)),
-            ]
+            conditional_swap_many(
+                meta,
+                swap_selector,
+                col_e,
+                &[col_a, col_b],
+                &[col_c, col_d],
+                &[col_a, col_b],
+                &[col_c, col_d],
+            )
         });
 
         // Enforces that input_left_balance + input_right_balance = computed_sum
@@ -112,15 +120,37 @@ impl<F: Field> MerkleSumTreeChip<F> {
             |meta| meta.query_advice(col_b, Rotation::cur()),
         );
 
+        // configure is zero chip, the balance is in col_a
+        let balance_inv = meta.advice_column();
+        let is_zero_config = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(zero_selector),
+            |meta| meta.query_advice(col_a, Rotation::cur()),
+            balance_inv,
+        );
+
+        // Enforces that the flag in col_b is equal to 1 if the balance is zero, 0 otherwise
+        meta.create_gate("zero balance flag", |meta| {
+            let s = meta.query_selector(zero_selector);
+            let flag = meta.query_advice(col_b, Rotation::cur());
+            vec![s * (is_zero_config.expr() - flag)]
+        });
+
+        // configure range check chip, the computed sum is in col_e
+        let range_check_config = RangeCheckChip::<F, MAX_BITS>::configure(meta, col_e);
+
         let config = MerkleSumTreeConfig {
             advice: [col_a, col_b, col_c, col_d, col_e],
             bool_selector,
             swap_selector,
             sum_selector,
             lt_selector,
+            zero_selector,
             instance,
             poseidon_config,
             lt_config,
+            is_zero_config,
+            range_check_config,
         };
 
         meta.create_gate(
@@ -170,20 +200,101 @@ impl<F: Field> MerkleSumTreeChip<F> {
         Ok((leaf_hash_cell, leaf_balance_cell))
     }
 
-    pub fn merkle_prove_layer(
+    // Computes the leaf hash as H(LEAF_TAG, username, balance) inside the circuit, the tag is a constant.
+    // Returns the leaf hash cell and the balance cell
+    pub fn assign_and_hash_leaf(
         &self,
         mut layouter: impl Layouter<F>,
+        username: F,
+        balance: F,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let leaf_cells = layouter.assign_region(
+            || "assign leaf",
+            |mut region| {
+                let tag = region.assign_advice_from_constant(
+                    || "leaf tag",
+                    self.config.advice[0],
+                    0,
+                    F::from(LEAF_TAG),
+                )?;
+                let username = region.assign_advice(
+                    || "username",
+                    self.config.advice[1],
+                    0,
+                    || Value::known(username),
+                )?;
+                let balance = region.assign_advice(
+                    || "leaf balance",
+                    self.config.advice[2],
+                    0,
+                    || Value::known(balance),
+                )?;
+
+                Ok([tag, username, balance])
+            },
+        )?;
+
+        let poseidon_chip = PoseidonChip::<F, MySpec<F, WIDTH, RATE>, WIDTH, RATE, L>::construct(
+            self.config.poseidon_config.clone(),
+        );
+
+        // the leaf has 3 elements, so it is hashed with the sponge instead of the L inputs hash
+        let mut sponge =
+            poseidon_chip.sponge::<ConstantLength<3>>(layouter.namespace(|| "leaf hash sponge"))?;
+        sponge.absorb(layouter.namespace(|| "absorb leaf"), &leaf_cells)?;
+        let leaf_hash = sponge.squeeze(layouter.namespace(|| "leaf hash"))?;
+
+        let [_, _, balance] = leaf_cells;
+        Ok((leaf_hash, balance))
+    }
+
+    // Proves one level of the path. The regions are named after `level` and the swap bit, so that
+    // a `VerifyFailure` of the MockProver can be mapped to the level of the tree
+    pub fn merkle_prove_layer(
+        &self,
+        layouter: impl Layouter<F>,
+        level: usize,
         prev_hash: &AssignedCell<F, F>,
         prev_balance: &AssignedCell<F, F>,
         element_hash: F,
         element_balance: F,
         index: F,
     ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
-        let (left_hash, left_balance, right_hash, right_balance, computed_sum_cell) = layouter
+        let (computed_hash, computed_sum, _) = self.merkle_prove_layer_with_element_balance(
+            layouter,
+            level,
+            prev_hash,
+            prev_balance,
+            element_hash,
+            element_balance,
+            index,
+        )?;
+        Ok((computed_hash, computed_sum))
+    }
+
+    // Same as `merkle_prove_layer`, but the cell of the path element balance is returned as well,
+    // so a parent circuit can copy it
+    #[allow(clippy::type_complexity)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn merkle_prove_layer_with_element_balance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        level: usize,
+        prev_hash: &AssignedCell<F, F>,
+        prev_balance: &AssignedCell<F, F>,
+        element_hash: F,
+        element_balance: F,
+        index: F,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let range_check_chip = RangeCheckChip::construct(self.config.range_check_config.clone());
+        let swap = if index == F::zero() { "unset" } else { "set" };
+
+        let (left_hash, left_balance, right_hash, right_balance, computed_sum_cell, r2) = layouter
             .assign_region(
-                || "merkle prove layer",
+                || format!("merkle prove layer level {} (swap bit {})", level, swap),
                 |mut region| {
                     // Row 0
+                    // the bool selector is enabled on the index cell of every level, not only the leaf level
                     self.config.bool_selector.enable(&mut region, 0)?;
                     self.config.swap_selector.enable(&mut region, 0)?;
                     let l1 = prev_hash.copy_advice(
@@ -275,12 +386,36 @@ impl<F: Field> MerkleSumTreeChip<F> {
                         || computed_sum,
                     )?;
 
+                    // Range check the computed sum to be less than 2^MAX_BITS
+                    range_check_chip.assign(&mut region, 1, computed_sum)?;
+
+                    // Range check the element balance, and the leaf balance at level 0, so that a balance of
+                    // p - x can't wrap the sum around. The previous balance of the other levels is a computed sum
+                    r2.copy_advice(
+                        || "copy element balance to be range checked",
+                        &mut region,
+                        self.config.advice[4],
+                        2,
+                    )?;
+                    range_check_chip.assign(&mut region, 2, Value::known(element_balance))?;
+
+                    if level == 0 {
+                        l2.copy_advice(
+                            || "copy leaf balance to be range checked",
+                            &mut region,
+                            self.config.advice[4],
+                            3,
+                        )?;
+                        range_check_chip.assign(&mut region, 3, prev_balance.value().copied())?;
+                    }
+
                     Ok((
                         left_hash,
                         left_balance,
                         right_hash,
                         right_balance,
                         computed_sum_cell,
+                        r2,
                     ))
                 },
             )?;
@@ -295,63 +430,223 @@ impl<F: Field> MerkleSumTreeChip<F> {
         // 2. Perform the hash function and assign the digest to the current row
         // 3. Constrain the digest to be equal to the hash of the left and right values
         let computed_hash = poseidon_chip.hash(
-            layouter.namespace(|| "hash four child nodes"),
+            layouter.namespace(|| format!("level {} hash four child nodes", level)),
             [left_hash, left_balance, right_hash, right_balance],
         )?;
 
-        Ok((computed_hash, computed_sum_cell))
+        Ok((computed_hash, computed_sum_cell, r2))
+    }
+
+    // Load the u8 table used to range check the computed sums, it must be called once by the circuit
+    pub fn load_range_check_table(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let range_check_chip = RangeCheckChip::construct(self.config.range_check_config.clone());
+        range_check_chip.load(&mut layouter)
     }
 
-    // Enforce computed sum to be less than total assets passed inside the instance column
+    // Enforce the sum to be less than the total assets passed inside the instance column at row 3.
+    // The sum cell is copied into the lhs column of LtChip, so the comparison is bound to the proven sum:
+    // a `computed_sum` different from the value of `sum_cell` leads to a wrong diff witness and fails.
+    // The total assets are range checked to be less than 2^MAX_BITS like the sums, otherwise an assets sum
+    // out of the N_BYTES range of LtChip would make the diff wrap. It requires `load_range_check_table`
     pub fn enforce_less_than(
         &self,
         mut layouter: impl Layouter<F>,
-        prev_computed_sum_cell: &AssignedCell<F, F>,
+        sum_cell: &AssignedCell<F, F>,
         computed_sum: F,
         total_assets: F,
     ) -> Result<(), Error> {
-        // Initiate chip config
         let chip = LtChip::construct(self.config.lt_config);
         chip.load(&mut layouter)?;
 
+        let range_check_chip = RangeCheckChip::construct(self.config.range_check_config.clone());
+
         layouter.assign_region(
             || "enforce sum to be less than total assets",
             |mut region| {
-                // copy the computed sum to the cell in the first column
-                prev_computed_sum_cell.copy_advice(
-                    || "copy computed sum",
+                // sum < total assets
+                sum_cell.copy_advice(|| "copy sum", &mut region, self.config.advice[0], 0)?;
+                let total_assets_cell = region.assign_advice_from_instance(
+                    || "copy total assets",
+                    self.config.instance,
+                    3,
+                    self.config.advice[1],
+                    0,
+                )?;
+
+                // Range check the total assets to be less than 2^MAX_BITS
+                let total_assets_cell = total_assets_cell.copy_advice(
+                    || "copy total assets to range check",
                     &mut region,
-                    self.config.advice[0],
+                    self.config.advice[4],
                     0,
                 )?;
+                range_check_chip.assign(&mut region, 0, total_assets_cell.value().copied())?;
 
-                // copy the total assets from instance column to the cell in the second column
-                region.assign_advice_from_instance(
-                    || "copy total assets",
+                // the check is a constant, so it can't be set to 0 by the prover
+                region.assign_advice_from_constant(
+                    || "check",
+                    self.config.advice[2],
+                    0,
+                    F::one(),
+                )?;
+
+                self.config.lt_selector.enable(&mut region, 0)?;
+
+                chip.assign(&mut region, 0, computed_sum, total_assets)
+            },
+        )
+    }
+
+    // Enforce the balance to be greater than the threshold passed inside the instance column at `threshold_row`.
+    // The balance cell is only copied, so it can stay private. The threshold is range checked like the total assets
+    // of `enforce_less_than`, the balance is already range checked by `merkle_prove_layer`. As for
+    // `enforce_less_than`, the u8 table of LtChip is loaded here, so only one of the two can be used by a circuit
+    pub fn enforce_greater_than(
+        &self,
+        mut layouter: impl Layouter<F>,
+        balance_cell: &AssignedCell<F, F>,
+        balance: F,
+        threshold: F,
+        threshold_row: usize,
+    ) -> Result<(), Error> {
+        let chip = LtChip::construct(self.config.lt_config);
+        chip.load(&mut layouter)?;
+
+        let range_check_chip = RangeCheckChip::construct(self.config.range_check_config.clone());
+
+        layouter.assign_region(
+            || "enforce balance to be greater than threshold",
+            |mut region| {
+                // threshold < balance
+                let threshold_cell = region.assign_advice_from_instance(
+                    || "copy threshold",
                     self.config.instance,
-                    3,
+                    threshold_row,
+                    self.config.advice[0],
+                    0,
+                )?;
+                balance_cell.copy_advice(
+                    || "copy balance",
+                    &mut region,
                     self.config.advice[1],
                     0,
                 )?;
 
-                // set check to be equal to 1
-                region.assign_advice(
+                // Range check the threshold to be less than 2^MAX_BITS
+                let threshold_cell = threshold_cell.copy_advice(
+                    || "copy threshold to range check",
+                    &mut region,
+                    self.config.advice[4],
+                    0,
+                )?;
+                range_check_chip.assign(&mut region, 0, threshold_cell.value().copied())?;
+
+                // the check is a constant, so it can't be set to 0 by the prover
+                region.assign_advice_from_constant(
                     || "check",
                     self.config.advice[2],
                     0,
-                    || Value::known(F::from(1)),
+                    F::one(),
                 )?;
 
-                // enable lt seletor
                 self.config.lt_selector.enable(&mut region, 0)?;
 
-                chip.assign(&mut region, 0, computed_sum, total_assets)?;
+                chip.assign(&mut region, 0, threshold, balance)
+            },
+        )
+    }
+
+    // Load the u8 table of LtChip. `enforce_less_than` and `enforce_greater_than` already load it, a circuit
+    // calling only `enforce_node_capacity` must call it once
+    pub fn load_lt_table(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        LtChip::construct(self.config.lt_config).load(&mut layouter)
+    }
+
+    // Enforce the sum of a node to be less than or equal to its capacity, passed inside the instance column at
+    // `capacity_row`. It is proven as `!(capacity < sum)`: the capacity is the lhs of LtChip and the check is the
+    // constant 0. The capacity is range checked like the total assets of `enforce_less_than`, the sum cell is
+    // already range checked by `merkle_prove_layer`. The LtChip table is not loaded here, so that it can be
+    // called at every level, see `load_lt_table`
+    pub fn enforce_node_capacity(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sum_cell: &AssignedCell<F, F>,
+        computed_sum: F,
+        capacity: F,
+        capacity_row: usize,
+    ) -> Result<(), Error> {
+        let chip = LtChip::construct(self.config.lt_config);
+        let range_check_chip = RangeCheckChip::construct(self.config.range_check_config.clone());
 
-                Ok(())
+        layouter.assign_region(
+            || "enforce sum to be within node capacity",
+            |mut region| {
+                // capacity < sum must be false
+                let capacity_cell = region.assign_advice_from_instance(
+                    || "copy capacity",
+                    self.config.instance,
+                    capacity_row,
+                    self.config.advice[0],
+                    0,
+                )?;
+                sum_cell.copy_advice(|| "copy sum", &mut region, self.config.advice[1], 0)?;
+
+                // Range check the capacity to be less than 2^MAX_BITS
+                let capacity_cell = capacity_cell.copy_advice(
+                    || "copy capacity to range check",
+                    &mut region,
+                    self.config.advice[4],
+                    0,
+                )?;
+                range_check_chip.assign(&mut region, 0, capacity_cell.value().copied())?;
+
+                region.assign_advice_from_constant(
+                    || "check",
+                    self.config.advice[2],
+                    0,
+                    F::zero(),
+                )?;
+
+                self.config.lt_selector.enable(&mut region, 0)?;
+
+                chip.assign(&mut region, 0, capacity, computed_sum)
             },
-        )?;
+        )
+    }
+
+    // Returns a cell containing 1 if the balance is zero, 0 otherwise. The balance itself is not revealed
+    pub fn assign_zero_balance_flag(
+        &self,
+        mut layouter: impl Layouter<F>,
+        balance_cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let is_zero_chip = IsZeroChip::construct(self.config.is_zero_config.clone());
+
+        layouter.assign_region(
+            || "zero balance flag",
+            |mut region| {
+                self.config.zero_selector.enable(&mut region, 0)?;
 
-        Ok(())
+                let balance = balance_cell.copy_advice(
+                    || "copy balance",
+                    &mut region,
+                    self.config.advice[0],
+                    0,
+                )?;
+
+                is_zero_chip.assign(&mut region, 0, balance.value().copied())?;
+
+                let flag = balance.value().map(|balance| {
+                    if *balance == F::zero() {
+                        F::one()
+                    } else {
+                        F::zero()
+                    }
+                });
+
+                region.assign_advice(|| "zero balance flag", self.config.advice[1], 0, || flag)
+            },
+        )
     }
 
     // Enforce permutation check between input cell and instance column at row passed as input
@@ -1,6 +1,8 @@
-use std::marker::PhantomData;
+use super::gadgets::{bool_gate, conditional_swap};
+use core::marker::PhantomData;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
 #[derive(Debug, Clone)]
 pub struct MerkleTreeV1Config {
@@ -9,14 +11,26 @@ pub struct MerkleTreeV1Config {
     pub swap_selector: Selector,
     pub hash_selector: Selector,
     pub instance: Column<Instance>,
+    pub range_check: Option<MerkleTreeV1RangeCheckConfig>,
 }
+
+// The dummy hash a + b = c wraps around the field modulus for large inputs. When configured, the inputs and the
+// digest of every level are looked up inside a fixed table containing the values from 0 to (bound - 1),
+// so that a sum out of the bound fails instead of wrapping
+#[derive(Debug, Clone)]
+pub struct MerkleTreeV1RangeCheckConfig {
+    pub table: Column<Fixed>,
+    pub selector: Selector,
+    pub bound: usize,
+}
+
 #[derive(Debug, Clone)]
-pub struct MerkleTreeV1Chip<F: FieldExt> {
+pub struct MerkleTreeV1Chip<F: Field> {
     config: MerkleTreeV1Config,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt> MerkleTreeV1Chip<F> {
+impl<F: Field> MerkleTreeV1Chip<F> {
     pub fn construct(config: MerkleTreeV1Config) -> Self {
         Self {
             config,
@@ -49,27 +63,21 @@ impl<F: FieldExt> MerkleTreeV1Chip<F> {
 
         // Enforces that c is either a 0 or 1 when the bool selector is enabled
         // s * c * (1 - c) = 0
-        meta.create_gate("bool constraint", |meta| {
-            let s = meta.query_selector(bool_selector);
-            let c = meta.query_advice(col_c, Rotation::cur());
-            vec![s * c.clone() * (Expression::Constant(F::from(1)) - c)]
-        });
+        bool_gate(meta, bool_selector, col_c);
 
         // Enforces that if the swap bit (c) is on, l=b and r=a. Otherwise, l=a and r=b.
         // s * (c * 2 * (b - a) - (l - a) - (b - r)) = 0
         // This applies only when the swap selector is enabled
         meta.create_gate("swap constraint", |meta| {
-            let s = meta.query_selector(swap_selector);
-            let a = meta.query_advice(col_a, Rotation::cur());
-            let b = meta.query_advice(col_b, Rotation::cur());
-            let c = meta.query_advice(col_c, Rotation::cur());
-            let l = meta.query_advice(col_a, Rotation::next());
-            let r = meta.query_advice(col_b, Rotation::next());
-            vec![
-                s * (c * Expression::Constant(F::from(2)) * (b.clone() - a.clone())
-                    - (l - a)
-                    - (b - r)),
-            ]
+            vec![conditional_swap(
+                meta,
+                swap_selector,
+                col_c,
+                col_a,
+                col_b,
+                col_a,
+                col_b,
+            )]
         });
 
         // enforce dummy hash function when hash selector is enabled
@@ -89,9 +97,65 @@ impl<F: FieldExt> MerkleTreeV1Chip<F> {
             swap_selector,
             hash_selector,
             instance,
+            range_check: None,
         }
     }
 
+    // Same as `configure`, but the inputs and the digest of the hash are range checked to be less than `bound`.
+    // As long as 2 * bound is less than the modulus, a + b can't wrap around
+    pub fn configure_with_range_check(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+        bound: usize,
+    ) -> MerkleTreeV1Config {
+        let mut config = Self::configure(meta, advice, instance);
+
+        let table = meta.fixed_column();
+        let selector = meta.complex_selector();
+        meta.annotate_lookup_any_column(table, || "digest range table");
+
+        // 0 is always in the table, so the lookups hold when the selector is off
+        for column in advice {
+            meta.lookup_any("digest range check", |meta| {
+                let s = meta.query_selector(selector);
+                let value = meta.query_advice(column, Rotation::cur());
+                let table = meta.query_fixed(table, Rotation::cur());
+                vec![(s * value, table)]
+            });
+        }
+
+        config.range_check = Some(MerkleTreeV1RangeCheckConfig {
+            table,
+            selector,
+            bound,
+        });
+        config
+    }
+
+    // Load the range table with the values from 0 to (bound - 1), it does nothing without range check
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range_check = match &self.config.range_check {
+            Some(range_check) => range_check,
+            None => return Ok(()),
+        };
+
+        layouter.assign_region(
+            || "load digest range table",
+            |mut region| {
+                for i in 0..range_check.bound {
+                    region.assign_fixed(
+                        || "digest range table",
+                        range_check.table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
     pub fn assing_leaf(
         &self,
         mut layouter: impl Layouter<F>,
@@ -138,6 +202,9 @@ impl<F: FieldExt> MerkleTreeV1Chip<F> {
                 // Row 1: | InputLeft | InputRight | Digest |
                 // Enabled Selectors: Hash
                 self.config.hash_selector.enable(&mut region, 1)?;
+                if let Some(range_check) = &self.config.range_check {
+                    range_check.selector.enable(&mut region, 1)?;
+                }
                 let mut input_l = node_cell.value().map(|x| x.to_owned());
                 let mut input_r = path_element;
                 index.map(|index| {
@@ -1,6 +1,8 @@
+use super::gadgets::{bool_gate, conditional_swap};
 use super::hash_v2::{Hash2Chip, Hash2Config};
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
-use std::marker::PhantomData;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+use core::marker::PhantomData;
 
 #[derive(Debug, Clone)]
 pub struct MerkleTreeV2Config {
@@ -11,12 +13,12 @@ pub struct MerkleTreeV2Config {
     pub hash2_config: Hash2Config,
 }
 #[derive(Debug, Clone)]
-pub struct MerkleTreeV2Chip<F: FieldExt> {
+pub struct MerkleTreeV2Chip<F: Field> {
     config: MerkleTreeV2Config,
     _marker: PhantomData<F>,
 }
 
-impl<F: FieldExt> MerkleTreeV2Chip<F> {
+impl<F: Field> MerkleTreeV2Chip<F> {
     pub fn construct(config: MerkleTreeV2Config) -> Self {
         Self {
             config,
@@ -50,27 +52,21 @@ impl<F: FieldExt> MerkleTreeV2Chip<F> {
 
         // Enforces that c is either a 0 or 1 when the bool selector is enabled
         // s * c * (1 - c) = 0
-        meta.create_gate("bool constraint", |meta| {
-            let s = meta.query_selector(bool_selector);
-            let c = meta.query_advice(col_c, Rotation::cur());
-            vec![s * c.clone() * (Expression::Constant(F::from(1)) - c)]
-        });
+        bool_gate(meta, bool_selector, col_c);
 
         // Enforces that if the swap bit (c) is on, l=b and r=a. Otherwise, l=a and r=b.
         // s * (c * 2 * (b - a) - (l - a) - (b - r)) = 0
         // This applies only when the swap selector is enabled
         meta.create_gate("swap constraint", |meta| {
-            let s = meta.query_selector(swap_selector);
-            let a = meta.query_advice(col_a, Rotation::cur());
-            let b = meta.query_advice(col_b, Rotation::cur());
-            let c = meta.query_advice(col_c, Rotation::cur());
-            let l = meta.query_advice(col_a, Rotation::next());
-            let r = meta.query_advice(col_b, Rotation::next());
-            vec![
-                s * (c * Expression::Constant(F::from(2)) * (b.clone() - a.clone())
-                    - (l - a)
-                    - (b - r)),
-            ]
+            vec![conditional_swap(
+                meta,
+                swap_selector,
+                col_c,
+                col_a,
+                col_b,
+                col_a,
+                col_b,
+            )]
         });
 
         let hash2_config = Hash2Chip::configure(meta, advice, instance);
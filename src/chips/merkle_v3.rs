@@ -1,13 +1,16 @@
+use super::gadgets::{bool_gate, conditional_swap};
 use super::poseidon::hash::{PoseidonChip, PoseidonConfig};
 use super::poseidon::spec::MySpec;
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use alloc::vec::Vec;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 const WIDTH: usize = 3;
 const RATE: usize = 2;
 const L: usize = 2;
 
 #[derive(Debug, Clone)]
-pub struct MerkleTreeV3Config <F: FieldExt> {
+pub struct MerkleTreeV3Config <F: Field> {
     pub advice: [Column<Advice>; 3],
     pub bool_selector: Selector,
     pub swap_selector: Selector,
@@ -15,11 +18,11 @@ pub struct MerkleTreeV3Config <F: FieldExt> {
     pub poseidon_config: PoseidonConfig<F, WIDTH, RATE, L>,
 }
 #[derive(Debug, Clone)]
-pub struct MerkleTreeV3Chip <F: FieldExt>{
+pub struct MerkleTreeV3Chip <F: Field>{
     config: MerkleTreeV3Config<F>,
 }
 
-impl <F: FieldExt> MerkleTreeV3Chip<F> {
+impl <F: Field> MerkleTreeV3Chip<F> {
     pub fn construct(config: MerkleTreeV3Config<F>) -> Self {
         Self { config }
     }
@@ -44,27 +47,21 @@ impl <F: FieldExt> MerkleTreeV3Chip<F> {
 
         // Enforces that c is either a 0 or 1 when the bool selector is enabled
         // s * c * (1 - c) = 0
-        meta.create_gate("bool constraint", |meta| {
-            let s = meta.query_selector(bool_selector);
-            let c = meta.query_advice(col_c, Rotation::cur());
-            vec![s * c.clone() * (Expression::Constant(F::from(1)) - c)]
-        });
+        bool_gate(meta, bool_selector, col_c);
 
         // Enforces that if the swap bit (c) is on, l=b and r=a. Otherwise, l=a and r=b.
         // s * (c * 2 * (b - a) - (l - a) - (b - r)) = 0
         // This applies only when the swap selector is enabled
         meta.create_gate("swap constraint", |meta| {
-            let s = meta.query_selector(swap_selector);
-            let a = meta.query_advice(col_a, Rotation::cur());
-            let b = meta.query_advice(col_b, Rotation::cur());
-            let c = meta.query_advice(col_c, Rotation::cur());
-            let l = meta.query_advice(col_a, Rotation::next());
-            let r = meta.query_advice(col_b, Rotation::next());
-            vec![
-                s * (c * Expression::Constant(F::from(2)) * (b.clone() - a.clone())
-                    - (l - a)
-                    - (b - r)),
-            ]
+            vec![conditional_swap(
+                meta,
+                swap_selector,
+                col_c,
+                col_a,
+                col_b,
+                col_a,
+                col_b,
+            )]
         });
 
         let hash_inputs = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
@@ -96,12 +93,24 @@ impl <F: FieldExt> MerkleTreeV3Chip<F> {
 
     pub fn merkle_prove_layer(
         &self,
-        mut layouter: impl Layouter<F>,
+        layouter: impl Layouter<F>,
         node_cell: &AssignedCell<F, F>,
         path_element: Value<F>,
         index: Value<F>,
     ) -> Result<AssignedCell<F, F>, Error> {
-        let (left, right) = layouter.assign_region(
+        let (digest, _, _) = self.assign_layer(layouter, node_cell, path_element, index)?;
+        Ok(digest)
+    }
+
+    // Same as `merkle_prove_layer`, but the cells of the path element and of the index are returned too
+    fn assign_layer(
+        &self,
+        mut layouter: impl Layouter<F>,
+        node_cell: &AssignedCell<F, F>,
+        path_element: Value<F>,
+        index: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let (left, right, element_cell, index_cell) = layouter.assign_region(
             || "merkle prove layer",
             |mut region| {
                 // Row 0
@@ -113,13 +122,14 @@ impl <F: FieldExt> MerkleTreeV3Chip<F> {
                     self.config.advice[0],
                     0,
                 )?;
-                region.assign_advice(
+                let element_cell = region.assign_advice(
                     || "assign element",
                     self.config.advice[1],
                     0,
                     || path_element,
                 )?;
-                region.assign_advice(|| "assign index", self.config.advice[2], 0, || index)?;
+                let index_cell =
+                    region.assign_advice(|| "assign index", self.config.advice[2], 0, || index)?;
 
                 // Row 1
                 // Here we just perform the assignment - no hashing is performed here!
@@ -143,7 +153,7 @@ impl <F: FieldExt> MerkleTreeV3Chip<F> {
                     || r,
                 )?;
 
-                Ok((left, right))
+                Ok((left, right, element_cell, index_cell))
             },
         )?;
 
@@ -158,7 +168,50 @@ impl <F: FieldExt> MerkleTreeV3Chip<F> {
         // 3. Constrain the digest to be equal to the hash of the left and right values
         let digest =
             poseidon_chip.hash(layouter.namespace(|| "hash row constaint"), [left, right])?;
-        Ok(digest)
+        Ok((digest, element_cell, index_cell))
+    }
+
+    // Proves that replacing old_leaf with new_leaf at the same position takes the old root to the new root.
+    // Both leaves are proven against the same path: the path elements and the indices of the new leaf
+    // are constrained to be equal to the ones of the old leaf. Returns the old root and the new root cells
+    pub fn prove_update(
+        &self,
+        mut layouter: impl Layouter<F>,
+        old_leaf: Value<F>,
+        new_leaf: Value<F>,
+        path_elements: &[Value<F>],
+        path_indices: &[Value<F>],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let mut old_digest = self.assing_leaf(layouter.namespace(|| "assign old leaf"), old_leaf)?;
+        let mut new_digest = self.assing_leaf(layouter.namespace(|| "assign new leaf"), new_leaf)?;
+
+        for (i, (element, index)) in path_elements.iter().zip(path_indices).enumerate() {
+            let (old_next, old_element, old_index) = self.assign_layer(
+                layouter.namespace(|| format!("old leaf level {}", i)),
+                &old_digest,
+                *element,
+                *index,
+            )?;
+            let (new_next, new_element, new_index) = self.assign_layer(
+                layouter.namespace(|| format!("new leaf level {}", i)),
+                &new_digest,
+                *element,
+                *index,
+            )?;
+
+            layouter.assign_region(
+                || format!("share path level {}", i),
+                |mut region| {
+                    region.constrain_equal(old_element.cell(), new_element.cell())?;
+                    region.constrain_equal(old_index.cell(), new_index.cell())
+                },
+            )?;
+
+            old_digest = old_next;
+            new_digest = new_next;
+        }
+
+        Ok((old_digest, new_digest))
     }
 
     // Enforce permutation check between input cell and instance column at row passed as input
@@ -1,136 +1,208 @@
+use arrayvec::ArrayVec;
 use eth_types::Field;
-use std::marker::PhantomData;
 
 use super::is_zero::{IsZeroChip, IsZeroConfig};
-use super::utils::add_carry;
+use super::utils::add_carry_limbs;
 use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
+// The accumulator is split into ACC_COLS columns of MAX_BITS bits, the most significant one first.
+// The most significant column is the overflow column and must stay zero, so the sum is capped at
+// MAX_BITS * (ACC_COLS - 1) bits. The new value and the accumulator columns besides the overflow column are
+// range checked to be less than 2^MAX_BITS. With ACC_COLS = 3 and MAX_BITS = 16:
+//
+// | value | sum_overflow_inv | sum_overflow (x * 2^32) | sum_hi (x * 2^16) | sum_lo (x * 2^0) |
+// | --    | --               | --                      | --                | --               |
+// |       |                  | prev_b                  | prev_c            | prev_d           |
+// | a     | 1/b or 0         | b                       | c                 | d                |
 #[derive(Debug, Clone)]
-pub struct OverFlowCheckConfig<F: Field> {
-    pub advice: [Column<Advice>; 5],
+pub struct OverFlowCheckConfig<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
+    pub update_value: Column<Advice>,
+    pub left_most_inv: Column<Advice>,
+    pub accumulate: [Column<Advice>; ACC_COLS],
+    pub constant: Column<Fixed>,
     pub instance: Column<Instance>,
     pub is_zero: IsZeroConfig<F>,
     pub selector: [Selector; 2],
+    pub range_selector: Selector,
+    pub range: Column<Fixed>,
 }
 
 #[derive(Debug, Clone)]
-pub struct OverFlowChip<F: Field> {
-    config: OverFlowCheckConfig<F>,
-    _marker: PhantomData<F>,
+pub struct OverFlowChip<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
+    config: OverFlowCheckConfig<MAX_BITS, ACC_COLS, F>,
 }
 
-impl<F: Field> OverFlowChip<F> {
-    pub fn construct(config: OverFlowCheckConfig<F>) -> Self {
-        Self {
-            config,
-            _marker: PhantomData,
-        }
+impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> OverFlowChip<MAX_BITS, ACC_COLS, F> {
+    pub fn construct(config: OverFlowCheckConfig<MAX_BITS, ACC_COLS, F>) -> Self {
+        Self { config }
     }
 
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 5],
+        update_value: Column<Advice>,
+        left_most_inv: Column<Advice>,
+        accumulate: [Column<Advice>; ACC_COLS],
+        constant: Column<Fixed>,
         selector: [Selector; 2],
         instance: Column<Instance>,
-    ) -> OverFlowCheckConfig<F> {
-        let col_a = advice[0];
-        let col_b_inv = advice[1];
-        let col_b = advice[2];
-        let col_c = advice[3];
-        let col_d = advice[4];
+    ) -> OverFlowCheckConfig<MAX_BITS, ACC_COLS, F> {
+        assert!(
+            ACC_COLS >= 2,
+            "at least one column is needed besides the overflow column"
+        );
+
         let add_carry_selector = selector[0];
         let overflow_check_selector = selector[1];
         let is_zero = IsZeroChip::configure(
             meta,
             |meta| meta.query_selector(overflow_check_selector),
-            |meta| meta.query_advice(col_b, Rotation::cur()),
-            // |meta| meta.query_advice(col_b_inv, Rotation::cur())
-            col_b_inv,
+            |meta| meta.query_advice(accumulate[0], Rotation::cur()),
+            left_most_inv,
         );
 
+        // the range checks are lookups, so they need a complex selector
+        let range_selector = meta.complex_selector();
+        let range = meta.fixed_column();
+
         // Enable equality on the advice and instance column to enable permutation check
-        meta.enable_equality(col_b);
-        meta.enable_equality(col_c);
-        meta.enable_equality(col_d);
+        for col in accumulate {
+            meta.enable_equality(col);
+        }
         meta.enable_equality(instance);
 
-        // enforce dummy hash function by creating a custom gate
+        // Enable constant column, used by `assign_first_row` and `reset_to_zero`
+        meta.enable_constant(constant);
+
         meta.create_gate("accumulate constraint", |meta| {
             let s_add = meta.query_selector(add_carry_selector);
             let s_over = meta.query_selector(overflow_check_selector);
-            let prev_b = meta.query_advice(col_b, Rotation::prev());
-            let prev_c = meta.query_advice(col_c, Rotation::prev());
-            let prev_d = meta.query_advice(col_d, Rotation::prev());
-            let a = meta.query_advice(col_a, Rotation::cur());
-            let b = meta.query_advice(col_b, Rotation::cur());
-            let c = meta.query_advice(col_c, Rotation::cur());
-            let d = meta.query_advice(col_d, Rotation::cur());
-
-            // Previous accumulator amount + new value from a_cell
-            // using binary expression (x_n-4 * 2^16) + (x_n-3 * 2^8) + ... + (x_n * 2)
+            let a = meta.query_advice(update_value, Rotation::cur());
+
+            // Previous accumulator amount + new value from a_cell, where the column i is weighted by
+            // 2^(MAX_BITS * (ACC_COLS - 1 - i)), e.g. (b * 2^32) + (c * 2^16) + d for 3 columns of 16 bits
+            let (prev_sum, sum) = (0..ACC_COLS).fold(
+                (a, Expression::Constant(F::zero())),
+                |(prev_sum, sum), i| {
+                    // 2^MAX_BITS doesn't fit into a u64 for MAX_BITS >= 64, so the shift is a power of 2 in F
+                    let shift = Expression::Constant(
+                        F::from(2).pow_vartime(&[MAX_BITS as u64 * (ACC_COLS - 1 - i) as u64]),
+                    );
+                    let prev = meta.query_advice(accumulate[i], Rotation::prev());
+                    let cur = meta.query_advice(accumulate[i], Rotation::cur());
+                    (prev_sum + prev * shift.clone(), sum + cur * shift)
+                },
+            );
+
             vec![
-                s_add
-                    * ((a
-                        + (prev_b * Expression::Constant(F::from(1 << 32)))
-                        + (prev_c * Expression::Constant(F::from(1 << 16)))
-                        + prev_d)
-                        - ((b.clone() * Expression::Constant(F::from(1 << 32)))
-                            + (c * Expression::Constant(F::from(1 << 16)))
-                            + d)),
-                // check 'b' is zero
-                // s_over.clone() * (a_equals_b.expr() * (output.clone() - c)),
+                s_add * (prev_sum - sum),
+                // check the overflow column is zero
                 s_over * (Expression::Constant(F::one()) - is_zero.expr()),
             ]
         });
 
+        // Without the range checks, the excess of the sum could be moved from the overflow column to the
+        // column next to it, which would keep the overflow column at zero
+        meta.annotate_lookup_any_column(range, || "LOOKUP_MAXBITS_RANGE");
+
+        meta.lookup_any("range check for new value", |meta| {
+            let s = meta.query_selector(range_selector);
+            let value = meta.query_advice(update_value, Rotation::cur());
+            vec![(s * value, meta.query_fixed(range, Rotation::cur()))]
+        });
+
+        for column in accumulate.iter().skip(1) {
+            meta.lookup_any("range check for accumulate", |meta| {
+                let s = meta.query_selector(range_selector);
+                let value = meta.query_advice(*column, Rotation::cur());
+                vec![(s * value, meta.query_fixed(range, Rotation::cur()))]
+            });
+        }
+
         OverFlowCheckConfig {
-            advice: [col_a, col_b_inv, col_b, col_c, col_d],
+            update_value,
+            left_most_inv,
+            accumulate,
+            constant,
             instance,
             selector: [add_carry_selector, overflow_check_selector],
             is_zero,
+            range_selector,
+            range,
         }
     }
 
-    // Initial accumulator values from instance for expreiment
+    // Load the range table, from 0 to 2^MAX_BITS - 1
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range = 1 << (MAX_BITS as usize);
+
+        layouter.assign_region(
+            || format!("load range check table of {} bits", MAX_BITS),
+            |mut region| {
+                for i in 0..range {
+                    region.assign_fixed(
+                        || "assign cell in fixed column",
+                        self.config.range,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    // Initial accumulator values from instance for expreiment.
+    // The overflow column starts at zero, the other columns are taken from the rows 0..ACC_COLS - 1
     pub fn assign_first_row(
         &self,
         mut layouter: impl Layouter<F>,
-    ) -> Result<
-        (
-            AssignedCell<F, F>,
-            AssignedCell<F, F>,
-            AssignedCell<F, F>,
-        ),
-        Error,
-    > {
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
         layouter.assign_region(
             || "first row",
             |mut region| {
-                let b_cell = region.assign_advice_from_instance(
-                    || "first acc[2]",
-                    self.config.instance,
-                    0,
-                    self.config.advice[2],
+                let mut cells = ArrayVec::new();
+                cells.push(region.assign_advice_from_constant(
+                    || "first acc[0]",
+                    self.config.accumulate[0],
                     0,
-                )?;
+                    F::zero(),
+                )?);
 
-                let c_cell = region.assign_advice_from_instance(
-                    || "first acc[4]",
-                    self.config.instance,
-                    0,
-                    self.config.advice[3],
-                    0,
-                )?;
+                for i in 1..ACC_COLS {
+                    cells.push(region.assign_advice_from_instance(
+                        || format!("first acc[{}]", i),
+                        self.config.instance,
+                        i - 1,
+                        self.config.accumulate[i],
+                        0,
+                    )?);
+                }
 
-                let d_cell = region.assign_advice_from_instance(
-                    || "first acc[4]",
-                    self.config.instance,
-                    1,
-                    self.config.advice[4],
-                    0,
-                )?;
+                Ok(cells)
+            },
+        )
+    }
 
-                Ok((b_cell, c_cell, d_cell))
+    // Assigns a fresh accumulator equal to zero, the cells are constrained to the zero constant.
+    // They can be passed as `prev` to `assign_advice_row` to start a new segment after a checkpoint
+    pub fn reset_to_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
+        layouter.assign_region(
+            || "reset accumulator",
+            |mut region| {
+                let mut cells = ArrayVec::new();
+                for (i, col) in self.config.accumulate.iter().enumerate() {
+                    cells.push(region.assign_advice_from_constant(
+                        || format!("zero acc[{}]", i),
+                        *col,
+                        0,
+                        F::zero(),
+                    )?);
+                }
+
+                Ok(cells)
             },
         )
     }
@@ -139,17 +211,8 @@ impl<F: Field> OverFlowChip<F> {
         &self,
         mut layouter: impl Layouter<F>,
         a: Value<F>,
-        prev_b: AssignedCell<F, F>,
-        prev_c: AssignedCell<F, F>,
-        prev_d: AssignedCell<F, F>,
-    ) -> Result<
-        (
-            AssignedCell<F, F>,
-            AssignedCell<F, F>,
-            AssignedCell<F, F>,
-        ),
-        Error,
-    > {
+        prev: ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
         let is_zero_chip = IsZeroChip::construct(self.config.is_zero.clone());
         layouter.assign_region(
             || "adivce row for accumulating",
@@ -157,54 +220,44 @@ impl<F: Field> OverFlowChip<F> {
                 // enable hash selector
                 self.config.selector[0].enable(&mut region, 1)?;
                 self.config.selector[1].enable(&mut region, 1)?;
+                self.config.range_selector.enable(&mut region, 1)?;
 
-                let _ = prev_b.copy_advice(|| "prev_b", &mut region, self.config.advice[2], 0);
-                let _ = prev_c.copy_advice(|| "prev_c", &mut region, self.config.advice[3], 0);
-                let _ = prev_d.copy_advice(|| "prev_d", &mut region, self.config.advice[4], 0);
+                for (i, cell) in prev.iter().enumerate() {
+                    cell.copy_advice(
+                        || format!("prev acc[{}]", i),
+                        &mut region,
+                        self.config.accumulate[i],
+                        0,
+                    )?;
+                }
 
                 // Assign new value to the cell inside the region
-                region.assign_advice(|| "a", self.config.advice[0], 1, || a)?;
-
-                let (hi, lo) = add_carry::<16, F>(a, prev_c.clone(), prev_d.clone());
-
-                // assigning two columns of accumulating value
-                let mut c_cell = region.assign_advice(
-                    || "sum_hi",
-                    self.config.advice[3],
-                    1,
-                    || Value::known(hi),
-                )?;
-                let d_cell = region.assign_advice(
-                    || "sum_lo",
-                    self.config.advice[4],
-                    1,
-                    || Value::known(lo),
-                )?;
-
-                let mut sum_overflow = F::zero();
-                if hi >= F::from(1 << 16) {
-                    let (ov, hi) =
-                        add_carry::<16, F>(Value::known(F::zero()), prev_b.clone(), c_cell.clone());
-                    sum_overflow = ov;
-                    c_cell = region.assign_advice(
-                        || "sum_hi",
-                        self.config.advice[3],
+                region.assign_advice(|| "a", self.config.update_value, 1, || a)?;
+
+                let sum = add_carry_limbs(
+                    a,
+                    &prev
+                        .iter()
+                        .map(|cell| cell.value().copied())
+                        .collect::<Vec<_>>(),
+                    MAX_BITS as usize,
+                );
+
+                // assigning the columns of accumulating value
+                let mut cells = ArrayVec::new();
+                for (i, limb) in sum.iter().enumerate() {
+                    cells.push(region.assign_advice(
+                        || format!("sum acc[{}]", i),
+                        self.config.accumulate[i],
                         1,
-                        || Value::known(hi),
-                    )?;
+                        || Value::known(*limb),
+                    )?);
                 }
 
-                let b_cell = region.assign_advice(
-                    || "sum_overflow",
-                    self.config.advice[2],
-                    1,
-                    || Value::known(sum_overflow),
-                )?;
-
                 // apply is_zero chip in here
-                let _is_overflow = is_zero_chip.assign(&mut region, 1, Value::known(hi));
+                let _is_overflow = is_zero_chip.assign(&mut region, 1, Value::known(sum[0]));
 
-                Ok((b_cell, c_cell, d_cell))
+                Ok(cells)
             },
         )
     }
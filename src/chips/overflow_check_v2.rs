@@ -5,23 +5,41 @@ use std::marker::PhantomData;
 use super::utils::{decompose_bigInt_to_ubits, value_f_to_big_uint};
 use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
+// The value is decomposed into ACC_COLS limbs of MAX_BITS bits. Each limb is decomposed again,
+// in the rows below it, into MAX_BITS / TABLE_BITS chunks of TABLE_BITS bits, which are looked up
+// in a table of 2^TABLE_BITS rows, so a wide limb doesn't require a table of 2^MAX_BITS rows.
+// With TABLE_BITS = MAX_BITS there is a single chunk per limb.
+//
+// | value | limb_0 (most significant) | limb_1  | selector | lookup_selector |
+// | --    | --                        | --      | --       | --              |
+// | v     | l_0                       | l_1     | 1        | 0               |
+// |       | l_0 chunk 0               | l_1 c_0 | 0        | 1               |
+// |       | l_0 chunk 1               | l_1 c_1 | 0        | 1               |
 #[derive(Debug, Clone)]
-pub struct OverflowCheckV2Config<const MAX_BITS: u8, const ACC_COLS: usize> {
+pub struct OverflowCheckV2Config<const MAX_BITS: u8, const ACC_COLS: usize, const TABLE_BITS: u8>
+{
     pub value: Column<Advice>,
     pub decomposed_values: [Column<Advice>; ACC_COLS],
     pub range: Column<Fixed>,
     pub instance: Column<Instance>,
     pub selector: Selector,
+    pub lookup_selector: Selector,
 }
 
 #[derive(Debug, Clone)]
-pub struct OverflowChipV2<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
-    config: OverflowCheckV2Config<MAX_BITS, ACC_COLS>,
+pub struct OverflowChipV2<const MAX_BITS: u8, const ACC_COLS: usize, const TABLE_BITS: u8, F: Field>
+{
+    config: OverflowCheckV2Config<MAX_BITS, ACC_COLS, TABLE_BITS>,
     _marker: PhantomData<F>,
 }
 
-impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> OverflowChipV2<MAX_BITS, ACC_COLS, F> {
-    pub fn construct(config: OverflowCheckV2Config<MAX_BITS, ACC_COLS>) -> Self {
+impl<const MAX_BITS: u8, const ACC_COLS: usize, const TABLE_BITS: u8, F: Field>
+    OverflowChipV2<MAX_BITS, ACC_COLS, TABLE_BITS, F>
+{
+    // number of chunks of TABLE_BITS bits in a limb
+    const CHUNKS: usize = (MAX_BITS / TABLE_BITS) as usize;
+
+    pub fn construct(config: OverflowCheckV2Config<MAX_BITS, ACC_COLS, TABLE_BITS>) -> Self {
         Self {
             config,
             _marker: PhantomData,
@@ -35,8 +53,19 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> OverflowChipV2<MAX_BIT
         range: Column<Fixed>,
         instance: Column<Instance>,
         selector: Selector,
-    ) -> OverflowCheckV2Config<MAX_BITS, ACC_COLS> {
+    ) -> OverflowCheckV2Config<MAX_BITS, ACC_COLS, TABLE_BITS> {
+        assert!(
+            TABLE_BITS > 0 && MAX_BITS % TABLE_BITS == 0,
+            "MAX_BITS = {} must be a multiple of TABLE_BITS = {}",
+            MAX_BITS,
+            TABLE_BITS
+        );
+
+        let lookup_selector = meta.complex_selector();
+
+        meta.enable_equality(value);
         decomposed_values.map(|col| meta.enable_equality(col));
+        meta.enable_equality(instance);
 
         meta.create_gate("equality check between decomposed value and value", |meta| {
             let s_doc = meta.query_selector(selector);
@@ -58,13 +87,36 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> OverflowChipV2<MAX_BIT
             vec![s_doc.clone() * (decomposed_value_sum - value)]
         });
 
-        meta.annotate_lookup_any_column(range, || "LOOKUP_MAXBITS_RANGE");
+        // every limb is the sum of its chunks, little-endian, in the rows below it
+        meta.create_gate("equality check between limb and its chunks", |meta| {
+            let s_doc = meta.query_selector(selector);
+
+            decomposed_values
+                .iter()
+                .map(|column| {
+                    let limb = meta.query_advice(*column, Rotation::cur());
+                    let chunks_sum = (0..Self::CHUNKS).fold(
+                        Expression::Constant(F::zero()),
+                        |acc, i| {
+                            let chunk = meta.query_advice(*column, Rotation(i as i32 + 1));
+                            acc + chunk
+                                * Expression::Constant(F::from(1 << (TABLE_BITS as usize * i)))
+                        },
+                    );
+                    s_doc.clone() * (chunks_sum - limb)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.annotate_lookup_any_column(range, || "LOOKUP_TABLEBITS_RANGE");
 
+        // 0 is in the table, so the lookups hold when the selector is off
         decomposed_values[0..ACC_COLS].iter().for_each(|column| {
-            meta.lookup_any("range check for MAXBITS", |meta| {
+            meta.lookup_any("range check for TABLEBITS", |meta| {
+                let s = meta.query_selector(lookup_selector);
                 let cell = meta.query_advice(*column, Rotation::cur());
                 let range = meta.query_fixed(range, Rotation::cur());
-                vec![(cell, range)]
+                vec![(s * cell, range)]
             });
         });
 
@@ -74,14 +126,17 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> OverflowChipV2<MAX_BIT
             range,
             instance,
             selector,
+            lookup_selector,
         }
     }
 
+    // Returns the value cell and the decomposed limb cells, the left most limb being the most significant one
+    #[allow(clippy::type_complexity)]
     pub fn assign(
         &self,
         mut layouter: impl Layouter<F>,
         update_value: Value<F>,
-    ) -> Result<(), Error> {
+    ) -> Result<(AssignedCell<F, F>, [AssignedCell<F, F>; ACC_COLS]), Error> {
         layouter.assign_region(
             || "assign decomposed values",
             |mut region| {
@@ -89,35 +144,62 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> OverflowChipV2<MAX_BIT
                 self.config.selector.enable(&mut region, 0)?;
 
                 // Assign input value to the cell inside the region
-                region.assign_advice(|| "assign value", self.config.value, 0, || update_value)?;
+                let value_cell = region.assign_advice(
+                    || "assign value",
+                    self.config.value,
+                    0,
+                    || update_value,
+                )?;
 
                 // Just used helper function for decomposing. In other halo2 application used functions based on Field.
                 let decomposed_values = decompose_bigInt_to_ubits(
                     &value_f_to_big_uint(update_value),
-                    MAX_BITS as usize,
                     ACC_COLS,
+                    MAX_BITS as usize,
+                ) as Vec<F>;
+                let chunks = decompose_bigInt_to_ubits(
+                    &value_f_to_big_uint(update_value),
+                    ACC_COLS * Self::CHUNKS,
+                    TABLE_BITS as usize,
                 ) as Vec<F>;
 
+                for row in 1..=Self::CHUNKS {
+                    self.config.lookup_selector.enable(&mut region, row)?;
+                }
+
                 // Note that, decomposed result is little edian. So, we need to reverse it.
+                let mut decomposed_cells = vec![];
                 for (idx, val) in decomposed_values.iter().rev().enumerate() {
-                    let _cell = region.assign_advice(
+                    let cell = region.assign_advice(
                         || format!("assign decomposed[{}] col", idx),
                         self.config.decomposed_values[idx],
                         0,
                         || Value::known(*val),
                     )?;
+                    decomposed_cells.push(cell);
+
+                    // the chunks of the limb are little-endian as well
+                    let limb_index = ACC_COLS - 1 - idx;
+                    for i in 0..Self::CHUNKS {
+                        region.assign_advice(
+                            || format!("assign decomposed[{}] chunk {}", idx, i),
+                            self.config.decomposed_values[idx],
+                            i + 1,
+                            || Value::known(chunks[limb_index * Self::CHUNKS + i]),
+                        )?;
+                    }
                 }
 
-                Ok(())
+                Ok((value_cell, decomposed_cells.try_into().unwrap()))
             },
         )
     }
 
     pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        let range = 1 << (MAX_BITS as usize);
+        let range = 1 << (TABLE_BITS as usize);
 
         layouter.assign_region(
-            || format!("load range check table of {} bits", MAX_BITS),
+            || format!("load range check table of {} bits", TABLE_BITS),
             |mut region| {
                 for i in 0..range {
                     region.assign_fixed(
@@ -8,7 +8,13 @@ is already implemented in halo2_gadgets, there is no wrapper chip that makes it
 
 use halo2_gadgets::poseidon::{primitives::*, Hash, Pow5Chip, Pow5Config};
 use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
-use std::marker::PhantomData;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use super::{
+    check_params,
+    sponge::{DomainLength, PoseidonSponge},
+};
 
 #[derive(Debug, Clone)]
 
@@ -47,6 +53,8 @@ impl<F: FieldExt, S: Spec<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize
         meta: &mut ConstraintSystem<F>,
         hash_inputs: Vec<Column<Advice>>,
     ) -> PoseidonConfig<F, WIDTH, RATE, L> {
+        check_params::<WIDTH, RATE, L>(hash_inputs.len());
+
         let partial_sbox = meta.advice_column();
         let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
         let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
@@ -88,4 +96,22 @@ impl<F: FieldExt, S: Spec<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize
         hasher.hash(layouter.namespace(|| "hash"), input_cells)
     }
 
+    // Returns a sponge sharing the pow5_chip of this chip, to hash an input absorbed in chunks.
+    // The domain D replaces L, e.g. `ConstantLength<N>` to hash N cells
+    pub fn sponge<D: Domain<F, RATE> + DomainLength>(
+        &self,
+        layouter: impl Layouter<F>,
+    ) -> Result<PoseidonSponge<F, S, D, WIDTH, RATE>, Error> {
+        PoseidonSponge::new(self.config.pow5_config.clone(), layouter)
+    }
+
+}
+
+// Number of advice and fixed columns allocated by `PoseidonChip::configure`, to tell the share of the layout taken by
+// the poseidon chips of a circuit: the WIDTH hash inputs passed by the caller plus the partial_sbox column, and the
+// rc_a and rc_b round constant columns, WIDTH each. The selectors of the Pow5Chip are not counted
+pub fn poseidon_footprint<const WIDTH: usize>() -> (usize, usize) {
+    let advice_cols = WIDTH + 1;
+    let fixed_cols = 2 * WIDTH;
+    (advice_cols, fixed_cols)
 }
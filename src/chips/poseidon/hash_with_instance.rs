@@ -8,7 +8,10 @@ is already implemented in halo2_gadgets, there is no wrapper chip that makes it
 
 use halo2_gadgets::poseidon::{primitives::*, Hash, Pow5Chip, Pow5Config};
 use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
-use std::marker::PhantomData;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use super::check_params;
 
 #[derive(Debug, Clone)]
 
@@ -50,6 +53,8 @@ impl<F: FieldExt, S: Spec<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize
         hash_inputs: Vec<Column<Advice>>,
         instance: Column<Instance>,
     ) -> PoseidonConfig<F, WIDTH, RATE, L> {
+        check_params::<WIDTH, RATE, L>(hash_inputs.len());
+
         let partial_sbox = meta.advice_column();
         let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
         let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
@@ -138,6 +143,53 @@ impl<F: FieldExt, S: Spec<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize
         hasher.hash(layouter.namespace(|| "hash"), hash_input_cells)
     }
 
+    // Hashes the already assigned `cells` followed by the `fresh` values, which are assigned here.
+    // Together they must be the L inputs of the hash, the cells can come from any region of the circuit
+    pub fn hash_mixed(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+        fresh: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert_eq!(
+            cells.len() + fresh.len(),
+            L,
+            "poseidon chip requires L = {} inputs, got {} cells and {} fresh values",
+            L,
+            cells.len(),
+            fresh.len()
+        );
+
+        let fresh_cells = layouter.assign_region(
+            || "load fresh inputs",
+            |mut region| {
+                fresh
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| {
+                        region.assign_advice(
+                            || "fresh input",
+                            self.config.hash_inputs[cells.len() + i],
+                            0,
+                            || x.to_owned(),
+                        )
+                    })
+                    .collect::<Result<Vec<AssignedCell<F, F>>, Error>>()
+            },
+        )?;
+
+        // the carried cells and the fresh ones are copied into the hash input cells by `hash`
+        let input_cells: [AssignedCell<F, F>; L] = cells
+            .iter()
+            .cloned()
+            .chain(fresh_cells)
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        self.hash(layouter.namespace(|| "hash mixed inputs"), &input_cells)
+    }
+
     pub fn expose_public(
         &self,
         mut layouter: impl Layouter<F>,
@@ -1,3 +1,29 @@
 pub mod hash_with_instance;
 pub mod hash;
 pub mod spec;
+pub mod sponge;
+
+// Checks the parameters required by the Pow5Chip, so that a misconfiguration panics with a clear message
+// instead of failing on the conversion of the columns into an array
+pub(crate) fn check_params<const WIDTH: usize, const RATE: usize, const L: usize>(
+    num_hash_inputs: usize,
+) {
+    assert_eq!(
+        num_hash_inputs, WIDTH,
+        "poseidon chip requires WIDTH = {} hash input columns, got {}",
+        WIDTH, num_hash_inputs
+    );
+    assert_eq!(
+        RATE,
+        WIDTH - 1,
+        "poseidon chip requires RATE = WIDTH - 1 = {}, got {}",
+        WIDTH - 1,
+        RATE
+    );
+    assert!(
+        L < WIDTH,
+        "poseidon chip requires the number of inputs L = {} to be less than WIDTH = {}",
+        L,
+        WIDTH
+    );
+}
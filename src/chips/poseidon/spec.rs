@@ -1,6 +1,8 @@
 use halo2_gadgets::poseidon::primitives::*;
 use halo2_proofs::{arithmetic::FieldExt};
-use std::marker::PhantomData;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::marker::PhantomData;
 
 // P128Pow5T3 is the default Spec provided by the Halo2 Gadget => https://github.com/privacy-scaling-explorations/halo2/blob/main/halo2_gadgets/src/poseidon/primitives/p128pow5t3.rs#L13
 // This spec hardcodes the WIDTH and RATE parameters of the hash function to 3 and 2 respectively
@@ -30,3 +32,73 @@ impl<F: FieldExt, const WIDTH: usize, const RATE: usize> Spec<F, WIDTH, RATE> fo
         0
     }
 }
+
+// The round counts and the constants of a `ConfigurableSpec`. By default the constants are generated with the
+// grain LFSR from the round counts, in the same way as for `MySpec`, an implementation can override them.
+// Note that the Pow5Chip requires an even number of full rounds and of partial rounds.
+pub trait SpecParams<F: FieldExt, const WIDTH: usize, const RATE: usize>: Debug + Clone + Copy {
+    const FULL_ROUNDS: usize;
+    const PARTIAL_ROUNDS: usize;
+
+    fn constants() -> (Vec<[F; WIDTH]>, Mds<F, WIDTH>, Mds<F, WIDTH>) {
+        GrainSpec::<F, Self, WIDTH, RATE>::constants()
+    }
+}
+
+// Poseidon spec whose round counts and constants come from the params P, so that the hash function can be
+// instantiated with a different security level. Reducing the number of rounds makes the hash insecure,
+// it's only meant for experiments on the performance of the circuit.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigurableSpec<F: FieldExt, P: SpecParams<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize>{
+    _marker: PhantomData<(F, P)>
+}
+
+impl<F: FieldExt, P: SpecParams<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize> Spec<F, WIDTH, RATE>
+    for ConfigurableSpec<F, P, WIDTH, RATE>
+{
+    fn full_rounds() -> usize {
+        P::FULL_ROUNDS
+    }
+
+    fn partial_rounds() -> usize {
+        P::PARTIAL_ROUNDS
+    }
+
+    fn sbox(val: F) -> F {
+        val.pow_vartime(&[5])
+    }
+
+    fn secure_mds() -> usize {
+        0
+    }
+
+    fn constants() -> (Vec<[F; WIDTH]>, Mds<F, WIDTH>, Mds<F, WIDTH>) {
+        P::constants()
+    }
+}
+
+// Only used to generate the default constants of the params, it doesn't override `Spec::constants`
+#[derive(Debug)]
+struct GrainSpec<F: FieldExt, P: SpecParams<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize>{
+    _marker: PhantomData<(F, P)>
+}
+
+impl<F: FieldExt, P: SpecParams<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize> Spec<F, WIDTH, RATE>
+    for GrainSpec<F, P, WIDTH, RATE>
+{
+    fn full_rounds() -> usize {
+        P::FULL_ROUNDS
+    }
+
+    fn partial_rounds() -> usize {
+        P::PARTIAL_ROUNDS
+    }
+
+    fn sbox(val: F) -> F {
+        val.pow_vartime(&[5])
+    }
+
+    fn secure_mds() -> usize {
+        0
+    }
+}
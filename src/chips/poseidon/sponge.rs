@@ -0,0 +1,90 @@
+// A streaming wrapper around the sponge of halo2_gadgets. Compared to `PoseidonChip::hash`, the input doesn't have to be
+// known in a single array of L cells, it can be absorbed in chunks across many regions and squeezed at the end.
+// The sponge state is kept by the wrapper between absorbs, the domain D defines the initial capacity and the padding.
+
+use halo2_gadgets::poseidon::{
+    primitives::{Absorbing, ConstantLength, Domain, Spec},
+    PaddedWord, Pow5Chip, Pow5Config, Sponge,
+};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+// Number of message cells a domain hashes. The padding of `ConstantLength` panics on any other length,
+// so the sponge checks the number of absorbed cells against it before squeezing
+pub trait DomainLength {
+    fn length() -> usize;
+}
+
+impl<const L: usize> DomainLength for ConstantLength<L> {
+    fn length() -> usize {
+        L
+    }
+}
+
+pub struct PoseidonSponge<
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE>,
+    D: Domain<F, RATE> + DomainLength,
+    const WIDTH: usize,
+    const RATE: usize,
+> {
+    sponge: Sponge<F, Pow5Chip<F, WIDTH, RATE>, S, Absorbing<PaddedWord<F>, RATE>, D, WIDTH, RATE>,
+    // number of message cells absorbed so far, used to compute the padding
+    absorbed: usize,
+}
+
+impl<
+        F: FieldExt,
+        S: Spec<F, WIDTH, RATE>,
+        D: Domain<F, RATE> + DomainLength,
+        const WIDTH: usize,
+        const RATE: usize,
+    > PoseidonSponge<F, S, D, WIDTH, RATE>
+{
+    pub fn new(
+        pow5_config: Pow5Config<F, WIDTH, RATE>,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<Self, Error> {
+        let pow5_chip = Pow5Chip::construct(pow5_config);
+        let sponge = Sponge::new(pow5_chip, layouter.namespace(|| "init sponge"))?;
+
+        Ok(Self {
+            sponge,
+            absorbed: 0,
+        })
+    }
+
+    // Absorbs the cells in order, the permutation is applied each time RATE cells have been absorbed
+    pub fn absorb(
+        &mut self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        for cell in cells {
+            self.sponge.absorb(
+                layouter.namespace(|| format!("absorb_{}", self.absorbed)),
+                PaddedWord::Message(cell.clone()),
+            )?;
+            self.absorbed += 1;
+        }
+        Ok(())
+    }
+
+    // Absorbs the padding of the domain and returns the first squeezed element.
+    // Fails with `Error::Synthesis` if the number of absorbed cells differs from the length of the domain
+    pub fn squeeze(mut self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        if self.absorbed != D::length() {
+            return Err(Error::Synthesis);
+        }
+
+        for (i, padding) in D::padding(self.absorbed).into_iter().enumerate() {
+            self.sponge.absorb(
+                layouter.namespace(|| format!("absorb_padding_{}", i)),
+                PaddedWord::Padding(padding),
+            )?;
+        }
+
+        self.sponge
+            .finish_absorbing(layouter.namespace(|| "finish absorbing"))?
+            .squeeze(layouter.namespace(|| "squeeze"))
+    }
+}
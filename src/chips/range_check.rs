@@ -0,0 +1,118 @@
+use eth_types::Field;
+use std::marker::PhantomData;
+
+use super::utils::{decompose_bigInt_to_ubits, value_f_to_big_uint};
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+// The chip enforces that the value in the `value` column is less than 2^MAX_BITS.
+// The value is decomposed into MAX_BITS / 8 bytes, each of them is looked up in a fixed u8 table.
+// value = byte_0 + byte_1 * 2^8 + ... + byte_{n-1} * 2^(8 * (n - 1))
+#[derive(Debug, Clone)]
+pub struct RangeCheckConfig<F: Field, const MAX_BITS: usize> {
+    pub value: Column<Advice>,
+    pub bytes: Vec<Column<Advice>>,
+    pub u8_table: Column<Fixed>,
+    pub selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RangeCheckChip<F: Field, const MAX_BITS: usize> {
+    config: RangeCheckConfig<F, MAX_BITS>,
+}
+
+impl<F: Field, const MAX_BITS: usize> RangeCheckChip<F, MAX_BITS> {
+    pub fn construct(config: RangeCheckConfig<F, MAX_BITS>) -> Self {
+        Self { config }
+    }
+
+    // `value` is the column of the value to check, it can be shared with other chips
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+    ) -> RangeCheckConfig<F, MAX_BITS> {
+        assert_eq!(MAX_BITS % 8, 0, "MAX_BITS must be a multiple of 8");
+
+        let selector = meta.complex_selector();
+        let bytes = (0..MAX_BITS / 8)
+            .map(|_| meta.advice_column())
+            .collect::<Vec<_>>();
+        let u8_table = meta.fixed_column();
+
+        meta.create_gate("range check decomposition", |meta| {
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(value, Rotation::cur());
+
+            let recomposed =
+                bytes
+                    .iter()
+                    .rev()
+                    .fold(Expression::Constant(F::zero()), |acc, byte| {
+                        acc * Expression::Constant(F::from(1 << 8))
+                            + meta.query_advice(*byte, Rotation::cur())
+                    });
+
+            vec![s * (value - recomposed)]
+        });
+
+        for byte in bytes.iter() {
+            meta.lookup_any("range check for bytes", |meta| {
+                let s = meta.query_selector(selector);
+                let byte = meta.query_advice(*byte, Rotation::cur());
+                vec![(s * byte, meta.query_fixed(u8_table, Rotation::cur()))]
+            });
+        }
+        meta.annotate_lookup_any_column(u8_table, || "LOOKUP_u8");
+
+        RangeCheckConfig {
+            value,
+            bytes,
+            u8_table,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    // Load the u8 table
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "load u8 table",
+            |mut region| {
+                for i in 0..256 {
+                    region.assign_fixed(
+                        || "u8 table",
+                        self.config.u8_table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    // Assign the bytes of `value`, which must be already assigned to the value column at `offset`
+    // If the value is not less than 2^MAX_BITS, the bytes don't recompose to the value
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<(), Error> {
+        self.config.selector.enable(region, offset)?;
+
+        let bytes: Vec<F> =
+            decompose_bigInt_to_ubits(&value_f_to_big_uint(value), self.config.bytes.len(), 8);
+
+        for (i, byte) in bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("byte {}", i),
+                self.config.bytes[i],
+                offset,
+                || Value::known(*byte),
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use eth_types::Field;
+use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+// The chip proves that `min <= value <= max` using two LtChip configurations.
+// Since LtChip only provides a strict less than, the bounds are shifted by one:
+// - `lower` checks `min < value + 1`
+// - `upper` checks `value < max + 1`
+// Both `is_lt` flags are then forced to 1 by a custom gate.
+#[derive(Debug, Clone)]
+pub struct RangeProofConfig<F: Field, const N_BYTES: usize> {
+    pub value: Column<Advice>,
+    pub min: Column<Advice>,
+    pub max: Column<Advice>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub lower: LtConfig<F, N_BYTES>,
+    pub upper: LtConfig<F, N_BYTES>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RangeProofChip<F: Field, const N_BYTES: usize> {
+    config: RangeProofConfig<F, N_BYTES>,
+}
+
+impl<F: Field, const N_BYTES: usize> RangeProofChip<F, N_BYTES> {
+    pub fn construct(config: RangeProofConfig<F, N_BYTES>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        min: Column<Advice>,
+        max: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> RangeProofConfig<F, N_BYTES> {
+        let selector = meta.complex_selector();
+
+        meta.enable_equality(value);
+        meta.enable_equality(min);
+        meta.enable_equality(max);
+        meta.enable_equality(instance);
+
+        let lower = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(min, Rotation::cur()),
+            |meta| meta.query_advice(value, Rotation::cur()) + Expression::Constant(F::one()),
+        );
+
+        let upper = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            |meta| meta.query_advice(max, Rotation::cur()) + Expression::Constant(F::one()),
+        );
+
+        // Enforces that both `min < value + 1` and `value < max + 1` hold
+        meta.create_gate("value is in [min, max]", |meta| {
+            let s = meta.query_selector(selector);
+            vec![
+                s.clone() * (lower.is_lt(meta, None) - Expression::Constant(F::one())),
+                s * (upper.is_lt(meta, None) - Expression::Constant(F::one())),
+            ]
+        });
+
+        RangeProofConfig {
+            value,
+            min,
+            max,
+            selector,
+            instance,
+            lower,
+            upper,
+        }
+    }
+
+    // Load the u8 lookup tables used by both LtChip configurations
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        LtChip::construct(self.config.lower).load(layouter)?;
+        LtChip::construct(self.config.upper).load(layouter)
+    }
+
+    // Returns the assigned value, min and max cells
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: F,
+        min: F,
+        max: F,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let lower_chip = LtChip::construct(self.config.lower);
+        let upper_chip = LtChip::construct(self.config.upper);
+
+        layouter.assign_region(
+            || "range proof",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                let value_cell = region.assign_advice(
+                    || "value",
+                    self.config.value,
+                    0,
+                    || Value::known(value),
+                )?;
+                let min_cell =
+                    region.assign_advice(|| "min", self.config.min, 0, || Value::known(min))?;
+                let max_cell =
+                    region.assign_advice(|| "max", self.config.max, 0, || Value::known(max))?;
+
+                lower_chip.assign(&mut region, 0, min, value + F::one())?;
+                upper_chip.assign(&mut region, 0, value, max + F::one())?;
+
+                Ok((value_cell, min_cell, max_cell))
+            },
+        )
+    }
+
+    // Enforce permutation check between input cell and instance column at row passed as input
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
@@ -4,10 +4,9 @@ use num_bigint::BigUint;
 use std::char::MAX;
 use std::fmt::Debug;
 
+use super::gadgets::assert_boolean;
 use super::is_zero::{IsZeroChip, IsZeroConfig};
-use super::utils::{
-    decompose_bigInt_to_ubits, f_to_big_uint, range_check, range_check_vec, value_f_to_big_uint,
-};
+use super::utils::{decompose_bigInt_to_ubits, f_to_big_uint, value_f_to_big_uint};
 use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
 
 #[derive(Debug, Clone)]
@@ -19,6 +18,11 @@ pub struct SafeAccumulatorConfig<const MAX_BITS: u8, const ACC_COLS: usize, F: F
     pub instance: Column<Instance>,
     pub is_zero: IsZeroConfig<F>,
     pub selector: [Selector; 2],
+    pub bool_selector: Selector,
+    pub recompose_selector: Selector,
+    pub limb_selector: Selector,
+    pub range_selector: Selector,
+    pub range: Column<Fixed>,
 }
 
 #[derive(Debug, Clone)]
@@ -53,10 +57,19 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
             left_most_inv,
         );
 
+        let recompose_selector = meta.selector();
+
+        // the range checks are lookups, so they need a complex selector
+        let range_selector = meta.complex_selector();
+        let range = meta.fixed_column();
+
         // Enable equality on the advice and instance column to enable permutation check
         accumulate.map(|col| meta.enable_equality(col));
         add_carries.map(|col| meta.enable_equality(col));
 
+        // the recomposed accumulator is assigned to the update_value column
+        meta.enable_equality(update_value);
+
         meta.enable_equality(instance);
 
         meta.create_gate("bool constraint", |meta| {
@@ -66,7 +79,7 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
 
             for carries in add_carries {
                 let a = meta.query_advice(carries, Rotation::cur());
-                exprs.push(s.clone() * a.clone() * (Expression::Constant(F::from(1)) - a));
+                exprs.push(s.clone() * assert_boolean(a));
             }
 
             exprs
@@ -115,7 +128,6 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
                         - ((carries_acc[ACC_COLS - 1].clone() * shift_next_chunk.clone())
                             + updated_acc[ACC_COLS - 1].clone())),
             ];
-            let check_range_add_value = vec![s_add.clone() * range_check(value, 1 << MAX_BITS)];
 
             // Check with other accumulation columns with carries
             //
@@ -152,15 +164,84 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
 
             [
                 check_add_value_exprs,
-                check_range_add_value,
                 check_accumulates_with_carries_expr,
                 check_overflow_expr,
-                range_check_vec(&s_over, previous_acc, 1 << MAX_BITS),
-                range_check_vec(&s_over, updated_acc, 1 << MAX_BITS),
             ]
             .concat()
         });
 
+        // The new value, the previous and the updated accumulate columns are range checked to be less than 2^MAX_BITS.
+        // Lookups into a fixed table keep the degree of the constraints low,
+        // while a polynomial range check would have a degree of 2^MAX_BITS
+        meta.annotate_lookup_any_column(range, || "LOOKUP_MAXBITS_RANGE");
+
+        meta.lookup_any("range check for new value", |meta| {
+            let s = meta.query_selector(range_selector);
+            let value = meta.query_advice(update_value, Rotation::cur());
+            vec![(s * value, meta.query_fixed(range, Rotation::cur()))]
+        });
+
+        for column in accumulate.iter() {
+            meta.lookup_any("range check for previous accumulate", |meta| {
+                let s = meta.query_selector(range_selector);
+                let previous = meta.query_advice(*column, Rotation::prev());
+                vec![(s * previous, meta.query_fixed(range, Rotation::cur()))]
+            });
+            meta.lookup_any("range check for updated accumulate", |meta| {
+                let s = meta.query_selector(range_selector);
+                let updated = meta.query_advice(*column, Rotation::cur());
+                vec![(s * updated, meta.query_fixed(range, Rotation::cur()))]
+            });
+        }
+
+        // Enforces that the value is equal to the weighted sum of the accumulate columns
+        // value = accumulate_0 + accumulate_1 * 2^MAX_BITS + ... + accumulate_{ACC_COLS - 1} * 2^(MAX_BITS * (ACC_COLS - 1))
+        // where accumulate_0 is the right most column
+        meta.create_gate("recompose constraint", |meta| {
+            let s = meta.query_selector(recompose_selector);
+            let value = meta.query_advice(update_value, Rotation::cur());
+
+            let shift_next_chunk = Expression::Constant(F::from(1 << MAX_BITS));
+            let recomposed = accumulate
+                .iter()
+                .fold(Expression::Constant(F::zero()), |acc, col| {
+                    acc * shift_next_chunk.clone() + meta.query_advice(*col, Rotation::cur())
+                });
+
+            vec![s * (recomposed - value)]
+        });
+
+        // Adds a value decomposed into limbs, see `assign_limbs_from_cell_chained`. The previous accumulate is two
+        // rows above and the limbs are on the previous row, every limb is added to the accumulate column of the
+        // same weight. The left most column has no outgoing carry, so the sum can't drop a carry out of it
+        let limb_selector = meta.selector();
+        meta.create_gate("limb accumulation constraint", |meta| {
+            let s = meta.query_selector(limb_selector);
+            let shift_next_chunk = Expression::Constant(F::from(1 << MAX_BITS));
+
+            (0..ACC_COLS)
+                .map(|i| {
+                    let previous = meta.query_advice(accumulate[i], Rotation(-2));
+                    let limb = meta.query_advice(accumulate[i], Rotation::prev());
+                    let updated = meta.query_advice(accumulate[i], Rotation::cur());
+
+                    let carry_out = if i > 0 {
+                        meta.query_advice(add_carries[i], Rotation::cur())
+                            * shift_next_chunk.clone()
+                    } else {
+                        Expression::Constant(F::zero())
+                    };
+                    let carry_in = if i < ACC_COLS - 1 {
+                        meta.query_advice(add_carries[i + 1], Rotation::cur())
+                    } else {
+                        Expression::Constant(F::zero())
+                    };
+
+                    s.clone() * ((updated + carry_out) - (previous + limb + carry_in))
+                })
+                .collect::<Vec<Expression<F>>>()
+        });
+
         SafeAccumulatorConfig {
             update_value,
             left_most_inv,
@@ -168,17 +249,276 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
             accumulate,
             instance,
             selector: [add_carry_selector, overflow_check_selector],
+            bool_selector,
             is_zero,
+            recompose_selector,
+            limb_selector,
+            range_selector,
+            range,
         }
     }
 
-    pub fn assign(
+    // Load the range table, from 0 to 2^MAX_BITS - 1
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range = 1 << (MAX_BITS as usize);
+
+        layouter.assign_region(
+            || format!("load range check table of {} bits", MAX_BITS),
+            |mut region| {
+                for i in 0..range {
+                    region.assign_fixed(
+                        || "assign cell in fixed column",
+                        self.config.range,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    // Assigns zero cells from the constant column into the accumulate columns, the start of a chain of
+    // `assign_from_cell_chained`. The cells are little-endian like the accumulate cells returned by `assign`.
+    // It requires a constant column enabled by the parent circuit with `enable_constant`
+    pub fn assign_zero_accumulate(
         &self,
         mut layouter: impl Layouter<F>,
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
+        layouter.assign_region(
+            || "zero accumulation",
+            |mut region| {
+                let mut cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
+                for i in 0..ACC_COLS {
+                    cells.push(region.assign_advice_from_constant(
+                        || format!("zero accumulate[{}]", ACC_COLS - 1 - i),
+                        self.config.accumulate[ACC_COLS - 1 - i],
+                        0,
+                        F::zero(),
+                    )?);
+                }
+                Ok(cells)
+            },
+        )
+    }
+
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        offset: usize,
+        update_value: Value<F>,
+        accumulated_values: [Value<F>; ACC_COLS],
+    ) -> Result<(ArrayVec<AssignedCell<F, F>, ACC_COLS>, [Value<F>; ACC_COLS]), Error> {
+        let (assigned_cells, _, updated_accumulates) = self.assign_update(
+            layouter,
+            offset,
+            update_value,
+            None,
+            None,
+            accumulated_values,
+        )?;
+        Ok((assigned_cells, updated_accumulates))
+    }
+
+    // Same as `assign`, but the carry cells are returned as well, between the accumulate cells and the updated values.
+    // Carry cells are little-endian like the accumulate cells, so a parent circuit can constrain or expose them
+    #[allow(clippy::type_complexity)]
+    pub fn assign_with_carries(
+        &self,
+        layouter: impl Layouter<F>,
         offset: usize,
         update_value: Value<F>,
         accumulated_values: [Value<F>; ACC_COLS],
+    ) -> Result<
+        (
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+            [Value<F>; ACC_COLS],
+        ),
+        Error,
+    > {
+        self.assign_update(
+            layouter,
+            offset,
+            update_value,
+            None,
+            None,
+            accumulated_values,
+        )
+    }
+
+    // Same as `assign`, but the value to add is copied from a cell assigned by another chip
+    pub fn assign_from_cell(
+        &self,
+        layouter: impl Layouter<F>,
+        offset: usize,
+        update_cell: &AssignedCell<F, F>,
+        accumulated_values: [Value<F>; ACC_COLS],
+    ) -> Result<(ArrayVec<AssignedCell<F, F>, ACC_COLS>, [Value<F>; ACC_COLS]), Error> {
+        let (assigned_cells, _, updated_accumulates) = self.assign_update(
+            layouter,
+            offset,
+            update_cell.value().copied(),
+            Some(update_cell),
+            None,
+            accumulated_values,
+        )?;
+        Ok((assigned_cells, updated_accumulates))
+    }
+
+    // Same as `assign_from_cell`, but the previous accumulation is copied from the accumulate cells returned by the
+    // previous call instead of being assigned from values, so consecutive updates can't be witnessed independently
+    pub fn assign_from_cell_chained(
+        &self,
+        layouter: impl Layouter<F>,
+        offset: usize,
+        update_cell: &AssignedCell<F, F>,
+        accumulated_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
     ) -> Result<(ArrayVec<AssignedCell<F, F>, ACC_COLS>, [Value<F>; ACC_COLS]), Error> {
+        // cells are little-endian, while the values follow the order of the columns
+        let mut accumulated_values = [Value::unknown(); ACC_COLS];
+        for (i, cell) in accumulated_cells.iter().enumerate() {
+            accumulated_values[ACC_COLS - 1 - i] = cell.value().copied();
+        }
+
+        let (assigned_cells, _, updated_accumulates) = self.assign_update(
+            layouter,
+            offset,
+            update_cell.value().copied(),
+            Some(update_cell),
+            Some(accumulated_cells),
+            accumulated_values,
+        )?;
+        Ok((assigned_cells, updated_accumulates))
+    }
+
+    // Same as `assign_from_cell_chained`, but the value to add can be wider than MAX_BITS, e.g. a balance of a merkle
+    // sum tree. The value is decomposed into ACC_COLS limbs of MAX_BITS bits, bound to the value cell by the recompose
+    // constraint, and every limb is added to the accumulate column of the same weight. The limbs are range checked
+    // as the previous accumulate of the update row, so the value must be less than 2^(MAX_BITS * ACC_COLS)
+    //
+    // | row | update_value | add_carries | accumulate          | selectors                                        |
+    // | --  | --           | --          | --                  | --                                               |
+    // | 0   |              |             | previous accumulate |                                                  |
+    // | 1   | value        |             | limbs of value      | recompose                                        |
+    // | 2   | 0            | carries     | updated accumulate  | limb accumulation, overflow check, bool, range   |
+    pub fn assign_limbs_from_cell_chained(
+        &self,
+        mut layouter: impl Layouter<F>,
+        update_cell: &AssignedCell<F, F>,
+        accumulated_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
+        let mut value = F::zero();
+        update_cell.value().map(|v| value = *v);
+
+        // both the limbs and the accumulate cells are little-endian
+        let limbs: Vec<F> =
+            decompose_bigInt_to_ubits(&f_to_big_uint(&value), ACC_COLS, MAX_BITS as usize);
+        let previous = accumulated_cells
+            .iter()
+            .map(|cell| {
+                let mut v = F::zero();
+                cell.value().map(|c| v = *c);
+                v
+            })
+            .collect::<Vec<F>>();
+
+        let is_zero_chip = IsZeroChip::construct(self.config.is_zero.clone());
+        layouter.assign_region(
+            || "accumulate limbs",
+            |mut region| {
+                self.config.recompose_selector.enable(&mut region, 1)?;
+                self.config.limb_selector.enable(&mut region, 2)?;
+                self.config.selector[1].enable(&mut region, 2)?;
+                self.config.bool_selector.enable(&mut region, 2)?;
+                self.config.range_selector.enable(&mut region, 2)?;
+
+                update_cell.copy_advice(
+                    || "copy value to decompose",
+                    &mut region,
+                    self.config.update_value,
+                    1,
+                )?;
+                // the update value of the range checked row is not used by the limb accumulation
+                region.assign_advice(
+                    || "assign no value for adding",
+                    self.config.update_value,
+                    2,
+                    || Value::known(F::zero()),
+                )?;
+
+                let left_most_idx = ACC_COLS - 1;
+                let mut partial_sum = BigUint::from(0u8);
+                let mut updated_cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
+                for (i, cell) in accumulated_cells.iter().enumerate() {
+                    let column = self.config.accumulate[left_most_idx - i];
+                    cell.copy_advice(
+                        || format!("copy previous accumulate[{}] col", left_most_idx - i),
+                        &mut region,
+                        column,
+                        0,
+                    )?;
+                    region.assign_advice(
+                        || format!("assign limb[{}]", left_most_idx - i),
+                        column,
+                        1,
+                        || Value::known(limbs[i]),
+                    )?;
+
+                    // the carry out of the column i is the bit above the sum of the lower i + 1 columns
+                    let shift_bits = MAX_BITS as usize * i;
+                    partial_sum +=
+                        (f_to_big_uint(&previous[i]) + f_to_big_uint(&limbs[i])) << shift_bits;
+                    let carry_mask = BigUint::from(1u8) << (shift_bits + MAX_BITS as usize);
+                    let carry = if partial_sum >= carry_mask && i < left_most_idx {
+                        F::one()
+                    } else {
+                        F::zero()
+                    };
+                    region.assign_advice(
+                        || format!("assign carried value at [{}]", left_most_idx - i),
+                        self.config.add_carries[left_most_idx - i],
+                        2,
+                        || Value::known(carry),
+                    )?;
+                }
+
+                // as in `assign_update`, a sum overflowing the left most column fails the overflow check
+                let updated: Vec<F> =
+                    decompose_bigInt_to_ubits(&partial_sum, ACC_COLS, MAX_BITS as usize);
+                is_zero_chip.assign(&mut region, 2, Value::known(updated[left_most_idx]))?;
+
+                for (i, v) in updated.iter().enumerate() {
+                    updated_cells.push(region.assign_advice(
+                        || format!("assign updated value to accumulated[{}]", left_most_idx - i),
+                        self.config.accumulate[left_most_idx - i],
+                        2,
+                        || Value::known(*v),
+                    )?);
+                }
+
+                Ok(updated_cells)
+            },
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn assign_update(
+        &self,
+        mut layouter: impl Layouter<F>,
+        offset: usize,
+        update_value: Value<F>,
+        update_cell: Option<&AssignedCell<F, F>>,
+        accumulated_cells: Option<&ArrayVec<AssignedCell<F, F>, ACC_COLS>>,
+        accumulated_values: [Value<F>; ACC_COLS],
+    ) -> Result<
+        (
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+            [Value<F>; ACC_COLS],
+        ),
+        Error,
+    > {
         let mut sum = F::zero();
         update_value.as_ref().map(|f| sum = sum.add(f));
 
@@ -189,28 +529,58 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
                 // enable selector
                 self.config.selector[0].enable(&mut region, offset + 1)?;
                 self.config.selector[1].enable(&mut region, offset + 1)?;
+                // the carries are assigned on the same row, so they are constrained to be 0 or 1
+                self.config.bool_selector.enable(&mut region, offset + 1)?;
+                self.config.range_selector.enable(&mut region, offset + 1)?;
 
                 let mut sum_big_uint = f_to_big_uint(&sum);
 
                 // Assign new value to the cell inside the region
-                region.assign_advice(
-                    || "assign value for adding",
-                    self.config.update_value,
-                    1,
-                    || update_value,
-                )?;
+                match update_cell {
+                    Some(cell) => {
+                        cell.copy_advice(
+                            || "copy value for adding",
+                            &mut region,
+                            self.config.update_value,
+                            1,
+                        )?;
+                    }
+                    None => {
+                        region.assign_advice(
+                            || "assign value for adding",
+                            self.config.update_value,
+                            1,
+                            || update_value,
+                        )?;
+                    }
+                }
 
                 // Assign previous accumulation
-                for (idx, val) in accumulated_values.iter().enumerate() {
-                    let _ = region.assign_advice(
-                        || format!("assign previous accumulate[{}] col", idx),
-                        self.config.accumulate[idx],
-                        0,
-                        || *val,
-                    )?;
+                match accumulated_cells {
+                    Some(cells) => {
+                        for (i, cell) in cells.iter().enumerate() {
+                            cell.copy_advice(
+                                || format!("copy previous accumulate[{}] col", ACC_COLS - 1 - i),
+                                &mut region,
+                                self.config.accumulate[ACC_COLS - 1 - i],
+                                0,
+                            )?;
+                        }
+                    }
+                    None => {
+                        for (idx, val) in accumulated_values.iter().enumerate() {
+                            let _ = region.assign_advice(
+                                || format!("assign previous accumulate[{}] col", idx),
+                                self.config.accumulate[idx],
+                                0,
+                                || *val,
+                            )?;
+                        }
+                    }
                 }
 
-                // Calculates updated accumulate value
+                // Calculates updated accumulate value, from the right most column, so the carry cells are little-endian
+                let mut carry_cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
                 for (idx, acc_val) in accumulated_values.iter().enumerate().rev() {
                     let shift_bits = MAX_BITS as usize * ((ACC_COLS - 1) - idx);
                     sum_big_uint += value_f_to_big_uint(*acc_val) << shift_bits;
@@ -218,17 +588,18 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
                     // calculate carried sum and assign
                     // if `sum_big_uint` is higher than `1 << shift_bits` assign carried value 1
                     let mut carry_flag = F::zero();
-                    let shift_mask = BigUint::new(vec![1 << (MAX_BITS as usize + shift_bits)]);
+                    let shift_mask = BigUint::from(1u8) << (MAX_BITS as usize + shift_bits);
                     if sum_big_uint >= shift_mask && idx > 0 {
                         carry_flag = F::one();
                     }
 
-                    let _ = region.assign_advice(
+                    let carry_cell = region.assign_advice(
                         || format!("assign carried value at [{}]", idx),
                         self.config.add_carries[idx],
                         offset + 1,
                         || Value::known(carry_flag.clone()),
-                    );
+                    )?;
+                    carry_cells.push(carry_cell);
                 }
 
                 // decomposed result is little-endian, so the vector is opposite to the order of the columns
@@ -254,7 +625,43 @@ impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
                     updated_accumulates[left_most_idx - i] = Value::known(v.clone());
                 }
                 // query assgiend cells via region
-                Ok((assigned_cells, updated_accumulates))
+                Ok((assigned_cells, carry_cells, updated_accumulates))
+            },
+        )
+    }
+
+    // Recomposes the accumulate cells returned by `assign` into a single cell
+    // cells are little-endian, so cells[i] is weighted by 2^(MAX_BITS * i)
+    pub fn recompose_accumulator(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "recompose accumulator",
+            |mut region| {
+                self.config.recompose_selector.enable(&mut region, 0)?;
+
+                let left_most_idx = ACC_COLS - 1;
+                let mut recomposed = Value::known(F::zero());
+                for (i, cell) in cells.iter().enumerate() {
+                    cell.copy_advice(
+                        || format!("copy accumulate[{}]", left_most_idx - i),
+                        &mut region,
+                        self.config.accumulate[left_most_idx - i],
+                        0,
+                    )?;
+
+                    let shift = F::from(1 << MAX_BITS).pow_vartime(&[i as u64]);
+                    recomposed = recomposed + cell.value().map(|v| *v * shift);
+                }
+
+                region.assign_advice(
+                    || "assign recomposed accumulator",
+                    self.config.update_value,
+                    0,
+                    || recomposed,
+                )
             },
         )
     }
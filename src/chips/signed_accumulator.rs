@@ -0,0 +1,360 @@
+use arrayvec::ArrayVec;
+use eth_types::Field;
+
+use super::safe_accumulator::{SafeACcumulatorChip, SafeAccumulatorConfig};
+use super::utils::f_to_big_uint;
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+// The chip accumulates signed values. A negative value `-v` is the field element `p - v`.
+// Every value is split into its sign bit and its magnitude, the magnitude is added either to the positive
+// or to the negative accumulator, which are both `SafeACcumulatorChip`s, so both sums are range checked.
+// The balance is `positive_acc - negative_acc`, a net negative balance is the field element `p - |balance|`.
+// Both accumulations start from zero cells taken from the constant column and every update copies the accumulate
+// cells of the previous one, so the intermediate sums can't be witnessed independently.
+//
+// | value | sign | magnitude | positive  | negative  | sign_selector | balance_selector |
+// | --    | --   | --        | --        | --        | --            | --               |
+// | 5     | 0    | 5         | 5         | 0         | 1             | 0                |
+// | p - 3 | 1    | 3         | 0         | 3         | 1             | 0                |
+// | 2     |      |           | 5         | 3         | 0             | 1                |
+#[derive(Debug, Clone)]
+pub struct SignedAccumulatorConfig<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
+    pub advice: [Column<Advice>; 5],
+    pub sign_selector: Selector,
+    pub balance_selector: Selector,
+    pub constant: Column<Fixed>,
+    pub instance: Column<Instance>,
+    pub positive: SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F>,
+    pub negative: SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedAccumulatorChip<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
+    config: SignedAccumulatorConfig<MAX_BITS, ACC_COLS, F>,
+}
+
+impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>
+    SignedAccumulatorChip<MAX_BITS, ACC_COLS, F>
+{
+    pub fn construct(config: SignedAccumulatorConfig<MAX_BITS, ACC_COLS, F>) -> Self {
+        Self { config }
+    }
+
+    // configure a safe accumulator with its own columns and selectors
+    fn configure_accumulator(
+        meta: &mut ConstraintSystem<F>,
+        instance: Column<Instance>,
+    ) -> SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F> {
+        let update_value = meta.advice_column();
+        let left_most_inv = meta.advice_column();
+        let add_carries = [(); ACC_COLS].map(|_| meta.advice_column());
+        let accumulate = [(); ACC_COLS].map(|_| meta.advice_column());
+        let selector = [meta.selector(), meta.selector(), meta.selector()];
+
+        SafeACcumulatorChip::configure(
+            meta,
+            update_value,
+            left_most_inv,
+            add_carries,
+            accumulate,
+            selector,
+            instance,
+        )
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        constant: Column<Fixed>,
+        instance: Column<Instance>,
+    ) -> SignedAccumulatorConfig<MAX_BITS, ACC_COLS, F> {
+        let col_value = advice[0];
+        let col_sign = advice[1];
+        let col_magnitude = advice[2];
+        let col_positive = advice[3];
+        let col_negative = advice[4];
+
+        let sign_selector = meta.selector();
+        let balance_selector = meta.selector();
+
+        // the positive and negative deltas are copied into the accumulators, the balance is exposed
+        meta.enable_equality(col_value);
+        meta.enable_equality(col_positive);
+        meta.enable_equality(col_negative);
+        meta.enable_equality(instance);
+
+        // the accumulations start from zero cells copied from the constant column
+        meta.enable_constant(constant);
+
+        // Enforces that
+        // - sign is either 0 or 1
+        // - value = magnitude if sign is 0, value = -magnitude if sign is 1
+        // - positive = magnitude and negative = 0 if sign is 0, the other way around if sign is 1
+        meta.create_gate("signed delta constraint", |meta| {
+            let s = meta.query_selector(sign_selector);
+            let value = meta.query_advice(col_value, Rotation::cur());
+            let sign = meta.query_advice(col_sign, Rotation::cur());
+            let magnitude = meta.query_advice(col_magnitude, Rotation::cur());
+            let positive = meta.query_advice(col_positive, Rotation::cur());
+            let negative = meta.query_advice(col_negative, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+
+            vec![
+                s.clone() * sign.clone() * (one.clone() - sign.clone()),
+                s.clone() * (value - magnitude.clone() + two * sign.clone() * magnitude.clone()),
+                s.clone() * (positive - (one - sign.clone()) * magnitude.clone()),
+                s * (negative - sign * magnitude),
+            ]
+        });
+
+        // Enforces that balance = positive - negative
+        meta.create_gate("balance constraint", |meta| {
+            let s = meta.query_selector(balance_selector);
+            let balance = meta.query_advice(col_value, Rotation::cur());
+            let positive = meta.query_advice(col_positive, Rotation::cur());
+            let negative = meta.query_advice(col_negative, Rotation::cur());
+
+            vec![s * (positive - negative - balance)]
+        });
+
+        let positive = Self::configure_accumulator(meta, instance);
+        let negative = Self::configure_accumulator(meta, instance);
+
+        SignedAccumulatorConfig {
+            advice,
+            sign_selector,
+            balance_selector,
+            constant,
+            instance,
+            positive,
+            negative,
+        }
+    }
+
+    // Load the range tables of both accumulators
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        SafeACcumulatorChip::construct(self.config.positive.clone()).load(layouter)?;
+        SafeACcumulatorChip::construct(self.config.negative.clone()).load(layouter)
+    }
+
+    // Split the value into its sign and magnitude, returns the positive and the negative delta cells
+    fn assign_signed_delta(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        // the representation with the smallest magnitude decides the sign
+        let sign = value.map(|v| {
+            if f_to_big_uint(&-v) < f_to_big_uint(&v) {
+                F::one()
+            } else {
+                F::zero()
+            }
+        });
+        let magnitude = value
+            .zip(sign)
+            .map(|(v, s)| if s == F::one() { -v } else { v });
+
+        layouter.assign_region(
+            || "assign signed delta",
+            |mut region| {
+                self.config.sign_selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "value", self.config.advice[0], 0, || value)?;
+                region.assign_advice(|| "sign", self.config.advice[1], 0, || sign)?;
+                region.assign_advice(|| "magnitude", self.config.advice[2], 0, || magnitude)?;
+
+                let positive = region.assign_advice(
+                    || "positive delta",
+                    self.config.advice[3],
+                    0,
+                    || {
+                        magnitude
+                            .zip(sign)
+                            .map(|(m, s)| if s == F::zero() { m } else { F::zero() })
+                    },
+                )?;
+                let negative = region.assign_advice(
+                    || "negative delta",
+                    self.config.advice[4],
+                    0,
+                    || {
+                        magnitude
+                            .zip(sign)
+                            .map(|(m, s)| if s == F::one() { m } else { F::zero() })
+                    },
+                )?;
+
+                Ok((positive, negative))
+            },
+        )
+    }
+
+    // Assigns zero cells from the constant column into the accumulate columns of `config`, little-endian like the
+    // accumulate cells returned by the accumulator
+    fn assign_zero_accumulate(
+        mut layouter: impl Layouter<F>,
+        config: &SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F>,
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
+        layouter.assign_region(
+            || "zero accumulation",
+            |mut region| {
+                let mut cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
+                for i in 0..ACC_COLS {
+                    cells.push(region.assign_advice_from_constant(
+                        || format!("zero accumulate[{}]", ACC_COLS - 1 - i),
+                        config.accumulate[ACC_COLS - 1 - i],
+                        0,
+                        F::zero(),
+                    )?);
+                }
+                Ok(cells)
+            },
+        )
+    }
+
+    // Returns the zero accumulate cells of the positive and of the negative accumulator
+    #[allow(clippy::type_complexity)]
+    pub fn assign_zero_accumulators(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<
+        (
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+        ),
+        Error,
+    > {
+        let positive = Self::assign_zero_accumulate(
+            layouter.namespace(|| "zero positive accumulate"),
+            &self.config.positive,
+        )?;
+        let negative = Self::assign_zero_accumulate(
+            layouter.namespace(|| "zero negative accumulate"),
+            &self.config.negative,
+        )?;
+        Ok((positive, negative))
+    }
+
+    // Adds the signed value to the accumulate cells returned by the previous step, or by `assign_zero_accumulators`.
+    // Returns the updated positive and negative accumulate cells
+    #[allow(clippy::type_complexity)]
+    pub fn accumulate_step(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        positive_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+        negative_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+    ) -> Result<
+        (
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+            ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+        ),
+        Error,
+    > {
+        let positive_chip = SafeACcumulatorChip::construct(self.config.positive.clone());
+        let negative_chip = SafeACcumulatorChip::construct(self.config.negative.clone());
+
+        let (positive, negative) =
+            self.assign_signed_delta(layouter.namespace(|| "signed delta"), value)?;
+
+        let (positive_cells, _) = positive_chip.assign_from_cell_chained(
+            layouter.namespace(|| "positive accumulate"),
+            0,
+            &positive,
+            positive_cells,
+        )?;
+        let (negative_cells, _) = negative_chip.assign_from_cell_chained(
+            layouter.namespace(|| "negative accumulate"),
+            0,
+            &negative,
+            negative_cells,
+        )?;
+
+        Ok((positive_cells, negative_cells))
+    }
+
+    // Recomposes both accumulators and returns the balance cell, positive_total - negative_total
+    pub fn assign_balance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        positive_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+        negative_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let positive_chip = SafeACcumulatorChip::construct(self.config.positive.clone());
+        let negative_chip = SafeACcumulatorChip::construct(self.config.negative.clone());
+
+        let positive_total = positive_chip
+            .recompose_accumulator(layouter.namespace(|| "recompose positive"), positive_cells)?;
+        let negative_total = negative_chip
+            .recompose_accumulator(layouter.namespace(|| "recompose negative"), negative_cells)?;
+
+        layouter.assign_region(
+            || "balance",
+            |mut region| {
+                self.config.balance_selector.enable(&mut region, 0)?;
+
+                let positive = positive_total.copy_advice(
+                    || "copy positive total",
+                    &mut region,
+                    self.config.advice[3],
+                    0,
+                )?;
+                let negative = negative_total.copy_advice(
+                    || "copy negative total",
+                    &mut region,
+                    self.config.advice[4],
+                    0,
+                )?;
+
+                region.assign_advice(
+                    || "balance",
+                    self.config.advice[0],
+                    0,
+                    || positive.value().copied() - negative.value().copied(),
+                )
+            },
+        )
+    }
+
+    // Accumulates the signed values starting from zero, returns the balance cell
+    pub fn accumulate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if values.is_empty() {
+            return Err(Error::Synthesis);
+        }
+
+        let (mut positive_cells, mut negative_cells) =
+            self.assign_zero_accumulators(layouter.namespace(|| "zero accumulators"))?;
+
+        for (i, value) in values.iter().enumerate() {
+            (positive_cells, negative_cells) = self.accumulate_step(
+                layouter.namespace(|| format!("accumulate {}", i)),
+                *value,
+                &positive_cells,
+                &negative_cells,
+            )?;
+        }
+
+        self.assign_balance(
+            layouter.namespace(|| "balance"),
+            &positive_cells,
+            &negative_cells,
+        )
+    }
+
+    // Enforce permutation check between cell and instance column
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
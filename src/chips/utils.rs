@@ -1,27 +1,14 @@
 use eth_types::Field;
 
+use alloc::{string::String, vec::Vec};
 use halo2_proofs::circuit::*;
 use halo2_proofs::plonk::Expression;
 use num_bigint::BigUint;
+use zeroize::{DefaultIsZeroes, Zeroize, ZeroizeOnDrop};
 
-fn parse_hex(hex_asm: &str) -> Vec<u8> {
-    let mut hex_bytes = hex_asm
-        .as_bytes()
-        .iter()
-        .filter_map(|b| match b {
-            b'0'..=b'9' => Some(b - b'0'),
-            b'a'..=b'f' => Some(b - b'a' + 10),
-            b'A'..=b'F' => Some(b - b'A' + 10),
-            _ => None,
-        })
-        .fuse();
-
-    let mut bytes = Vec::new();
-    while let (Some(h), Some(l)) = (hex_bytes.next(), hex_bytes.next()) {
-        bytes.push(h << 4 | l)
-    }
-    bytes
-}
+pub use crate::decompose_bigInt::{
+    decompose_bigInt_to_ubits, decompose_biguint_general, decompose_biguint_u64,
+};
 
 pub fn value_f_to_big_uint<F: Field>(v: Value<F>) -> BigUint {
     let mut sum = F::zero();
@@ -63,11 +50,109 @@ pub fn add_carry<const MAX_BITS: usize, F: Field>(
     f_to_nbits::<MAX_BITS, F>(&sum)
 }
 
+// Same as `add_carry` with a third limb: splits `value + overflow * 2^(2 * MAX_BITS) + hi * 2^MAX_BITS + lo`
+// into (overflow, hi, lo), with hi and lo less than 2^MAX_BITS, so a sum beyond 2^(2 * MAX_BITS) doesn't
+// need a second `add_carry` on hi. The sum is split by shifting and masking it as a `BigUint`
+pub fn add_carry3<const MAX_BITS: usize, F: Field>(
+    value: Value<F>,
+    overflow: Value<F>,
+    hi: Value<F>,
+    lo: Value<F>,
+) -> (F, F, F) {
+    let limbs = add_carry_limbs(value, &[overflow, hi, lo], MAX_BITS);
+    (limbs[0], limbs[1], limbs[2])
+}
+
+// Generalization of `add_carry3` to any number of limbs, most significant first.
+// Every limb but the first one is less than 2^max_bits, the first one takes the rest of the sum
+pub fn add_carry_limbs<F: Field>(value: Value<F>, limbs: &[Value<F>], max_bits: usize) -> Vec<F> {
+    let sum = limbs.iter().fold(BigUint::from(0u8), |acc, limb| {
+        (acc << max_bits) + value_f_to_big_uint(*limb)
+    }) + value_f_to_big_uint(value);
+
+    // `decompose_biguint_general` returns the lower limbs in little-endian order
+    let lower_limbs = limbs.len() - 1;
+    let top = sum.clone() >> (max_bits * lower_limbs);
+
+    decompose_biguint_general::<F>(&top, 1, F::CAPACITY as usize)
+        .into_iter()
+        .chain(
+            decompose_biguint_general::<F>(&sum, lower_limbs, max_bits)
+                .into_iter()
+                .rev(),
+        )
+        .collect()
+}
+
+// Same as `add_carry`, but computed over integers instead of field elements.
+// Returns None if `value + hi * 2^max_bits + lo` doesn't fit into 2 * max_bits bits,
+// so that the witness generation fails before producing an unsatisfiable circuit.
+pub fn checked_add_limbs<F: Field>(value: F, hi: F, lo: F, max_bits: usize) -> Option<(F, F)> {
+    debug_assert!(max_bits <= 64);
+
+    let sum = f_to_big_uint(&value) + (f_to_big_uint(&hi) << max_bits) + f_to_big_uint(&lo);
+
+    if sum.bits() > (2 * max_bits) as u64 {
+        return None;
+    }
+
+    let mask = (BigUint::from(1u8) << max_bits) - 1u8;
+    let to_f = |limb: BigUint| F::from(limb.iter_u64_digits().next().unwrap_or(0));
+
+    Some((to_f(&sum >> max_bits), to_f(sum & mask)))
+}
+
+// Wrapper of a secret witness, the scalar is overwritten with zero when dropped.
+// It only clears the copy held by the wrapper: the value assigned to the circuit is copied by the prover
+// and any copy made by the caller before wrapping it is not affected.
+pub struct SecretValue<F: Field>(Scalar<F>);
+
+// The default of a field element is zero, so `zeroize` wipes the scalar with its volatile write of the default
+#[derive(Clone, Copy, Default)]
+struct Scalar<F: Field>(F);
+
+impl<F: Field> DefaultIsZeroes for Scalar<F> {}
+
+impl<F: Field> SecretValue<F> {
+    pub fn new(value: F) -> Self {
+        Self(Scalar(value))
+    }
+
+    pub fn value(&self) -> Value<F> {
+        Value::known(self.0 .0)
+    }
+}
+
+impl<F: Field> Zeroize for SecretValue<F> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<F: Field> Drop for SecretValue<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: Field> ZeroizeOnDrop for SecretValue<F> {}
+
+// the canonical representation of the field element is little-endian
 fn to_uint<F: Field>(sum: F) -> BigUint {
-    let sum_str = format!("{:?}", sum);
-    let (_, splited_sum_str) = sum_str.split_at(2); // remove '0x'
+    BigUint::from_bytes_le(sum.to_repr().as_ref())
+}
 
-    BigUint::from_bytes_be(parse_hex(splited_sum_str).as_slice())
+// Big-endian hex string of the field element prefixed with '0x', the same as its `Debug` output
+pub fn f_to_hex<F: Field>(value: &F) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let repr = value.to_repr();
+    let mut hex = String::from("0x");
+    for byte in repr.as_ref().iter().rev() {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+    hex
 }
 
 pub fn range_check<F: Field>(value: Expression<F>, range: usize) -> Expression<F> {
@@ -89,39 +174,147 @@ pub fn range_check_vec<F: Field>(
     exprs
 }
 
-pub fn decompose_bigInt_to_ubits<F: Field>(
-    e: &BigUint,
-    number_of_limbs: usize,
-    bit_len: usize,
-) -> Vec<F> {
-    debug_assert!(bit_len <= 64);
-
-    let mut e = e.iter_u64_digits();
-    let mask: u64 = (1u64 << bit_len) - 1u64;
-    let mut u64_digit = e.next().unwrap_or(0);
-    let mut rem = 64;
-    (0..number_of_limbs)
-        .map(|_| match rem.cmp(&bit_len) {
-            core::cmp::Ordering::Greater => {
-                let limb = u64_digit & mask;
-                u64_digit >>= bit_len;
-                rem -= bit_len;
-                F::from(limb)
-            }
-            core::cmp::Ordering::Equal => {
-                let limb = u64_digit & mask;
-                u64_digit = e.next().unwrap_or(0);
-                rem = 64;
-                F::from(limb)
-            }
-            core::cmp::Ordering::Less => {
-                let mut limb = u64_digit;
-                u64_digit = e.next().unwrap_or(0);
-                limb |= (u64_digit & ((1 << (bit_len - rem)) - 1)) << rem; // *
-                u64_digit >>= bit_len - rem;
-                rem += 64 - bit_len;
-                F::from(limb)
-            }
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_carry3, checked_add_limbs, decompose_bigInt_to_ubits, f_to_big_uint, f_to_hex,
+        SecretValue,
+    };
+    use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr as Fp};
+    use num_bigint::BigUint;
+    use zeroize::Zeroize;
+
+    #[test]
+    fn test_secret_value_zeroize() {
+        let mut secret = SecretValue::new(Fp::from(1000));
+        secret.value().assert_if_known(|v| *v == Fp::from(1000));
+
+        secret.zeroize();
+        secret.value().assert_if_known(|v| *v == Fp::zero());
+    }
+
+    #[test]
+    fn test_f_to_hex() {
+        for value in [Fp::zero(), Fp::from(0xabcdef), -Fp::one()] {
+            assert_eq!(f_to_hex(&value), format!("{:?}", value));
+        }
+    }
+
+    #[test]
+    fn test_f_to_big_uint() {
+        assert_eq!(
+            f_to_big_uint(&Fp::from(0x1234_5678)),
+            BigUint::from(0x1234_5678u64)
+        );
+
+        // p - 1 has as many bits as the modulus
+        assert_eq!(f_to_big_uint(&-Fp::one()).bits(), 254);
+    }
+
+    fn recompose(limbs: &[Fp], bit_len: usize) -> BigUint {
+        limbs.iter().rev().fold(BigUint::from(0u8), |acc, limb| {
+            (acc << bit_len) + f_to_big_uint(limb)
         })
-        .collect()
+    }
+
+    #[test]
+    fn test_decompose_big_int_to_ubits_recompose() {
+        // value spanning two u64 digits
+        let value = BigUint::from(0xabcdu64) << 64 | BigUint::from(u64::MAX - 5);
+
+        // limbs are little endian, 20 limbs of 4 bits cover 80 bits
+        let limbs = decompose_bigInt_to_ubits::<Fp>(&value, 20, 4);
+        assert_eq!(limbs.len(), 20);
+        assert_eq!(recompose(&limbs, 4), value);
+
+        // 5 doesn't divide 64, so limbs straddle the u64 digits
+        let limbs = decompose_bigInt_to_ubits::<Fp>(&value, 16, 5);
+        assert_eq!(recompose(&limbs, 5), value);
+
+        // full u64 limbs
+        let limbs = decompose_bigInt_to_ubits::<Fp>(&value, 2, 64);
+        assert_eq!(limbs, vec![Fp::from(u64::MAX - 5), Fp::from(0xabcd)]);
+    }
+
+    #[test]
+    fn test_decompose_big_int_to_ubits_zero_padding() {
+        let value = BigUint::from(0x1234u64);
+
+        let limbs = decompose_bigInt_to_ubits::<Fp>(&value, 20, 4);
+        assert_eq!(recompose(&limbs, 4), value);
+
+        // the limbs above the value are zero
+        assert!(limbs[4..].iter().all(|limb| *limb == Fp::zero()));
+    }
+
+    #[test]
+    fn test_checked_add_limbs() {
+        // 0x01 + 0x0f * 16 + 0x0e = 0xff fits into 8 bits
+        assert_eq!(
+            checked_add_limbs(Fp::from(1), Fp::from(15), Fp::from(14), 4),
+            Some((Fp::from(15), Fp::from(15)))
+        );
+
+        // carry from lo to hi
+        assert_eq!(
+            checked_add_limbs(Fp::from(1), Fp::from(0), Fp::from(15), 4),
+            Some((Fp::from(1), Fp::from(0)))
+        );
+
+        // 0xff + 1 = 0x100 doesn't fit into 8 bits
+        assert_eq!(
+            checked_add_limbs(Fp::from(1), Fp::from(15), Fp::from(15), 4),
+            None
+        );
+
+        // a value wrapping around the field is not accepted either
+        assert_eq!(
+            checked_add_limbs(-Fp::from(1), Fp::from(0), Fp::from(1), 4),
+            None
+        );
+    }
+
+    #[test]
+    fn test_add_carry3() {
+        let split = |value: u64, overflow: u64, hi: u64, lo: u64| {
+            add_carry3::<4, Fp>(
+                Value::known(Fp::from(value)),
+                Value::known(Fp::from(overflow)),
+                Value::known(Fp::from(hi)),
+                Value::known(Fp::from(lo)),
+            )
+        };
+        let limbs =
+            |overflow: u64, hi: u64, lo: u64| (Fp::from(overflow), Fp::from(hi), Fp::from(lo));
+
+        // 0xff is the largest sum below the double boundary 2^8
+        assert_eq!(split(0x0f, 0, 0x0f, 0), limbs(0, 0xf, 0xf));
+
+        // 0xff + 1 = 0x100 crosses it, the carry goes through hi into the overflow limb
+        assert_eq!(split(1, 0, 0x0f, 0x0f), limbs(1, 0, 0));
+
+        // the previous overflow limb is kept, 2 * 2^8 + 0xff + 1 = 3 * 2^8
+        assert_eq!(split(1, 2, 0x0f, 0x0f), limbs(3, 0, 0));
+
+        // a value larger than 2^8 on its own, 0x1234 + 0x01 = 0x1235
+        assert_eq!(split(0x1234, 0, 0, 1), limbs(0x12, 0x3, 0x5));
+
+        // 2^32 + 2 + (2^16 - 1) = 2^32 + 2^16 + 1 with 16 bits limbs, as in the overflow check circuit
+        assert_eq!(
+            add_carry3::<16, Fp>(
+                Value::known(Fp::from((1 << 32) + 2)),
+                Value::known(Fp::zero()),
+                Value::known(Fp::zero()),
+                Value::known(Fp::from((1 << 16) - 1)),
+            ),
+            limbs(1, 1, 1)
+        );
+
+        // 64 bits limbs, 2^(2 * 64) doesn't fit into a u64 shift
+        let max = Value::known(Fp::from(u64::MAX));
+        assert_eq!(
+            add_carry3::<64, Fp>(max, Value::known(Fp::zero()), max, max),
+            limbs(1, 0, u64::MAX - 1)
+        );
+    }
 }
@@ -16,3 +16,43 @@ pub mod add_carry_v2;
 pub mod overflow_check;
 pub mod overflow_check_v2;
 pub mod safe_accumulator;
+pub mod range_proof;
+pub mod inclusion_range;
+pub mod adjacent_leaves;
+pub mod commitment;
+pub mod signed_accumulator;
+pub mod poseidon_sponge;
+pub mod hash_n;
+pub mod proof_of_solvency;
+pub mod pack_limbs;
+pub mod bounded_accumulator;
+pub mod conditional_swap;
+pub mod mux;
+pub mod committed_value;
+pub mod proof_of_liabilities;
+pub mod committed_sum;
+pub mod multi_tree_membership;
+pub mod balance_decrease;
+
+// the circuits of the experiments, so they can be built and proven from outside the crate
+pub use add_carry_v1::AddCarryCircuit as AddCarryV1Circuit;
+pub use add_carry_v2::AddCarryCircuit as AddCarryV2Circuit;
+pub use balance_decrease::BalanceDecreaseCircuit;
+pub use committed_sum::CommittedSumCircuit;
+pub use hash_v1::Hash1Circuit;
+pub use hash_v2::Hash2Circuit;
+pub use inclusion_check::MyCircuit as InclusionCheckCircuit;
+pub use inclusion_check_v2::MyCircuit as InclusionCheckV2Circuit;
+pub use merkle_sum_tree::MerkleSumTreeCircuit;
+pub use merkle_v1::MerkleTreeV1Circuit;
+pub use merkle_v2::MerkleTreeV2Circuit;
+pub use merkle_v3::MerkleTreeV3Circuit;
+pub use multi_tree_membership::MultiTreeMembershipCircuit;
+pub use overflow_check::OverflowCheckCircuit;
+pub use overflow_check_v2::OverflowCheckCircuitV2;
+pub use poseidon::PoseidonCircuit;
+pub use proof_of_liabilities::ProofOfLiabilitiesCircuit;
+pub use proof_of_solvency::ProofOfSolvencyCircuit;
+pub use range_proof::RangeProofCircuit;
+pub use safe_accumulator::SafeAccumulatorCircuit;
+pub use signed_accumulator::SignedAccumulatorCircuit;
@@ -4,7 +4,7 @@ use halo2_proofs::{circuit::*, plonk::*};
 use super::super::chips::add_carry_v1::{AddCarryChip, AddCarryConfig};
 
 #[derive(Default)]
-struct AddCarryCircuit<F: Field> {
+pub struct AddCarryCircuit<F: Field> {
     pub a: Vec<Value<F>>,
 }
 
@@ -61,9 +61,47 @@ impl<F: Field> Circuit<F> for AddCarryCircuit<F> {
     }
 }
 
+// Accumulates two segments in separate regions, the second segment starts from the cells of the first one
+#[derive(Default)]
+struct ChainedAddCarryCircuit<F: Field> {
+    pub first: Vec<Value<F>>,
+    pub second: Vec<Value<F>>,
+}
+
+impl<F: Field> Circuit<F> for ChainedAddCarryCircuit<F> {
+    type Config = AddCarryConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        AddCarryCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = AddCarryChip::construct(config);
+
+        let (b, c) = chip.assign_first_row(layouter.namespace(|| "load first row"))?;
+        let (b, c) =
+            chip.continue_from(layouter.namespace(|| "first segment"), b, c, &self.first)?;
+        let (b, c) =
+            chip.continue_from(layouter.namespace(|| "second segment"), b, c, &self.second)?;
+
+        chip.expose_public(layouter.namespace(|| "carry check"), &b, 0)?;
+        chip.expose_public(layouter.namespace(|| "remain check"), &c, 1)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::AddCarryCircuit;
+    use super::{AddCarryCircuit, ChainedAddCarryCircuit};
     use halo2_proofs::{
         circuit::Value,
         dev::{FailureLocation, MockProver, VerifyFailure},
@@ -123,4 +161,23 @@ mod tests {
         let valid_prover = MockProver::run(k, &circuit, vec![public_inputs.clone()]).unwrap();
         valid_prover.assert_satisfied();
     }
+
+    #[test]
+    fn test_chained_segments() {
+        let k = 5;
+
+        // 0xffff + 1 + 2 = 0x10002
+        let circuit = ChainedAddCarryCircuit::<Fp> {
+            first: vec![Value::known(Fp::from((1 << 16) - 1))],
+            second: vec![Value::known(Fp::from(1)), Value::known(Fp::from(2))],
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(2)]]).unwrap();
+        prover.assert_satisfied();
+
+        // (0, 3) is the sum of the second segment alone, but the second segment continues from the first one
+        let invalid_prover =
+            MockProver::run(k, &circuit, vec![vec![Fp::from(0), Fp::from(3)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
 }
@@ -5,7 +5,7 @@ use halo2_proofs::{circuit::*, plonk::*};
 use super::super::chips::add_carry_v2::{AddCarryV2Chip, AddCarryV2Config};
 
 #[derive(Default)]
-struct AddCarryCircuit<F: Field> {
+pub struct AddCarryCircuit<F: Field> {
     pub a: Value<F>,
 }
 
@@ -22,10 +22,17 @@ impl<F: Field> Circuit<F> for AddCarryCircuit<F> {
         let col_b_inv = meta.advice_column();
         let col_b = meta.advice_column();
         let col_c = meta.advice_column();
+        let constant = meta.fixed_column();
         let carry_selector = meta.complex_selector();
         let instance = meta.instance_column();
 
-        AddCarryV2Chip::configure(meta, [col_a, col_b_inv, col_b, col_c], carry_selector, instance)
+        AddCarryV2Chip::configure(
+            meta,
+            [col_a, col_b_inv, col_b, col_c],
+            constant,
+            carry_selector,
+            instance,
+        )
     }
 
     fn synthesize(
@@ -35,6 +42,8 @@ impl<F: Field> Circuit<F> for AddCarryCircuit<F> {
     ) -> Result<(), Error> {
         let chip = AddCarryV2Chip::construct(config);
 
+        chip.load(&mut layouter)?;
+
         let (prev_b, prev_c) = chip.assign_first_row(layouter.namespace(|| "load first row"))?;
         let (b, c) =
             chip.assign_advice_row(layouter.namespace(|| "load row"), self.a, prev_b.clone(), prev_c.clone())?;
@@ -46,13 +55,63 @@ impl<F: Field> Circuit<F> for AddCarryCircuit<F> {
     }
 }
 
+// Accumulates the values starting from a zero seed taken from the constant column, so the instance column
+// stays empty. The accumulated value is compared against the constants `hi` and `lo`
+#[derive(Default)]
+struct AddCarryZeroSeedCircuit<F: Field> {
+    pub values: Vec<Value<F>>,
+    pub hi: F,
+    pub lo: F,
+}
+
+impl<F: Field> Circuit<F> for AddCarryZeroSeedCircuit<F> {
+    type Config = AddCarryV2Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+            hi: self.hi,
+            lo: self.lo,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        AddCarryCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = AddCarryV2Chip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let (mut b, mut c) = chip.assign_first_row_zero(layouter.namespace(|| "zero first row"))?;
+        for (i, a) in self.values.iter().enumerate() {
+            (b, c) =
+                chip.assign_advice_row(layouter.namespace(|| format!("load row {}", i)), *a, b, c)?;
+        }
+
+        layouter.assign_region(
+            || "check accumulated value",
+            |mut region| {
+                region.constrain_constant(b.cell(), self.hi)?;
+                region.constrain_constant(c.cell(), self.lo)
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::AddCarryCircuit;
+    use super::{AddCarryCircuit, AddCarryZeroSeedCircuit};
     use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
     #[test]
     fn test_carry_2() {
-        let k = 4;
+        let k = 9;
 
         // a: new value
         // public_input[0]: x * 2^16 
@@ -66,4 +125,46 @@ mod tests {
         prover.assert_satisfied();
         assert_eq!(prover.verify(), Ok(()));
     }
+
+    #[test]
+    fn test_carry_2_with_over_16bits_value() {
+        let k = 9;
+
+        // a is a 20 bits value
+        let a = Value::known(Fp::from((1 << 20) - 1));
+
+        // the accumulator would be correct, but 'a' is out of the range supported by the chip
+        let public_inputs = vec![Fp::from(0), Fp::from(0), Fp::from(15), Fp::from((1 << 16) - 1)];
+
+        let circuit = AddCarryCircuit { a };
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_carry_2_from_zero_seed() {
+        let k = 9;
+
+        // (2^16 - 1) + 1 + 5 = 2^16 + 5
+        let values = [(1 << 16) - 1, 1, 5]
+            .map(|x| Value::known(Fp::from(x)))
+            .to_vec();
+
+        let circuit = AddCarryZeroSeedCircuit {
+            values: values.clone(),
+            hi: Fp::from(1),
+            lo: Fp::from(5),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+
+        // the carry is dropped
+        let circuit = AddCarryZeroSeedCircuit {
+            values,
+            hi: Fp::from(0),
+            lo: Fp::from(5),
+        };
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
 }
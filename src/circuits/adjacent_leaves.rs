@@ -0,0 +1,170 @@
+use super::super::chips::adjacent_leaves::AdjacentLeavesChip;
+use super::super::chips::merkle_sum_tree::{MerkleSumTreeChip, MerkleSumTreeConfig};
+use eth_types::Field;
+use gadgets::less_than::{LtChip, LtInstruction};
+use halo2_proofs::{circuit::*, plonk::*};
+use std::marker::PhantomData;
+
+#[derive(Default)]
+// the path elements are the ones shared by the two leaves, starting from the level of their parent
+struct AdjacentLeavesCircuit<F: Field> {
+    pub left_leaf_hash: F,
+    pub left_leaf_balance: F,
+    pub right_leaf_hash: F,
+    pub right_leaf_balance: F,
+    pub path_element_hashes: Vec<F>,
+    pub path_element_balances: Vec<F>,
+    pub path_indices: Vec<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Circuit<F> for AdjacentLeavesCircuit<F> {
+    type Config = MerkleSumTreeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let col_d = meta.advice_column();
+        let col_e = meta.advice_column();
+
+        let instance = meta.instance_column();
+
+        AdjacentLeavesChip::configure(meta, [col_a, col_b, col_c, col_d, col_e], instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // the lt config is part of the merkle sum tree config, its u8 table is loaded even if not used here
+        LtChip::construct(config.lt_config).load(&mut layouter)?;
+
+        let merkle_chip = MerkleSumTreeChip::construct(config.clone());
+        merkle_chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+        let chip = AdjacentLeavesChip::construct(config);
+
+        let (left_hash, left_balance) = merkle_chip.assing_leaf_hash_and_balance(
+            layouter.namespace(|| "assign left leaf"),
+            self.left_leaf_hash,
+            self.left_leaf_balance,
+        )?;
+
+        let (right_hash, right_balance) = merkle_chip.assing_leaf_hash_and_balance(
+            layouter.namespace(|| "assign right leaf"),
+            self.right_leaf_hash,
+            self.right_leaf_balance,
+        )?;
+
+        let (root_hash, _) = chip.prove_adjacent_leaves(
+            layouter.namespace(|| "prove adjacent leaves"),
+            &left_hash,
+            &left_balance,
+            &right_hash,
+            &right_balance,
+            &self.path_element_hashes,
+            &self.path_element_balances,
+            &self.path_indices,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "public left leaf"), &left_hash, 0)?;
+        chip.expose_public(layouter.namespace(|| "public right leaf"), &right_hash, 1)?;
+        chip.expose_public(layouter.namespace(|| "public root"), &root_hash, 2)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdjacentLeavesCircuit;
+    use crate::merkle_sum_tree::{MerkleSumTree, Node};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as Fp};
+    use std::marker::PhantomData;
+
+    fn build_tree() -> MerkleSumTree {
+        let leaves = (0..8)
+            .map(|i| Node {
+                hash: Fp::from(i as u64 + 1),
+                balance: Fp::from((i as u64 + 1) * 10),
+            })
+            .collect();
+
+        MerkleSumTree::new(leaves)
+    }
+
+    // uses the path of the left leaf above the lowest level as shared path
+    fn instantiate_circuit(
+        tree: &MerkleSumTree,
+        left_index: usize,
+        right_index: usize,
+    ) -> AdjacentLeavesCircuit<Fp> {
+        let proof = tree.generate_proof(left_index);
+        let left = &tree.leaves()[left_index];
+        let right = &tree.leaves()[right_index];
+
+        AdjacentLeavesCircuit {
+            left_leaf_hash: left.hash,
+            left_leaf_balance: left.balance,
+            right_leaf_hash: right.hash,
+            right_leaf_balance: right.balance,
+            path_element_hashes: proof.path_elements[1..].iter().map(|n| n.hash).collect(),
+            path_element_balances: proof.path_elements[1..].iter().map(|n| n.balance).collect(),
+            path_indices: proof.path_indices[1..].to_vec(),
+            _marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn test_genuine_siblings() {
+        let tree = build_tree();
+
+        let circuit = instantiate_circuit(&tree, 2, 3);
+        let public_input = vec![
+            tree.leaves()[2].hash,
+            tree.leaves()[3].hash,
+            tree.root().hash,
+        ];
+
+        let valid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        valid_prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_non_siblings() {
+        let tree = build_tree();
+
+        // leaves 3 and 4 are next to each other but belong to different parents
+        let circuit = instantiate_circuit(&tree, 3, 4);
+        let public_input = vec![
+            tree.leaves()[3].hash,
+            tree.leaves()[4].hash,
+            tree.root().hash,
+        ];
+
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_swapped_siblings() {
+        let tree = build_tree();
+
+        // siblings passed in the wrong order hash to a different parent
+        let circuit = instantiate_circuit(&tree, 3, 2);
+        let public_input = vec![
+            tree.leaves()[3].hash,
+            tree.leaves()[2].hash,
+            tree.root().hash,
+        ];
+
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
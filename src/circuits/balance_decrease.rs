@@ -0,0 +1,134 @@
+use super::super::chips::balance_decrease::{BalanceDecreaseChip, BalanceDecreaseConfig};
+use super::utils::NumPublic;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+// Proves that the balance committed in the old state is strictly greater than the one committed in the new state.
+// The old commitment is exposed at row 0 and the new commitment at row 1
+#[derive(Default)]
+pub struct BalanceDecreaseCircuit<F: Field> {
+    pub old_balance: Value<F>,
+    pub old_blinding: Value<F>,
+    pub new_balance: Value<F>,
+    pub new_blinding: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for BalanceDecreaseCircuit<F> {
+    type Config = BalanceDecreaseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let blinding = meta.advice_column();
+        let instance = meta.instance_column();
+
+        BalanceDecreaseChip::configure(meta, value, blinding, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = BalanceDecreaseChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let (old_commitment, new_commitment) = chip.assign(
+            layouter.namespace(|| "balance decrease"),
+            self.old_balance,
+            self.old_blinding,
+            self.new_balance,
+            self.new_blinding,
+        )?;
+
+        chip.expose_public(
+            layouter.namespace(|| "public old commitment"),
+            &old_commitment,
+            0,
+        )?;
+        chip.expose_public(
+            layouter.namespace(|| "public new commitment"),
+            &new_commitment,
+            1,
+        )
+    }
+}
+
+// old and new commitments
+impl<F: Field> NumPublic for BalanceDecreaseCircuit<F> {
+    fn num_public(&self) -> usize {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::chips::poseidon::spec::MySpec;
+    use super::BalanceDecreaseCircuit;
+    use crate::circuits::utils::run_checked;
+    use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+    use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr as Fp};
+
+    fn compute_commitment(value: Fp, blinding: Fp) -> Fp {
+        poseidon::Hash::<_, MySpec<Fp, 3, 2>, ConstantLength<2>, 3, 2>::init()
+            .hash([value, blinding])
+    }
+
+    fn build_circuit(old_balance: u64, new_balance: u64) -> (BalanceDecreaseCircuit<Fp>, Vec<Fp>) {
+        let (old_balance, old_blinding) = (Fp::from(old_balance), Fp::from(11u64));
+        let (new_balance, new_blinding) = (Fp::from(new_balance), Fp::from(22u64));
+
+        let public_input = vec![
+            compute_commitment(old_balance, old_blinding),
+            compute_commitment(new_balance, new_blinding),
+        ];
+
+        let circuit = BalanceDecreaseCircuit {
+            old_balance: Value::known(old_balance),
+            old_blinding: Value::known(old_blinding),
+            new_balance: Value::known(new_balance),
+            new_blinding: Value::known(new_blinding),
+        };
+
+        (circuit, public_input)
+    }
+
+    #[test]
+    fn test_balance_decrease() {
+        let (circuit, public_input) = build_circuit(100, 60);
+
+        let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_balance_increase() {
+        let (circuit, public_input) = build_circuit(60, 100);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_equal_balances() {
+        // the balance must strictly decrease
+        let (circuit, public_input) = build_circuit(100, 100);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_wrong_commitment() {
+        let (circuit, mut public_input) = build_circuit(100, 60);
+        public_input[1] = compute_commitment(Fp::from(50u64), Fp::from(22u64));
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use super::super::chips::add_carry_v1::{AddCarryChip, AddCarryConfig};
+use eth_types::Field;
+use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+struct BoundedAccumulatorConfig<F: Field> {
+    add_carry_config: AddCarryConfig<F>,
+    // | hi | lo | total | max_total | check |
+    advice: [Column<Advice>; 5],
+    selector: Selector,
+    instance: Column<Instance>,
+    lt_config: LtConfig<F, 8>,
+}
+
+// Accumulates the values with `AddCarryChip`, then compares the recomposed total `hi * 2^16 + lo` against
+// the public `max_total` with `LtChip` instead of range checking every limb.
+// The bound is inclusive, as in the range proof `total < max_total + 1` is checked.
+// The public inputs are `max_total` (row 0) and the check, 1 if total <= max_total and 0 otherwise (row 1)
+#[derive(Default)]
+struct BoundedAccumulatorCircuit<F: Field> {
+    pub values: Vec<Value<F>>,
+}
+
+impl<F: Field> Circuit<F> for BoundedAccumulatorCircuit<F> {
+    type Config = BoundedAccumulatorConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let add_carry_advice = [(); 3].map(|_| meta.advice_column());
+        let constant = meta.fixed_column();
+        let carry_selector = meta.complex_selector();
+        let instance = meta.instance_column();
+
+        let add_carry_config =
+            AddCarryChip::configure(meta, add_carry_advice, constant, carry_selector, instance);
+
+        let advice = [(); 5].map(|_| meta.advice_column());
+        let [hi, lo, total, max_total, check] = advice;
+        let selector = meta.complex_selector();
+
+        // hi and lo are copied from the accumulator, max_total from the instance column, check is exposed
+        meta.enable_equality(hi);
+        meta.enable_equality(lo);
+        meta.enable_equality(max_total);
+        meta.enable_equality(check);
+
+        let lt_config = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(total, Rotation::cur()),
+            |meta| meta.query_advice(max_total, Rotation::cur()) + Expression::Constant(F::one()),
+        );
+
+        meta.create_gate("bounded total", |meta| {
+            let s = meta.query_selector(selector);
+            let hi = meta.query_advice(hi, Rotation::cur());
+            let lo = meta.query_advice(lo, Rotation::cur());
+            let total = meta.query_advice(total, Rotation::cur());
+            let check = meta.query_advice(check, Rotation::cur());
+
+            vec![
+                s.clone() * (hi * Expression::Constant(F::from(1 << 16)) + lo - total),
+                s * (lt_config.is_lt(meta, None) - check),
+            ]
+        });
+
+        BoundedAccumulatorConfig {
+            add_carry_config,
+            advice,
+            selector,
+            instance,
+            lt_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let add_carry_chip = AddCarryChip::construct(config.add_carry_config);
+        let lt_chip = LtChip::construct(config.lt_config);
+
+        lt_chip.load(&mut layouter)?;
+
+        let (hi, lo) = add_carry_chip.assign_first_row(layouter.namespace(|| "load first row"))?;
+        let (hi, lo) = add_carry_chip.continue_from(
+            layouter.namespace(|| "accumulate"),
+            hi,
+            lo,
+            &self.values,
+        )?;
+
+        let check = layouter.assign_region(
+            || "compare total against max_total",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                let [hi_col, lo_col, total_col, max_total_col, check_col] = config.advice;
+
+                hi.copy_advice(|| "copy hi", &mut region, hi_col, 0)?;
+                lo.copy_advice(|| "copy lo", &mut region, lo_col, 0)?;
+                let total_value =
+                    hi.value().copied() * Value::known(F::from(1 << 16)) + lo.value().copied();
+                region.assign_advice(|| "total", total_col, 0, || total_value)?;
+
+                let max_total = region.assign_advice_from_instance(
+                    || "max_total",
+                    config.instance,
+                    0,
+                    max_total_col,
+                    0,
+                )?;
+
+                let mut total = F::zero();
+                total_value.map(|f| total = f);
+                let mut rhs = F::one();
+                max_total.value().map(|f| rhs += f);
+
+                lt_chip.assign(&mut region, 0, total, rhs)?;
+
+                region.assign_advice(
+                    || "check",
+                    check_col,
+                    0,
+                    || Value::known(F::from((total < rhs) as u64)),
+                )
+            },
+        )?;
+
+        add_carry_chip.expose_public(layouter.namespace(|| "public check"), &check, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundedAccumulatorCircuit;
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+
+    fn build_circuit() -> BoundedAccumulatorCircuit<Fp> {
+        // 0xffff + 1 + 100 = 0x10064, the total carries into the hi column
+        BoundedAccumulatorCircuit {
+            values: [(1 << 16) - 1, 1, 100]
+                .map(|x: u64| Value::known(Fp::from(x)))
+                .to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_total_at_bound() {
+        let k = 9;
+        let circuit = build_circuit();
+
+        let prover =
+            MockProver::run(k, &circuit, vec![vec![Fp::from(0x10064), Fp::from(1)]]).unwrap();
+        prover.assert_satisfied();
+
+        // the check can't be exposed as failed
+        let invalid_prover =
+            MockProver::run(k, &circuit, vec![vec![Fp::from(0x10064), Fp::from(0)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_total_above_bound() {
+        let k = 9;
+        let circuit = build_circuit();
+
+        let prover =
+            MockProver::run(k, &circuit, vec![vec![Fp::from(0x10063), Fp::from(0)]]).unwrap();
+        prover.assert_satisfied();
+
+        // the check can't be exposed as passed
+        let invalid_prover =
+            MockProver::run(k, &circuit, vec![vec![Fp::from(0x10063), Fp::from(1)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use super::super::chips::commitment::{CommitmentChip, CommitmentConfig};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+#[derive(Default)]
+// the commitment to open is passed as public input at row 0
+struct CommitmentCircuit<F> {
+    pub value: Value<F>,
+    pub blinding: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for CommitmentCircuit<F> {
+    type Config = CommitmentConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_value = meta.advice_column();
+        let col_blinding = meta.advice_column();
+        let instance = meta.instance_column();
+
+        CommitmentChip::configure(meta, [col_value, col_blinding], instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = CommitmentChip::construct(config.clone());
+
+        let (value_cell, blinding_cell) = chip.assign_private(
+            layouter.namespace(|| "assign private"),
+            self.value,
+            self.blinding,
+        )?;
+
+        // copy the claimed commitment from instance column
+        let commitment_cell = layouter.assign_region(
+            || "claimed commitment",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "copy commitment",
+                    config.instance,
+                    0,
+                    config.advice[0],
+                    0,
+                )
+            },
+        )?;
+
+        chip.open(
+            layouter.namespace(|| "open"),
+            &value_cell,
+            &blinding_cell,
+            &commitment_cell,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::chips::poseidon::spec::MySpec;
+    use super::CommitmentCircuit;
+    use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+
+    const WIDTH: usize = 3;
+    const RATE: usize = 2;
+    const L: usize = 2;
+
+    fn compute_commitment(value: Fp, blinding: Fp) -> Fp {
+        poseidon::Hash::<_, MySpec<Fp, WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init()
+            .hash([value, blinding])
+    }
+
+    #[test]
+    fn test_valid_opening() {
+        let value = Fp::from(1000);
+        let blinding = Fp::from(123456789);
+
+        let circuit = CommitmentCircuit {
+            value: Value::known(value),
+            blinding: Value::known(blinding),
+        };
+
+        let public_input = vec![compute_commitment(value, blinding)];
+        let prover = MockProver::run(7, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_wrong_blinding() {
+        let value = Fp::from(1000);
+        let blinding = Fp::from(123456789);
+
+        // the prover opens the commitment with a different blinding factor
+        let circuit = CommitmentCircuit {
+            value: Value::known(value),
+            blinding: Value::known(blinding + Fp::one()),
+        };
+
+        let public_input = vec![compute_commitment(value, blinding)];
+        let invalid_prover = MockProver::run(7, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use super::super::chips::commitment::CommitmentChip;
+use super::super::chips::committed_sum::{CommittedSumChip, CommittedSumConfig};
+use super::super::chips::is_zero::IsZeroChip;
+use super::super::chips::safe_accumulator::SafeACcumulatorChip;
+use super::utils::NumPublic;
+use arrayvec::ArrayVec;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+// 8 bits for each accumulate column and 4 columns, the left most one is the overflow column,
+// so the total must fit into 24 bits
+const MAX_BITS: u8 = 8;
+const ACC_COLS: usize = 4;
+
+// Proves that N committed balances sum up to a public total, without revealing the balances.
+// The commitments are exposed at rows 0..N and the total at row N
+pub struct CommittedSumCircuit<const N: usize, F: Field> {
+    pub values: [Value<F>; N],
+    pub blindings: [Value<F>; N],
+}
+
+impl<const N: usize, F: Field> Default for CommittedSumCircuit<N, F> {
+    fn default() -> Self {
+        Self {
+            values: [Value::unknown(); N],
+            blindings: [Value::unknown(); N],
+        }
+    }
+}
+
+impl<const N: usize, F: Field> Circuit<F> for CommittedSumCircuit<N, F> {
+    type Config = CommittedSumConfig<MAX_BITS, ACC_COLS, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let blinding = meta.advice_column();
+        let update_value = meta.advice_column();
+        let left_most_inv = meta.advice_column();
+        let add_carries = [(); ACC_COLS].map(|_| meta.advice_column());
+        let accumulate = [(); ACC_COLS].map(|_| meta.advice_column());
+        let constant = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        CommittedSumChip::configure(
+            meta,
+            value,
+            blinding,
+            update_value,
+            left_most_inv,
+            add_carries,
+            accumulate,
+            constant,
+            instance,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = CommittedSumChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let openings = self
+            .values
+            .iter()
+            .copied()
+            .zip(self.blindings.iter().copied())
+            .collect::<Vec<_>>();
+        let (commitments, total) =
+            chip.assign(layouter.namespace(|| "commit and sum"), &openings)?;
+
+        for (i, commitment) in commitments.iter().enumerate() {
+            chip.expose_public(
+                layouter.namespace(|| format!("public commitment {}", i)),
+                commitment,
+                i,
+            )?;
+        }
+        chip.expose_public(layouter.namespace(|| "public total"), &total, N)
+    }
+}
+
+// N commitments and the total
+impl<const N: usize, F: Field> NumPublic for CommittedSumCircuit<N, F> {
+    fn num_public(&self) -> usize {
+        N + 1
+    }
+}
+
+// Commits to a single value and witnesses the accumulation of the value into a zero accumulator as
+// `forged_total`. The carries are field elements chosen so that the accumulation constraint is satisfied,
+// so only the bool constraint on the carries rejects a wrong total
+struct ForgedSumCircuit<F: Field> {
+    pub value: Value<F>,
+    pub blinding: Value<F>,
+    pub forged_total: u64,
+}
+
+impl<F: Field> Circuit<F> for ForgedSumCircuit<F> {
+    type Config = CommittedSumConfig<MAX_BITS, ACC_COLS, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            blinding: Value::unknown(),
+            forged_total: self.forged_total,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        CommittedSumCircuit::<1, F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = CommittedSumChip::construct(config.clone());
+        let commitment_chip = CommitmentChip::construct(config.commitment_config.clone());
+        let accumulator_config = config.accumulator_config.clone();
+        let accumulator_chip = SafeACcumulatorChip::construct(accumulator_config.clone());
+
+        chip.load(&mut layouter)?;
+
+        let (value_cell, blinding_cell) = commitment_chip.assign_private(
+            layouter.namespace(|| "assign opening"),
+            self.value,
+            self.blinding,
+        )?;
+        let commitment = commitment_chip.commit(
+            layouter.namespace(|| "commit value"),
+            &value_cell,
+            &blinding_cell,
+        )?;
+
+        // value + 0 = carry_3 * 2^MAX_BITS + forged_total, then every carry is shifted into the next one
+        let carry = value_cell.value().map(|v| *v - F::from(self.forged_total));
+        let shift_inv = F::from(1 << MAX_BITS).invert().unwrap();
+
+        let accumulate_cells = layouter.assign_region(
+            || "forged accumulation",
+            |mut region| {
+                accumulator_config.selector[0].enable(&mut region, 1)?;
+                accumulator_config.selector[1].enable(&mut region, 1)?;
+                accumulator_config.bool_selector.enable(&mut region, 1)?;
+                accumulator_config.range_selector.enable(&mut region, 1)?;
+
+                value_cell.copy_advice(
+                    || "copy value",
+                    &mut region,
+                    accumulator_config.update_value,
+                    1,
+                )?;
+
+                // little-endian like the accumulate cells returned by the accumulator
+                let mut cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
+                let mut forged_carry = carry;
+                for i in (0..ACC_COLS).rev() {
+                    region.assign_advice_from_constant(
+                        || format!("zero accumulate[{}]", i),
+                        accumulator_config.accumulate[i],
+                        0,
+                        F::zero(),
+                    )?;
+
+                    forged_carry = forged_carry.map(|c| c * shift_inv);
+                    region.assign_advice(
+                        || format!("forged carry[{}]", i),
+                        accumulator_config.add_carries[i],
+                        1,
+                        || forged_carry,
+                    )?;
+
+                    let forged_accumulate = if i == ACC_COLS - 1 {
+                        F::from(self.forged_total)
+                    } else {
+                        F::zero()
+                    };
+                    cells.push(region.assign_advice(
+                        || format!("forged accumulate[{}]", i),
+                        accumulator_config.accumulate[i],
+                        1,
+                        || Value::known(forged_accumulate),
+                    )?);
+                }
+
+                IsZeroChip::construct(accumulator_config.is_zero.clone()).assign(
+                    &mut region,
+                    1,
+                    Value::known(F::zero()),
+                )?;
+
+                Ok(cells)
+            },
+        )?;
+
+        let total = accumulator_chip
+            .recompose_accumulator(layouter.namespace(|| "recompose total"), &accumulate_cells)?;
+
+        chip.expose_public(layouter.namespace(|| "public commitment"), &commitment, 0)?;
+        chip.expose_public(layouter.namespace(|| "public total"), &total, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::chips::poseidon::spec::MySpec;
+    use super::{CommittedSumCircuit, ForgedSumCircuit};
+    use crate::circuits::utils::run_checked;
+    use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr as Fp,
+    };
+
+    fn compute_commitment(value: Fp, blinding: Fp) -> Fp {
+        poseidon::Hash::<_, MySpec<Fp, 3, 2>, ConstantLength<2>, 3, 2>::init()
+            .hash([value, blinding])
+    }
+
+    fn build_circuit() -> (CommittedSumCircuit<4, Fp>, Vec<Fp>) {
+        let values = [100u64, 200, 250, 50].map(Fp::from);
+        let blindings = [11u64, 22, 33, 44].map(Fp::from);
+
+        let mut public_input = values
+            .iter()
+            .zip(blindings.iter())
+            .map(|(value, blinding)| compute_commitment(*value, *blinding))
+            .collect::<Vec<_>>();
+        public_input.push(Fp::from(600u64));
+
+        let circuit = CommittedSumCircuit {
+            values: values.map(Value::known),
+            blindings: blindings.map(Value::known),
+        };
+
+        (circuit, public_input)
+    }
+
+    #[test]
+    fn test_committed_sum() {
+        // 100 + 200 + 250 + 50 = 600
+        let (circuit, public_input) = build_circuit();
+
+        let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_wrong_committed_sum() {
+        let (circuit, mut public_input) = build_circuit();
+        public_input[4] = Fp::from(601u64);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_forged_committed_sum() {
+        let (value, blinding) = (Fp::from(100u64), Fp::from(11u64));
+        let public_input = vec![compute_commitment(value, blinding), Fp::from(101u64)];
+
+        // the accumulation of 100 is witnessed as 101 with carries that are neither 0 nor 1
+        let circuit = ForgedSumCircuit {
+            value: Value::known(value),
+            blinding: Value::known(blinding),
+            forged_total: 101,
+        };
+
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(!failures.is_empty());
+        for failure in failures {
+            match failure {
+                VerifyFailure::ConstraintNotSatisfied { constraint, .. } => {
+                    assert!(constraint.to_string().contains("bool constraint"))
+                }
+                _ => panic!("unexpected failure {:?}", failure),
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use super::super::chips::commitment::CommitmentChip;
+use super::super::chips::committed_value::{CommittedValueChip, CommittedValueConfig};
+use super::super::chips::overflow_check_v2::OverflowChipV2;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+// 2 limbs of 8 bits, each range checked as 2 chunks of 4 bits, so the committed value fits into 16 bits
+const MAX_BITS: u8 = 8;
+const ACC_COLS: usize = 2;
+const TABLE_BITS: u8 = 4;
+
+// the commitment to the range checked value is exposed at row 0
+#[derive(Default)]
+struct CommittedValueCircuit<F: Field> {
+    pub value: Value<F>,
+    pub blinding: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for CommittedValueCircuit<F> {
+    type Config = CommittedValueConfig<MAX_BITS, ACC_COLS, TABLE_BITS, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let blinding = meta.advice_column();
+        let decomposed_values = [(); ACC_COLS].map(|_| meta.advice_column());
+        let range = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        CommittedValueChip::configure(meta, value, blinding, decomposed_values, range, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = CommittedValueChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let commitment = chip.commit(
+            layouter.namespace(|| "commit to value"),
+            self.value,
+            self.blinding,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "public commitment"), &commitment, 0)
+    }
+}
+
+// Same as `CommittedValueCircuit`, but the value is decomposed and committed without the off-circuit check of
+// `CommittedValueChip::commit`, as a prover building the witness by other means would do
+#[derive(Default)]
+struct UncheckedCommittedValueCircuit<F: Field> {
+    pub value: Value<F>,
+    pub blinding: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for UncheckedCommittedValueCircuit<F> {
+    type Config = CommittedValueConfig<MAX_BITS, ACC_COLS, TABLE_BITS, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        CommittedValueCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = CommittedValueChip::construct(config.clone());
+        let overflow_chip =
+            OverflowChipV2::<MAX_BITS, ACC_COLS, TABLE_BITS, F>::construct(config.overflow_config);
+        let commitment_chip = CommitmentChip::construct(config.commitment_config.clone());
+
+        chip.load(&mut layouter)?;
+
+        let (value_cell, _) =
+            overflow_chip.assign(layouter.namespace(|| "range check value"), self.value)?;
+
+        let blinding_cell = layouter.assign_region(
+            || "assign blinding",
+            |mut region| {
+                region.assign_advice(
+                    || "blinding",
+                    config.commitment_config.advice[1],
+                    0,
+                    || self.blinding,
+                )
+            },
+        )?;
+
+        let commitment =
+            commitment_chip.commit(layouter.namespace(|| "commit"), &value_cell, &blinding_cell)?;
+
+        chip.expose_public(layouter.namespace(|| "public commitment"), &commitment, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::chips::poseidon::spec::MySpec;
+    use super::{CommittedValueCircuit, UncheckedCommittedValueCircuit};
+    use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+    use halo2_proofs::{
+        circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp, plonk::Error,
+    };
+
+    fn compute_commitment(value: Fp, blinding: Fp) -> Fp {
+        poseidon::Hash::<_, MySpec<Fp, 3, 2>, ConstantLength<2>, 3, 2>::init()
+            .hash([value, blinding])
+    }
+
+    #[test]
+    fn test_in_range_value() {
+        let value = Fp::from(0xbeef);
+        let blinding = Fp::from(123456789);
+
+        let circuit = CommittedValueCircuit {
+            value: Value::known(value),
+            blinding: Value::known(blinding),
+        };
+
+        let public_input = vec![compute_commitment(value, blinding)];
+        let prover = MockProver::run(7, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the commitment of another value doesn't match
+        let public_input = vec![compute_commitment(value + Fp::one(), blinding)];
+        let invalid_prover = MockProver::run(7, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_out_of_range_value() {
+        // 2^16 doesn't fit into 16 bits
+        let value = Fp::from(1 << 16);
+        let blinding = Fp::from(123456789);
+
+        let circuit = CommittedValueCircuit {
+            value: Value::known(value),
+            blinding: Value::known(blinding),
+        };
+
+        // the synthesis stops at the range check, before the value is hashed
+        let public_input = vec![compute_commitment(value, blinding)];
+        assert!(matches!(
+            MockProver::run(7, &circuit, vec![public_input]),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[test]
+    fn test_out_of_range_value_without_guard() {
+        // 2^16 doesn't fit into 16 bits
+        let value = Fp::from(1 << 16);
+        let blinding = Fp::from(123456789);
+
+        let circuit = UncheckedCommittedValueCircuit {
+            value: Value::known(value),
+            blinding: Value::known(blinding),
+        };
+
+        // the commitment matches, but the limbs of 2^16 don't recompose to the value
+        let public_input = vec![compute_commitment(value, blinding)];
+        let invalid_prover = MockProver::run(7, &circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover
+            .verify()
+            .unwrap_err()
+            .iter()
+            .map(|failure| failure.to_string())
+            .collect::<Vec<_>>();
+        assert!(failures
+            .iter()
+            .any(|failure| failure.contains("equality check between decomposed value and value")));
+    }
+}
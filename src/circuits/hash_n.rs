@@ -0,0 +1,72 @@
+use super::super::chips::hash_n::{HashNChip, HashNConfig};
+
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+struct HashNCircuit<F, const N: usize> {
+    pub inputs: [Value<F>; N],
+}
+
+impl<F: Field, const N: usize> Circuit<F> for HashNCircuit<F, N> {
+    type Config = HashNConfig<N>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            inputs: [Value::unknown(); N],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let inputs = [(); N].map(|_| meta.advice_column());
+        let output = meta.advice_column();
+        let instance = meta.instance_column();
+
+        HashNChip::configure(meta, inputs, output, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = HashNChip::construct(config);
+
+        let mut input_cells = vec![];
+        for (i, input) in self.inputs.iter().enumerate() {
+            input_cells.push(
+                chip.load_private(layouter.namespace(|| format!("load input {}", i)), *input)?,
+            );
+        }
+
+        let output = chip.hash(
+            layouter.namespace(|| "hash row"),
+            input_cells.try_into().unwrap(),
+        )?;
+        chip.expose_public(layouter.namespace(|| "hash output check"), &output, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashNCircuit;
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+
+    #[test]
+    fn test_hash_4() {
+        let k = 4;
+
+        // successful case
+        let inputs = [2, 7, 1, 5].map(|x| Value::known(Fp::from(x)));
+        let public_inputs = vec![Fp::from(15)];
+        let circuit = HashNCircuit::<Fp, 4> { inputs };
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // failure case
+        let public_inputs = vec![Fp::from(14)];
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
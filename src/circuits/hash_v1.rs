@@ -1,13 +1,14 @@
 use super::super::chips::hash_v1::{Hash1Chip, Hash1Config};
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
-struct Hash1Circuit<F> {
+pub struct Hash1Circuit<F> {
     pub a: Value<F>,
 }
 
-impl<F: FieldExt> Circuit<F> for Hash1Circuit<F> {
+impl<F: Field> Circuit<F> for Hash1Circuit<F> {
     type Config = Hash1Config;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -35,10 +36,44 @@ impl<F: FieldExt> Circuit<F> for Hash1Circuit<F> {
     }
 }
 
+// hashes a batch of values, the output of the i-th value is exposed at row i
+#[derive(Default)]
+struct Hash1BatchCircuit<F> {
+    pub values: Vec<Value<F>>,
+}
+
+impl<F: Field> Circuit<F> for Hash1BatchCircuit<F> {
+    type Config = Hash1Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Hash1Circuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = Hash1Chip::construct(config);
+        let b_cells = chip.assign_rows(layouter.namespace(|| "load rows"), &self.values)?;
+        for (row, b) in b_cells.iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "hash output check"), b, row)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Hash1Circuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use super::{Hash1BatchCircuit, Hash1Circuit};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
     #[test]
     fn test_hash_1() {
         let k = 4;
@@ -52,4 +87,180 @@ mod tests {
         let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
         assert!(prover.verify().is_err());
     }
+
+    #[test]
+    fn test_min_k() {
+        use crate::circuits::utils::min_k;
+        use halo2_proofs::halo2curves::bn256::Fr;
+
+        let circuit = Hash1Circuit {
+            a: Value::known(Fr::from(2)),
+        };
+
+        // a single row and the blinding rows
+        let k = min_k(&circuit);
+        assert!(k <= 4);
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(4)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_hash_1_batch() {
+        let k = 4;
+        let values = [2u64, 3, 7].map(|x| Value::known(Fp::from(x))).to_vec();
+        let circuit = Hash1BatchCircuit { values };
+
+        let public_inputs = vec![Fp::from(4), Fp::from(6), Fp::from(14)];
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // the output of the last value is wrong
+        let public_inputs = vec![Fp::from(4), Fp::from(6), Fp::from(15)];
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_downsize_params() {
+        use crate::circuits::utils::{downsize_params, gen_proof, gen_srs, verify_kzg_proof};
+        use halo2_proofs::{
+            halo2curves::bn256::Fr,
+            plonk::{keygen_pk, keygen_vk},
+            poly::commitment::Params,
+        };
+
+        let params = gen_srs(10);
+        let small_params = downsize_params(&params, 4);
+        assert_eq!(small_params.k(), 4);
+        // the source params are left untouched
+        assert_eq!(params.k(), 10);
+
+        let circuit = Hash1Circuit {
+            a: Value::known(Fr::from(2)),
+        };
+        let vk = keygen_vk(&small_params, &circuit).unwrap();
+        let pk = keygen_pk(&small_params, vk, &circuit).unwrap();
+
+        let public_input = vec![Fr::from(4)];
+        let proof = gen_proof(&small_params, &pk, circuit, &public_input);
+        assert!(verify_kzg_proof(
+            &small_params,
+            pk.get_vk(),
+            &[public_input],
+            &proof
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't downsize params of k = 4 to the larger k = 10")]
+    fn test_downsize_params_to_larger_k() {
+        use crate::circuits::utils::{downsize_params, gen_srs};
+
+        downsize_params(&gen_srs(4), 10);
+    }
+
+    #[test]
+    fn test_proof_bundle_round_trip() {
+        use crate::circuits::utils::{gen_proof, gen_srs, verify_bundle, ProofBundle};
+        use crate::error::ExperimentError;
+        use halo2_proofs::{
+            halo2curves::bn256::Fr,
+            plonk::{keygen_pk, keygen_vk},
+        };
+
+        let params = gen_srs(4);
+        let circuit = Hash1Circuit {
+            a: Value::known(Fr::from(2)),
+        };
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+        let public_input = vec![Fr::from(4)];
+        let bundle = ProofBundle {
+            proof: gen_proof(&params, &pk, circuit, &public_input),
+            instances: vec![public_input],
+        };
+
+        let bytes = bundle.to_bytes();
+        let decoded = ProofBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, bundle);
+        assert!(verify_bundle(&params, pk.get_vk(), &decoded));
+
+        // the proof doesn't match other instances
+        let wrong_bundle = ProofBundle {
+            instances: vec![vec![Fr::from(8)]],
+            ..decoded
+        };
+        assert!(!verify_bundle(&params, pk.get_vk(), &wrong_bundle));
+
+        // a truncated bundle is rejected
+        assert!(matches!(
+            ProofBundle::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ExperimentError::InvalidProofBundle(_))
+        ));
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_prover_spans() {
+        use crate::circuits::utils::full_prover;
+        use halo2_proofs::halo2curves::bn256::Fr;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id};
+        use tracing::Subscriber;
+        use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+        type Spans = Arc<Mutex<Vec<(String, Vec<(String, u64)>)>>>;
+
+        // records the name and the integer fields of every new span
+        struct SpanRecorder(Spans);
+        struct FieldRecorder(Vec<(String, u64)>);
+
+        impl Visit for FieldRecorder {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                self.0.push((field.name().to_string(), value));
+            }
+
+            fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+        }
+
+        impl<S: Subscriber> Layer<S> for SpanRecorder {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+                let mut fields = FieldRecorder(vec![]);
+                attrs.record(&mut fields);
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((attrs.metadata().name().to_string(), fields.0));
+            }
+        }
+
+        let spans = Spans::default();
+        let subscriber = tracing_subscriber::registry().with(SpanRecorder(spans.clone()));
+
+        let circuit = Hash1Circuit {
+            a: Value::known(Fr::from(2)),
+        };
+        tracing::subscriber::with_default(subscriber, || {
+            full_prover(circuit, 4, &[Fr::from(4)]);
+        });
+
+        let field = |name: &str, value: u64| (name.to_string(), value);
+        assert_eq!(
+            *spans.lock().unwrap(),
+            vec![
+                ("keygen".to_string(), vec![field("k", 4)]),
+                (
+                    "prove".to_string(),
+                    vec![field("k", 4), field("instances", 1)]
+                ),
+                (
+                    "verify".to_string(),
+                    vec![field("k", 4), field("instances", 1)]
+                ),
+            ]
+        );
+    }
 }
@@ -1,14 +1,17 @@
 use super::super::chips::hash_v2::{Hash2Chip, Hash2Config};
+use super::super::chips::utils::SecretValue;
+use super::utils::CircuitExt;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
-struct Hash2Circuit<F> {
+pub struct Hash2Circuit<F> {
     pub a: Value<F>,
     pub b: Value<F>,
 }
 
-impl<F: FieldExt> Circuit<F> for Hash2Circuit<F> {
+impl<F: Field> Circuit<F> for Hash2Circuit<F> {
     type Config = Hash2Config;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -39,10 +42,54 @@ impl<F: FieldExt> Circuit<F> for Hash2Circuit<F> {
     }
 }
 
+// the hash output a + b
+impl<F: Field> CircuitExt<F> for Hash2Circuit<F> {
+    fn instances(&self) -> Vec<Vec<F>> {
+        let mut c = F::zero();
+        self.a.zip(self.b).map(|(a, b)| c = a + b);
+        vec![vec![c]]
+    }
+}
+
+#[derive(Default)]
+// same as Hash2Circuit, but the inputs are loaded with `load_private_zeroizing`
+struct Hash2ZeroizingCircuit<F> {
+    pub a: F,
+    pub b: F,
+}
+
+impl<F: Field> Circuit<F> for Hash2ZeroizingCircuit<F> {
+    type Config = Hash2Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Hash2Circuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = Hash2Chip::construct(config);
+        let a =
+            chip.load_private_zeroizing(layouter.namespace(|| "load a"), SecretValue::new(self.a))?;
+        let b =
+            chip.load_private_zeroizing(layouter.namespace(|| "load b"), SecretValue::new(self.b))?;
+        let c = chip.hash(layouter.namespace(|| "load row"), a, b)?;
+        chip.expose_public(layouter.namespace(|| "hash output check"), &c, 0)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Hash2Circuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use super::{Hash2Circuit, Hash2ZeroizingCircuit};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
 
     #[test]
     fn test_hash_2() {
@@ -61,4 +108,149 @@ mod tests {
         let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
         assert!(prover.verify().is_err());
     }
+
+    #[test]
+    fn test_hash_2_zeroizing() {
+        let k = 4;
+
+        let circuit = Hash2ZeroizingCircuit {
+            a: Fp::from(2),
+            b: Fp::from(7),
+        };
+
+        let public_inputs = vec![Fp::from(9)];
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let public_inputs = vec![Fp::from(8)];
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_load_or_gen_srs() {
+        use crate::circuits::utils::{gen_proof, gen_srs, load_or_gen_srs, verify_kzg_proof};
+        use crate::error::ExperimentError;
+        use halo2_proofs::{
+            halo2curves::bn256::Fr,
+            plonk::{keygen_pk, keygen_vk},
+            poly::commitment::Params,
+        };
+        use std::fs::File;
+        use std::path::Path;
+
+        let k = 4;
+
+        // the srs is written to a file with a larger k than needed
+        let path = std::env::temp_dir().join("halo2-experiments-srs-6");
+        gen_srs(6).write(&mut File::create(&path).unwrap()).unwrap();
+
+        let params = load_or_gen_srs(k, Some(&path)).unwrap();
+        assert_eq!(params.k(), k);
+
+        let circuit = Hash2Circuit {
+            a: Value::known(Fr::from(2)),
+            b: Value::known(Fr::from(7)),
+        };
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+        let public_input = vec![Fr::from(9)];
+        let proof = gen_proof(&params, &pk, circuit, &public_input);
+        assert!(verify_kzg_proof(
+            &params,
+            pk.get_vk(),
+            &[public_input],
+            &proof
+        ));
+
+        // the srs is too small for k = 8
+        assert!(matches!(
+            load_or_gen_srs(8, Some(&path)),
+            Err(ExperimentError::SrsTooSmall {
+                expected: 8,
+                found: 6
+            })
+        ));
+
+        // a missing file falls back to a fresh srs
+        let params = load_or_gen_srs(k, Some(Path::new("ptau/missing"))).unwrap();
+        assert_eq!(params.k(), k);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_deterministic_proof() {
+        use crate::circuits::utils::{gen_proof_with_rng, gen_srs, verify_kzg_proof};
+        use halo2_proofs::{
+            halo2curves::bn256::Fr,
+            plonk::{keygen_pk, keygen_vk},
+        };
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let k = 4;
+        let circuit = || Hash2Circuit {
+            a: Value::known(Fr::from(2)),
+            b: Value::known(Fr::from(7)),
+        };
+        let public_input = vec![Fr::from(9)];
+
+        let params = gen_srs(k);
+        let vk = keygen_vk(&params, &circuit()).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit()).unwrap();
+
+        let prove = |seed: u64| {
+            gen_proof_with_rng(
+                &params,
+                &pk,
+                circuit(),
+                &public_input,
+                StdRng::seed_from_u64(seed),
+            )
+        };
+
+        // same seed, same bytes
+        let proof = prove(42);
+        assert_eq!(proof, prove(42));
+        assert!(verify_kzg_proof(
+            &params,
+            pk.get_vk(),
+            &[public_input.clone()],
+            &proof
+        ));
+
+        // another seed gives other blinding factors
+        assert_ne!(proof, prove(43));
+    }
+
+    #[test]
+    fn test_gen_proof_ext() {
+        use crate::circuits::utils::{gen_proof_ext, gen_srs, verify_kzg_proof, CircuitExt};
+        use halo2_proofs::{
+            halo2curves::bn256::Fr,
+            plonk::{keygen_pk, keygen_vk},
+        };
+
+        let k = 4;
+        let circuit = Hash2Circuit {
+            a: Value::known(Fr::from(2)),
+            b: Value::known(Fr::from(7)),
+        };
+        let instances = circuit.instances();
+        assert_eq!(instances, vec![vec![Fr::from(9)]]);
+
+        let params = gen_srs(k);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+        // the public input is derived from the circuit, it is not passed by the caller
+        let proof = gen_proof_ext(&params, &pk, circuit);
+        assert!(verify_kzg_proof(&params, pk.get_vk(), &instances, &proof));
+        assert!(!verify_kzg_proof(
+            &params,
+            pk.get_vk(),
+            &[vec![Fr::from(8)]],
+            &proof
+        ));
+    }
 }
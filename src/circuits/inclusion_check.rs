@@ -1,17 +1,19 @@
 use super::super::chips::inclusion_check::{InclusionCheckChip, InclusionCheckConfig};
+use super::utils::NumPublic;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
 
 // define circuit struct using array of usernames and balances
-struct MyCircuit<F> {
+pub struct MyCircuit<F> {
     pub usernames: [Value<F>; 10],
     pub balances: [Value<F>; 10],
     pub inclusion_index: u8,
 }
 
-impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+impl<F: Field> Circuit<F> for MyCircuit<F> {
     type Config = InclusionCheckConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -65,11 +67,78 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     }
 }
 
+// the username and the balance of the inclusion row
+impl<F> NumPublic for MyCircuit<F> {
+    fn num_public(&self) -> usize {
+        2
+    }
+}
+
+// Same as `MyCircuit`, but the username and the balance are exposed at `username_row` and `balance_row`
+#[derive(Default)]
+struct InclusionAtRowsCircuit<F> {
+    pub usernames: [Value<F>; 10],
+    pub balances: [Value<F>; 10],
+    pub inclusion_index: u8,
+    pub username_row: usize,
+    pub balance_row: usize,
+}
+
+impl<F: Field> Circuit<F> for InclusionAtRowsCircuit<F> {
+    type Config = InclusionCheckConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            username_row: self.username_row,
+            balance_row: self.balance_row,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = InclusionCheckChip::<F>::construct(config);
+
+        for i in 0..self.usernames.len() {
+            if (i as u8) == self.inclusion_index {
+                let (username_cell, balance_cell) = chip.assign_inclusion_check_row(
+                    layouter.namespace(|| "inclusion row"),
+                    self.usernames[i],
+                    self.balances[i],
+                )?;
+
+                chip.expose_public_at(
+                    layouter.namespace(|| "expose public"),
+                    &username_cell,
+                    &balance_cell,
+                    self.username_row,
+                    self.balance_row,
+                )?;
+            } else {
+                chip.assign_generic_row(
+                    layouter.namespace(|| "generic row"),
+                    self.usernames[i],
+                    self.balances[i],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::MyCircuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use super::{InclusionAtRowsCircuit, MyCircuit};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
     #[test]
     fn test_inclusion_check_1() {
         let k = 4;
@@ -118,12 +187,44 @@ mod tests {
         let prover = MockProver::run(k, &circuit, vec![public_input_invalid2]).unwrap();
         assert!(prover.verify().is_err());
     }
+
+    #[test]
+    fn test_expose_public_at_rows() {
+        let k = 4;
+
+        let usernames = [0u64, 1, 2, 3, 4, 5, 6, 7, 8, 9].map(|i| Value::known(Fp::from(i)));
+        let balances = [0u64, 1, 2, 3, 4, 5, 6, 7, 8, 9].map(|i| Value::known(Fp::from(i * 2)));
+
+        let circuit = InclusionAtRowsCircuit::<Fp> {
+            usernames,
+            balances,
+            inclusion_index: 7,
+            username_row: 3,
+            balance_row: 4,
+        };
+
+        // rows 0, 1 and 2 are left to other public outputs
+        let public_input = vec![
+            Fp::from(0),
+            Fp::from(0),
+            Fp::from(0),
+            Fp::from(7),
+            Fp::from(14),
+        ];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the values at the default rows 0 and 1 are not checked anymore
+        let public_input_invalid = vec![Fp::from(7), Fp::from(14)];
+        let prover = MockProver::run(k, &circuit, vec![public_input_invalid]).unwrap();
+        assert!(prover.verify().is_err());
+    }
 }
 
 #[cfg(feature = "dev-graph")]
 #[test]
 fn print_inclusion_check() {
-    use halo2_proofs::halo2curves::pasta::Fp;
+    use halo2_proofs::halo2curves::bn256::Fr as Fp;
     use plotters::prelude::*;
 
     let root =
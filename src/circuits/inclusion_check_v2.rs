@@ -1,17 +1,21 @@
 use super::super::chips::inclusion_check_v2::{InclusionCheckV2Chip, InclusionCheckV2Config};
+use super::utils::NumPublic;
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
 // define circuit struct using array of usernames and balances
-struct MyCircuit<F> {
+pub struct MyCircuit<F> {
     pub usernames: [Value<F>; 10],
     pub balances: [Value<F>; 10],
     pub inclusion_index: u8,
     pub constant: F,
+    // if set, overrides the select bits derived from inclusion_index
+    pub select_bits: Option<[Value<F>; 10]>,
 }
 
-impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+impl<F: Field> Circuit<F> for MyCircuit<F> {
     type Config = InclusionCheckV2Config;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -24,6 +28,8 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
         let col_balance = meta.advice_column();
         let col_username_accumulator = meta.advice_column();
         let col_balance_accumulator = meta.advice_column();
+        let col_select = meta.advice_column();
+        let col_found = meta.advice_column();
         let instance = meta.instance_column();
                 
         // Create a fixed column to load constants.
@@ -37,6 +43,8 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
                 col_balance,
                 col_username_accumulator,
                 col_balance_accumulator,
+                col_select,
+                col_found,
             ],
             instance,
             constant
@@ -51,13 +59,22 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
         // We create a new instance of chip using the config passed as input
         let chip = InclusionCheckV2Chip::<F>::construct(config);
 
-        let (user_acc_last_row_cell, balance_acc_last_row_cell) = chip.assign_rows(
-            layouter.namespace(|| "init table"),
-            self.usernames,
-            self.balances,
-            self.constant,
-            self.inclusion_index,
-        )?;
+        let (user_acc_last_row_cell, balance_acc_last_row_cell, found_cell) = match self.select_bits {
+            Some(select_bits) => chip.assign_rows_with_select_bits(
+                layouter.namespace(|| "init table"),
+                self.usernames,
+                self.balances,
+                self.constant,
+                select_bits,
+            )?,
+            None => chip.assign_rows(
+                layouter.namespace(|| "init table"),
+                self.usernames,
+                self.balances,
+                self.constant,
+                self.inclusion_index,
+            )?,
+        };
 
         chip.expose_public(
             layouter.namespace(|| "expose public"),
@@ -69,29 +86,107 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
             &balance_acc_last_row_cell,
             1,
         )?;
+        chip.expose_public(layouter.namespace(|| "expose found"), &found_cell, 2)?;
 
         Ok(())
     }
 }
 
+// the last row of the username and balance accumulators and the found flag
+impl<F> NumPublic for MyCircuit<F> {
+    fn num_public(&self) -> usize {
+        3
+    }
+}
+
+// Same table as MyCircuit, split across regions of at most chunk_size entries
+struct ChunkedCircuit<const N: usize, F> {
+    usernames: [Value<F>; N],
+    balances: [Value<F>; N],
+    chunk_size: usize,
+    inclusion_index: usize,
+}
+
+impl<const N: usize, F: Field> Circuit<F> for ChunkedCircuit<N, F> {
+    type Config = InclusionCheckV2Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            usernames: [Value::unknown(); N],
+            balances: [Value::unknown(); N],
+            chunk_size: self.chunk_size,
+            inclusion_index: self.inclusion_index,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 6].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        InclusionCheckV2Chip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = InclusionCheckV2Chip::<F>::construct(config);
+
+        let (user_acc_last_row_cell, balance_acc_last_row_cell, found_cell) = chip
+            .assign_rows_chunked(
+                layouter.namespace(|| "init chunked table"),
+                &self.usernames,
+                &self.balances,
+                self.chunk_size,
+                self.inclusion_index,
+            )?;
+
+        chip.expose_public(
+            layouter.namespace(|| "expose public"),
+            &user_acc_last_row_cell,
+            0,
+        )?;
+        chip.expose_public(
+            layouter.namespace(|| "expose public"),
+            &balance_acc_last_row_cell,
+            1,
+        )?;
+        chip.expose_public(layouter.namespace(|| "expose found"), &found_cell, 2)
+    }
+}
+
+// The public inputs expected by MyCircuit: the username (row 0) and the balance (row 1)
+// of the entry at inclusion_index, which are the last rows of the accumulators, and the found flag (row 2)
+pub fn expected_public<F: Field>(
+    usernames: &[F],
+    balances: &[F],
+    inclusion_index: u8,
+) -> Vec<F> {
+    let i = inclusion_index as usize;
+    vec![usernames[i], balances[i], F::one()]
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::MyCircuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use super::{expected_public, ChunkedCircuit, MyCircuit};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
 
     #[test]
     fn test_inclusion_check_2() {
         let k = 5;
 
         // initate usernames and balances array
-        let mut usernames: [Value<Fp>; 10] = [Value::default(); 10];
-        let mut balances: [Value<Fp>; 10] = [Value::default(); 10];
+        let mut usernames: [Fp; 10] = [Fp::zero(); 10];
+        let mut balances: [Fp; 10] = [Fp::zero(); 10];
 
         // add 10 values to the username array and balances array
         for i in 0..10 {
-            usernames[i] = Value::known(Fp::from(i as u64));
-            balances[i] = Value::known(Fp::from(i as u64) * Fp::from(2));
+            usernames[i] = Fp::from(i as u64);
+            balances[i] = Fp::from(i as u64) * Fp::from(2);
         }
 
         // Table is
@@ -110,25 +205,142 @@ mod tests {
         let constant = Fp::from(0);
 
         let circuit = MyCircuit::<Fp> {
-            usernames,
-            balances,
+            usernames: usernames.map(Value::known),
+            balances: balances.map(Value::known),
             inclusion_index: 7,
             constant,
+            select_bits: None,
         };
 
         // Test 1 - Inclusion check on a existing entry for the corresponding inclusion_index
-        let public_input_valid = vec![Fp::from(7), Fp::from(14)];
+        let public_input_valid = expected_public(&usernames, &balances, 7);
+        assert_eq!(public_input_valid, vec![Fp::from(7), Fp::from(14), Fp::one()]);
         let prover = MockProver::run(k, &circuit, vec![public_input_valid]).unwrap();
         prover.assert_satisfied();
 
         // Test 2 - Inclusion check on a existing entry but not for the corresponding inclusion_index
-        let public_input_invalid = vec![Fp::from(8), Fp::from(16)];
+        let public_input_invalid = expected_public(&usernames, &balances, 8);
         let prover = MockProver::run(k, &circuit, vec![public_input_invalid]).unwrap();
         assert!(prover.verify().is_err());
 
         // Test 3 - Inclusion check on a non-existing entry
-        let public_input_invalid2 = vec![Fp::from(10), Fp::from(20)];
+        let public_input_invalid2 = vec![Fp::from(10), Fp::from(20), Fp::one()];
         let prover = MockProver::run(k, &circuit, vec![public_input_invalid2]).unwrap();
         assert!(prover.verify().is_err());
     }
+
+    #[test]
+    fn test_inclusion_check_2_no_false_inclusion_from_sum() {
+        let k = 5;
+
+        let mut usernames: [Fp; 10] = [Fp::zero(); 10];
+        let mut balances: [Fp; 10] = [Fp::zero(); 10];
+
+        for i in 0..10 {
+            usernames[i] = Fp::from(i as u64);
+            balances[i] = Fp::from(i as u64) * Fp::from(2);
+        }
+
+        // turn on the select bit for both username 3 and username 4
+        let mut select_bits: [Value<Fp>; 10] = [Value::known(Fp::zero()); 10];
+        select_bits[3] = Value::known(Fp::one());
+        select_bits[4] = Value::known(Fp::one());
+
+        let circuit = MyCircuit::<Fp> {
+            usernames: usernames.map(Value::known),
+            balances: balances.map(Value::known),
+            inclusion_index: 0,
+            constant: Fp::from(0),
+            select_bits: Some(select_bits),
+        };
+
+        // with an additive accumulator 3 + 4 would have produced a false inclusion of username 7
+        let public_input_invalid = expected_public(&usernames, &balances, 7);
+        let prover = MockProver::run(k, &circuit, vec![public_input_invalid]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // the accumulator only holds the last selected row
+        let public_input_valid = expected_public(&usernames, &balances, 4);
+        let prover = MockProver::run(k, &circuit, vec![public_input_valid]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_inclusion_check_2_zero_valued_entry() {
+        let k = 5;
+
+        // the entry at index 0 has username 0 and balance 0, the same as the initial accumulators
+        let usernames: [Fp; 10] = [0u64, 1, 2, 3, 4, 5, 6, 7, 8, 9].map(Fp::from);
+        let balances: [Fp; 10] = [0u64, 2, 4, 6, 8, 10, 12, 14, 16, 18].map(Fp::from);
+
+        let circuit = MyCircuit::<Fp> {
+            usernames: usernames.map(Value::known),
+            balances: balances.map(Value::known),
+            inclusion_index: 0,
+            constant: Fp::from(0),
+            select_bits: None,
+        };
+
+        let public_input_valid = expected_public(&usernames, &balances, 0);
+        assert_eq!(public_input_valid, vec![Fp::zero(), Fp::zero(), Fp::one()]);
+        let prover = MockProver::run(k, &circuit, vec![public_input_valid]).unwrap();
+        prover.assert_satisfied();
+
+        // the zero entry can't be reported as a miss
+        let public_input_invalid = vec![Fp::zero(), Fp::zero(), Fp::zero()];
+        let prover = MockProver::run(k, &circuit, vec![public_input_invalid]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // without any select bit on, the accumulators keep the same zero values, but nothing is found
+        let circuit = MyCircuit::<Fp> {
+            usernames: usernames.map(Value::known),
+            balances: balances.map(Value::known),
+            inclusion_index: 0,
+            constant: Fp::from(0),
+            select_bits: Some([Value::known(Fp::zero()); 10]),
+        };
+
+        let public_input_miss = vec![Fp::zero(), Fp::zero(), Fp::zero()];
+        let prover = MockProver::run(k, &circuit, vec![public_input_miss]).unwrap();
+        prover.assert_satisfied();
+
+        let public_input_invalid = vec![Fp::zero(), Fp::zero(), Fp::one()];
+        let prover = MockProver::run(k, &circuit, vec![public_input_invalid]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_inclusion_check_2_chunked() {
+        let k = 5;
+
+        let usernames: [Fp; 9] = [0u64, 1, 2, 3, 4, 5, 6, 7, 8].map(Fp::from);
+        let balances: [Fp; 9] = [0u64, 2, 4, 6, 8, 10, 12, 14, 16].map(Fp::from);
+
+        // 9 entries in chunks of 3 are assigned in three regions, each one starting from the last row
+        // of the accumulators and of the found flag of the previous chunk
+        for inclusion_index in [1, 4, 8] {
+            let circuit = ChunkedCircuit::<9, Fp> {
+                usernames: usernames.map(Value::known),
+                balances: balances.map(Value::known),
+                chunk_size: 3,
+                inclusion_index,
+            };
+
+            let public_input_valid = expected_public(&usernames, &balances, inclusion_index as u8);
+            let prover = MockProver::run(k, &circuit, vec![public_input_valid]).unwrap();
+            prover.assert_satisfied();
+
+            // the accumulators carried to the next chunks can't be replaced by another entry
+            let public_input_invalid = expected_public(&usernames, &balances, 5);
+            let prover = MockProver::run(k, &circuit, vec![public_input_invalid]).unwrap();
+            assert!(prover.verify().is_err());
+
+            // the found flag carried to the next chunks can't be reset
+            let mut public_input_invalid =
+                expected_public(&usernames, &balances, inclusion_index as u8);
+            public_input_invalid[2] = Fp::zero();
+            let prover = MockProver::run(k, &circuit, vec![public_input_invalid]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
 }
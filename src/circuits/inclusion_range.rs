@@ -0,0 +1,230 @@
+use super::super::chips::inclusion_check_v2::{InclusionCheckV2Chip, InclusionCheckV2Config};
+use eth_types::Field;
+use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
+use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+#[derive(Default)]
+// define circuit struct using array of usernames and balances, the cap is a public input
+struct InclusionRangeCircuit<F> {
+    pub usernames: [Value<F>; 10],
+    pub balances: [Value<F>; 10],
+    pub inclusion_index: u8,
+    pub cap: F,
+}
+
+#[derive(Clone, Debug)]
+struct InclusionRangeConfig<F: Field> {
+    inclusion_config: InclusionCheckV2Config,
+    q_lt: Selector,
+    balance: Column<Advice>,
+    cap: Column<Advice>,
+    check: Column<Advice>,
+    lt_config: LtConfig<F, 8>,
+    instance: Column<Instance>,
+}
+
+impl<F: Field> Circuit<F> for InclusionRangeCircuit<F> {
+    type Config = InclusionRangeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_username = meta.advice_column();
+        let col_balance = meta.advice_column();
+        let col_username_accumulator = meta.advice_column();
+        let col_balance_accumulator = meta.advice_column();
+        let col_select = meta.advice_column();
+        let col_found = meta.advice_column();
+        let constant = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        let inclusion_config = InclusionCheckV2Chip::configure(
+            meta,
+            [
+                col_username,
+                col_balance,
+                col_username_accumulator,
+                col_balance_accumulator,
+                col_select,
+                col_found,
+            ],
+            instance,
+            constant,
+        );
+
+        let q_lt = meta.complex_selector();
+        let balance = meta.advice_column();
+        let cap = meta.advice_column();
+        let check = meta.advice_column();
+
+        // balance is copied from the inclusion table, cap is copied from the instance column and check is exposed
+        meta.enable_equality(balance);
+        meta.enable_equality(cap);
+        meta.enable_equality(check);
+
+        let lt_config = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(q_lt),
+            |meta| meta.query_advice(balance, Rotation::cur()),
+            |meta| meta.query_advice(cap, Rotation::cur()),
+        );
+
+        meta.create_gate(
+            "verifies that `check` is equal to is_lt from LtChip",
+            |meta| {
+                let q_enable = meta.query_selector(q_lt);
+                let check = meta.query_advice(check, Rotation::cur());
+
+                vec![q_enable * (lt_config.is_lt(meta, None) - check)]
+            },
+        );
+
+        InclusionRangeConfig {
+            inclusion_config,
+            q_lt,
+            balance,
+            cap,
+            check,
+            lt_config,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let inclusion_chip = InclusionCheckV2Chip::<F>::construct(config.inclusion_config);
+
+        let (username_cell, balance_cell, _) = inclusion_chip.assign_rows(
+            layouter.namespace(|| "init table"),
+            self.usernames,
+            self.balances,
+            F::zero(),
+            self.inclusion_index,
+        )?;
+
+        // the username is public, while the balance stays private
+        inclusion_chip.expose_public(
+            layouter.namespace(|| "public username"),
+            &username_cell,
+            0,
+        )?;
+
+        let lt_chip = LtChip::construct(config.lt_config);
+        lt_chip.load(&mut layouter)?;
+
+        let check_cell = layouter.assign_region(
+            || "balance less than cap",
+            |mut region| {
+                balance_cell.copy_advice(
+                    || "copy included balance",
+                    &mut region,
+                    config.balance,
+                    0,
+                )?;
+
+                // copy the cap from instance column
+                region.assign_advice_from_instance(
+                    || "copy cap",
+                    config.instance,
+                    1,
+                    config.cap,
+                    0,
+                )?;
+
+                let mut balance = F::zero();
+                balance_cell.value().map(|f| balance = *f);
+
+                let check_cell = region.assign_advice(
+                    || "check",
+                    config.check,
+                    0,
+                    || Value::known(F::from((balance < self.cap) as u64)),
+                )?;
+
+                config.q_lt.enable(&mut region, 0)?;
+
+                lt_chip.assign(&mut region, 0, balance, self.cap)?;
+
+                Ok(check_cell)
+            },
+        )?;
+
+        layouter.constrain_instance(check_cell.cell(), config.instance, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InclusionRangeCircuit;
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+
+    fn instantiate_circuit(inclusion_index: u8, cap: Fp) -> InclusionRangeCircuit<Fp> {
+        let mut usernames: [Value<Fp>; 10] = [Value::default(); 10];
+        let mut balances: [Value<Fp>; 10] = [Value::default(); 10];
+
+        // Table is
+        // username | balance
+        // 0        | 0
+        // 1        | 2
+        // ...
+        // 9        | 18
+        for i in 0..10 {
+            usernames[i] = Value::known(Fp::from(i as u64));
+            balances[i] = Value::known(Fp::from(i as u64) * Fp::from(2));
+        }
+
+        InclusionRangeCircuit {
+            usernames,
+            balances,
+            inclusion_index,
+            cap,
+        }
+    }
+
+    #[test]
+    fn test_included_and_under_cap() {
+        let k = 9;
+
+        // balance of username 7 is 14
+        let circuit = instantiate_circuit(7, Fp::from(20));
+
+        let public_input = vec![Fp::from(7), Fp::from(20), Fp::from(1)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_included_and_over_cap() {
+        let k = 9;
+
+        let circuit = instantiate_circuit(7, Fp::from(10));
+
+        // the circuit exposes 0 as the balance is not less than the cap
+        let public_input = vec![Fp::from(7), Fp::from(10), Fp::from(0)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // claiming to be under the cap fails
+        let public_input = vec![Fp::from(7), Fp::from(10), Fp::from(1)];
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_not_included() {
+        let k = 9;
+
+        let circuit = instantiate_circuit(7, Fp::from(20));
+
+        // username 10 is not in the table
+        let public_input = vec![Fp::from(10), Fp::from(20), Fp::from(1)];
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
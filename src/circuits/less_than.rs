@@ -1,6 +1,9 @@
-use super::super::chips::less_than::{LessThanChip, LessThanConfig};
+use super::super::chips::less_than::{
+    LessThanChip, LessThanConfig, LessThanFixedChip, LessThanFixedConfig,
+};
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
 
@@ -9,7 +12,7 @@ struct MyCircuit<F> {
     pub input: Value<F>,
 }
 
-impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+impl<F: Field> Circuit<F> for MyCircuit<F> {
     type Config = LessThanConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -39,11 +42,89 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     }
 }
 
+#[derive(Debug, Clone)]
+struct FoundFlagConfig {
+    less_than_config: LessThanConfig,
+    instance: Column<Instance>,
+}
+
+// same as MyCircuit, the found flag is exposed at row 0 of a second instance column
+#[derive(Default)]
+struct FoundFlagCircuit<F> {
+    pub input: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for FoundFlagCircuit<F> {
+    type Config = FoundFlagConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let less_than_config = MyCircuit::<F>::configure(meta);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        FoundFlagConfig {
+            less_than_config,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = LessThanChip::<F>::construct(config.less_than_config);
+
+        let found = chip.assign(layouter.namespace(|| "init table"), self.input)?;
+
+        layouter.constrain_instance(found.cell(), config.instance, 0)
+    }
+}
+
+// the table from 0 to (TARGET-1) is a fixed column of the circuit
+const TARGET: usize = 800;
+
+#[derive(Default)]
+struct FixedTableCircuit<F> {
+    pub input: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for FixedTableCircuit<F> {
+    type Config = LessThanFixedConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let input = meta.advice_column();
+
+        LessThanFixedChip::configure(meta, input, TARGET)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = LessThanFixedChip::<F>::construct(config);
+
+        chip.load(&mut layouter)?;
+        chip.assign(layouter.namespace(|| "input"), self.input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::MyCircuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use super::{FixedTableCircuit, FoundFlagCircuit, MyCircuit};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
     #[test]
     fn test_less_than_2() {
         let k = 10;
@@ -80,4 +161,49 @@ mod tests {
         assert!(invalid_prover.verify().is_err());
 
     }
+
+    #[test]
+    fn test_less_than_found_flag() {
+        let k = 10;
+
+        let table = (0..800u64).map(Fp::from).collect::<Vec<_>>();
+
+        // 755 is in the table, the flag is exposed as 1
+        let circuit = FoundFlagCircuit::<Fp> {
+            input: Value::known(Fp::from(755)),
+        };
+        let prover = MockProver::run(k, &circuit, vec![table.clone(), vec![Fp::from(1)]]).unwrap();
+        prover.assert_satisfied();
+
+        // the flag can't be exposed as 0
+        let invalid_prover =
+            MockProver::run(k, &circuit, vec![table.clone(), vec![Fp::from(0)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+
+        // 800 is not in the table, no proof of the flag
+        let circuit = FoundFlagCircuit::<Fp> {
+            input: Value::known(Fp::from(800)),
+        };
+        let invalid_prover = MockProver::run(k, &circuit, vec![table, vec![Fp::from(1)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_less_than_fixed_table() {
+        let k = 10;
+
+        // 755 < 800, the table is not passed as public input
+        let circuit = FixedTableCircuit::<Fp> {
+            input: Value::known(Fp::from(755)),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // 800 is not in the table
+        let circuit = FixedTableCircuit::<Fp> {
+            input: Value::known(Fp::from(800)),
+        };
+        let invalid_prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
 }
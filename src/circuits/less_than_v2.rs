@@ -1,3 +1,7 @@
+use super::super::chips::hash_v2::{Hash2Chip, Hash2Config};
+use super::super::chips::less_than_v2::{
+    configure_lt_for_max, required_bytes, LessThanV2Chip, LessThanV2Config,
+};
 use eth_types::Field;
 use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
 use std::marker::PhantomData;
@@ -108,11 +112,333 @@ impl<F: Field> Circuit<F> for MyCircuit<F> {
     }
 }
 
+#[derive(Default)]
+// compares the output of the dummy hash against a constant bound, the check is exposed as public input
+struct HashBoundCircuit<F> {
+    pub a: Value<F>,
+    pub b: Value<F>,
+    pub bound: u64,
+}
+
+#[derive(Clone, Debug)]
+struct HashBoundConfig<F: Field> {
+    hash_config: Hash2Config,
+    lt_config: LessThanV2Config<F, 8>,
+}
+
+impl<F: Field> Circuit<F> for HashBoundCircuit<F> {
+    type Config = HashBoundConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let instance = meta.instance_column();
+
+        let hash_config = Hash2Chip::configure(meta, [col_a, col_b, col_c], instance);
+
+        let lhs = meta.advice_column();
+        let rhs = meta.fixed_column();
+        let check = meta.advice_column();
+
+        let lt_config = LessThanV2Chip::configure(meta, lhs, rhs, check);
+
+        HashBoundConfig {
+            hash_config,
+            lt_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let hash_chip = Hash2Chip::construct(config.hash_config);
+        let lt_chip = LessThanV2Chip::construct(config.lt_config);
+
+        lt_chip.load(&mut layouter)?;
+
+        let a = hash_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = hash_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+        let c = hash_chip.hash(layouter.namespace(|| "load row"), a, b)?;
+
+        let check = layouter.assign_region(
+            || "hash output less than bound",
+            |mut region| lt_chip.assign_cell_vs_const(&mut region, 0, &c, F::from(self.bound)),
+        )?;
+
+        hash_chip.expose_public(layouter.namespace(|| "public check"), &check, 0)
+    }
+}
+
+#[derive(Default)]
+// compares two private values and exposes the lt flag followed by the diff bytes of LtChip
+struct DiffBytesCircuit<F> {
+    pub lhs: u64,
+    pub rhs: u64,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Clone, Debug)]
+struct DiffBytesConfig<F: Field> {
+    lt_config: LessThanV2Config<F, 8>,
+    instance: Column<Instance>,
+}
+
+impl<F: Field> Circuit<F> for DiffBytesCircuit<F> {
+    type Config = DiffBytesConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let lhs = meta.advice_column();
+        let rhs = meta.fixed_column();
+        let check = meta.advice_column();
+        let instance = meta.instance_column();
+
+        let lt_config = LessThanV2Chip::configure(meta, lhs, rhs, check);
+
+        // the diff bytes are exposed
+        for diff in lt_config.lt_config.diff {
+            meta.enable_equality(diff);
+        }
+        meta.enable_equality(lt_config.lt_config.lt);
+        meta.enable_equality(instance);
+
+        DiffBytesConfig {
+            lt_config,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let lt_chip = LessThanV2Chip::construct(config.lt_config.clone());
+
+        lt_chip.load(&mut layouter)?;
+
+        let lhs = F::from(self.lhs);
+        let rhs = F::from(self.rhs);
+
+        let (lt_cell, diff_cells) = layouter.assign_region(
+            || "compare",
+            |mut region| {
+                config.lt_config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "lhs", config.lt_config.lhs, 0, || Value::known(lhs))?;
+                region.assign_fixed(|| "rhs", config.lt_config.rhs, 0, || Value::known(rhs))?;
+                region.assign_advice(
+                    || "check",
+                    config.lt_config.check,
+                    0,
+                    || Value::known(F::from((lhs < rhs) as u64)),
+                )?;
+
+                lt_chip.assign(&mut region, 0, lhs, rhs)
+            },
+        )?;
+
+        layouter.constrain_instance(lt_cell.cell(), config.instance, 0)?;
+        for (i, cell) in diff_cells.iter().enumerate() {
+            layouter.constrain_instance(cell.cell(), config.instance, i + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+// proves that a private value is greater than or equal to a constant bound
+struct GteCircuit<F> {
+    pub value: u64,
+    pub bound: u64,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Clone, Debug)]
+struct GteConfig<F: Field> {
+    lt_config: LessThanV2Config<F, 8>,
+    value: Column<Advice>,
+}
+
+impl<F: Field> Circuit<F> for GteCircuit<F> {
+    type Config = GteConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        meta.enable_equality(value);
+
+        let lhs = meta.advice_column();
+        let rhs = meta.fixed_column();
+        let check = meta.advice_column();
+
+        let lt_config = LessThanV2Chip::configure(meta, lhs, rhs, check);
+
+        GteConfig { lt_config, value }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let lt_chip = LessThanV2Chip::construct(config.lt_config);
+
+        lt_chip.load(&mut layouter)?;
+
+        let value = layouter.assign_region(
+            || "load value",
+            |mut region| {
+                region.assign_advice(
+                    || "value",
+                    config.value,
+                    0,
+                    || Value::known(F::from(self.value)),
+                )
+            },
+        )?;
+
+        layouter.assign_region(
+            || "value not less than bound",
+            |mut region| lt_chip.assign_gte(&mut region, 0, &value, F::from(self.bound)),
+        )
+    }
+}
+
+// bound on the values compared by `BoundedLtCircuit`, the LtChip is sized from it
+const MAX_VALUE: u128 = 1_000_000;
+
+#[derive(Default)]
+// same as `MyCircuit`, with N_BYTES derived from MAX_VALUE instead of being picked by hand
+struct BoundedLtCircuit<F> {
+    pub value_l: u64,
+    pub value_r: u64,
+    pub check: bool,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Clone, Debug)]
+struct BoundedLtConfig<F: Field> {
+    q_enable: Selector,
+    value_l: Column<Advice>,
+    value_r: Column<Advice>,
+    check: Column<Advice>,
+    lt: LtConfig<F, { required_bytes(MAX_VALUE) }>,
+}
+
+impl<F: Field> Circuit<F> for BoundedLtCircuit<F> {
+    type Config = BoundedLtConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let q_enable = meta.complex_selector();
+        let value_l = meta.advice_column();
+        let value_r = meta.advice_column();
+        let check = meta.advice_column();
+
+        let lt = configure_lt_for_max(
+            meta,
+            |meta| meta.query_selector(q_enable),
+            |meta| meta.query_advice(value_l, Rotation::cur()),
+            |meta| meta.query_advice(value_r, Rotation::cur()),
+            MAX_VALUE,
+        );
+
+        meta.create_gate(
+            "verifies that `check` is equal to is_lt from LtChip",
+            |meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let check = meta.query_advice(check, Rotation::cur());
+
+                vec![q_enable * (lt.is_lt(meta, None) - check)]
+            },
+        );
+
+        BoundedLtConfig {
+            q_enable,
+            value_l,
+            value_r,
+            check,
+            lt,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = LtChip::construct(config.lt);
+
+        chip.load(&mut layouter)?;
+
+        layouter.assign_region(
+            || "witness",
+            |mut region| {
+                region.assign_advice(
+                    || "value left",
+                    config.value_l,
+                    0,
+                    || Value::known(F::from(self.value_l)),
+                )?;
+                region.assign_advice(
+                    || "value right",
+                    config.value_r,
+                    0,
+                    || Value::known(F::from(self.value_r)),
+                )?;
+                region.assign_advice(
+                    || "check",
+                    config.check,
+                    0,
+                    || Value::known(F::from(self.check as u64)),
+                )?;
+
+                config.q_enable.enable(&mut region, 0)?;
+
+                chip.assign(&mut region, 0, F::from(self.value_l), F::from(self.value_r))
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    use super::super::super::chips::less_than_v2::{configure_lt_for_max, required_bytes};
+    use super::BoundedLtCircuit;
+    use super::DiffBytesCircuit;
+    use super::GteCircuit;
+    use super::HashBoundCircuit;
     use super::MyCircuit;
-    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as Fp};
+    use super::MAX_VALUE;
+    use gadgets::less_than::LtConfig;
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        halo2curves::bn256::Fr as Fp,
+        plonk::{ConstraintSystem, Expression},
+    };
     use std::marker::PhantomData;
 
     #[test]
@@ -150,4 +476,150 @@ mod tests {
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
     }
+
+    #[test]
+    fn test_hash_output_vs_const() {
+        let k = 9;
+
+        // hash output is 2 + 7 = 9
+        let circuit = HashBoundCircuit::<Fp> {
+            a: Value::known(Fp::from(2)),
+            b: Value::known(Fp::from(7)),
+            bound: 10,
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // the hash output is not less than the bound
+        let circuit = HashBoundCircuit::<Fp> {
+            a: Value::known(Fp::from(2)),
+            b: Value::known(Fp::from(7)),
+            bound: 9,
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_diff_bytes() {
+        let k = 9;
+
+        // 0x1234 - 0x34 = 0x1200, lt is 0
+        let circuit = DiffBytesCircuit::<Fp> {
+            lhs: 0x1234,
+            rhs: 0x34,
+            _marker: PhantomData,
+        };
+        let public_input = [0u64, 0x00, 0x12, 0, 0, 0, 0, 0, 0].map(Fp::from).to_vec();
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // 5 - 10 + 2^64 = 0xfffffffffffffffb, lt is 1
+        let circuit = DiffBytesCircuit::<Fp> {
+            lhs: 5,
+            rhs: 10,
+            _marker: PhantomData,
+        };
+        let public_input = [1u64, 0xfb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+            .map(Fp::from)
+            .to_vec();
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // a wrong byte fails
+        let mut invalid_input = public_input;
+        invalid_input[1] = Fp::from(0xfa);
+        let prover = MockProver::run(k, &circuit, vec![invalid_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_gte() {
+        let k = 9;
+
+        // equal and greater values are not less than the bound
+        for value in [10, 11, 1 << 40] {
+            let circuit = GteCircuit::<Fp> {
+                value,
+                bound: 10,
+                _marker: PhantomData,
+            };
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // a value less than the bound fails, as the lt flag must be 0
+        let circuit = GteCircuit::<Fp> {
+            value: 9,
+            bound: 10,
+            _marker: PhantomData,
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_required_bytes() {
+        assert_eq!(required_bytes(0), 1);
+        assert_eq!(required_bytes(0xff), 1);
+        assert_eq!(required_bytes(0x100), 2);
+        assert_eq!(required_bytes(0xffff), 2);
+        assert_eq!(required_bytes(u64::MAX as u128), 8);
+        assert_eq!(required_bytes(u64::MAX as u128 + 1), 9);
+        assert_eq!(required_bytes(u128::MAX), 16);
+
+        // 1_000_000 < 2^20
+        assert_eq!(required_bytes(MAX_VALUE), 3);
+    }
+
+    #[test]
+    fn test_less_than_up_to_max() {
+        let k = 9;
+        let max = MAX_VALUE as u64;
+
+        let verify = |value_l: u64, value_r: u64, check: bool| {
+            let circuit = BoundedLtCircuit::<Fp> {
+                value_l,
+                value_r,
+                check,
+                _marker: PhantomData,
+            };
+            MockProver::run(k, &circuit, vec![])
+                .unwrap()
+                .verify()
+                .is_ok()
+        };
+
+        // the comparison is sound for every pair of values up to the declared max
+        for (value_l, value_r) in [(0, max), (max - 1, max), (max, max), (max, 0), (1, 0)] {
+            let check = value_l < value_r;
+            assert!(verify(value_l, value_r, check));
+            assert!(!verify(value_l, value_r, !check));
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "N_BYTES = 2 is too small for values up to 1000000, at least 3 bytes are required"
+    )]
+    fn test_configure_lt_for_max_too_small() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let q_enable = meta.complex_selector();
+
+        let _: LtConfig<Fp, 2> = configure_lt_for_max(
+            &mut meta,
+            |meta| meta.query_selector(q_enable),
+            |_| Expression::Constant(Fp::zero()),
+            |_| Expression::Constant(Fp::one()),
+            MAX_VALUE,
+        );
+    }
 }
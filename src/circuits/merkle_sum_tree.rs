@@ -1,25 +1,135 @@
 use super::super::chips::merkle_sum_tree::{MerkleSumTreeChip, MerkleSumTreeConfig};
+use super::super::error::{check_path_lengths, ExperimentError};
+use super::utils::NumPublic;
+use crate::merkle_sum_tree::{hash_nodes, MerkleProof, Node};
 use eth_types::Field;
-use halo2_proofs::{circuit::*, plonk::*};
+use gadgets::less_than::{LtChip, LtInstruction};
+use halo2_proofs::{circuit::*, halo2curves::bn256::Fr as Fp, plonk::*};
 use std::marker::PhantomData;
 
 #[derive(Default)]
-struct MerkleSumTreeCircuit<F: Field> {
+pub struct MerkleSumTreeCircuit<F: Field> {
     pub leaf_hash: F,
     pub leaf_balance: F,
     pub path_element_hashes: Vec<F>,
     pub path_element_balances: Vec<F>,
     pub path_indices: Vec<F>,
     pub assets_sum: F,
+    // standard membership proof: only the leaf hash (row 0) and the root hash (row 1) are public, the balances
+    // stay private and they are not compared against the assets sum
+    pub membership_only: bool,
+    // leaf binding mode: the leaf hash is computed inside the circuit as `hash_leaf(username, leaf_balance)`,
+    // so the public leaf hash (row 0) can only be paired with the balance it was hashed with
+    pub leaf_username: Option<F>,
     _marker: PhantomData<F>,
 }
 
+impl<F: Field> MerkleSumTreeCircuit<F> {
+    // Builds the circuit in the full mode, used by the tests and by the benchmarks of `benches/proving.rs`
+    pub fn new(
+        leaf_hash: F,
+        leaf_balance: F,
+        path_element_hashes: Vec<F>,
+        path_element_balances: Vec<F>,
+        path_indices: Vec<F>,
+        assets_sum: F,
+    ) -> Self {
+        Self {
+            leaf_hash,
+            leaf_balance,
+            path_element_hashes,
+            path_element_balances,
+            path_indices,
+            assets_sum,
+            membership_only: false,
+            leaf_username: None,
+            _marker: PhantomData,
+        }
+    }
+
+    // Checks that the path is not empty and that there is one balance and one index per path element
+    pub fn validate(&self) -> Result<(), ExperimentError> {
+        check_path_lengths(
+            self.path_element_hashes.len(),
+            &[
+                ("path_element_balances", self.path_element_balances.len()),
+                ("path_indices", self.path_indices.len()),
+            ],
+        )
+    }
+}
+
+impl MerkleSumTreeCircuit<Fp> {
+    // Builds the circuit in the full mode from the membership proof of a leaf. `total_assets` is the
+    // `assets_sum` compared against the sum of the whole tree, which is `total_liabilities` of all the leaves
+    pub fn from_proof(proof: &MerkleProof, total_assets: Fp) -> Self {
+        Self::new(
+            proof.leaf.hash,
+            proof.leaf.balance,
+            proof.path_elements.iter().map(|node| node.hash).collect(),
+            proof
+                .path_elements
+                .iter()
+                .map(|node| node.balance)
+                .collect(),
+            proof.path_indices.clone(),
+            total_assets,
+        )
+    }
+
+    // Returns the public inputs in the same order as they are exposed by `synthesize`:
+    // leaf hash (row 0), leaf balance (row 1), root hash (row 2), assets sum (row 3) and zero balance flag (row 4),
+    // or leaf hash (row 0) and root hash (row 1) in membership only mode
+    pub fn public_inputs(&self) -> Vec<Fp> {
+        let mut node = Node {
+            hash: self.leaf_hash,
+            balance: self.leaf_balance,
+        };
+
+        for i in 0..self.path_element_hashes.len() {
+            let element = Node {
+                hash: self.path_element_hashes[i],
+                balance: self.path_element_balances[i],
+            };
+
+            node = if self.path_indices[i] == Fp::zero() {
+                hash_nodes(&node, &element)
+            } else {
+                hash_nodes(&element, &node)
+            };
+        }
+
+        if self.membership_only {
+            return vec![self.leaf_hash, node.hash];
+        }
+
+        let zero_balance_flag = if self.leaf_balance == Fp::zero() {
+            Fp::one()
+        } else {
+            Fp::zero()
+        };
+
+        vec![
+            self.leaf_hash,
+            self.leaf_balance,
+            node.hash,
+            self.assets_sum,
+            zero_balance_flag,
+        ]
+    }
+}
+
 impl<F: Field> Circuit<F> for MerkleSumTreeCircuit<F> {
     type Config = MerkleSumTreeConfig<F>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        // the modes change the copy constraints to the instance column and the leaf regions
+        Self {
+            membership_only: self.membership_only,
+            leaf_username: self.leaf_username.map(|_| F::zero()),
+            ..Self::default()
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -40,24 +150,39 @@ impl<F: Field> Circuit<F> for MerkleSumTreeCircuit<F> {
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
+        // a shape error is returned as `Error::Synthesis` instead of panicking on the path vectors
+        self.validate()?;
+
         let chip = MerkleSumTreeChip::construct(config);
-        let (leaf_hash, leaf_balance) = chip.assing_leaf_hash_and_balance(
-            layouter.namespace(|| "assign leaf"),
-            F::from(self.leaf_hash),
-            F::from(self.leaf_balance),
-        )?;
+        chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+
+        let (leaf_hash, leaf_balance) = match self.leaf_username {
+            Some(username) => chip.assign_and_hash_leaf(
+                layouter.namespace(|| "hash leaf"),
+                username,
+                self.leaf_balance,
+            )?,
+            None => chip.assing_leaf_hash_and_balance(
+                layouter.namespace(|| "assign leaf"),
+                F::from(self.leaf_hash),
+                F::from(self.leaf_balance),
+            )?,
+        };
 
         chip.expose_public(layouter.namespace(|| "public leaf hash"), &leaf_hash, 0)?;
-        chip.expose_public(
-            layouter.namespace(|| "public leaf balance"),
-            &leaf_balance,
-            1,
-        )?;
+        if !self.membership_only {
+            chip.expose_public(
+                layouter.namespace(|| "public leaf balance"),
+                &leaf_balance,
+                1,
+            )?;
+        }
 
         // apply it for level 0 of the merkle tree
         // node cells passed as inputs are the leaf_hash cell and the leaf_balance cell
         let (mut next_hash, mut next_sum) = chip.merkle_prove_layer(
             layouter.namespace(|| format!("level {} merkle proof", 0)),
+            0,
             &leaf_hash,
             &leaf_balance,
             self.path_element_hashes[0],
@@ -70,6 +195,7 @@ impl<F: Field> Circuit<F> for MerkleSumTreeCircuit<F> {
         for i in 1..self.path_element_balances.len() {
             (next_hash, next_sum) = chip.merkle_prove_layer(
                 layouter.namespace(|| format!("level {} merkle proof", i)),
+                i,
                 &next_hash,
                 &next_sum,
                 self.path_element_hashes[i],
@@ -78,7 +204,12 @@ impl<F: Field> Circuit<F> for MerkleSumTreeCircuit<F> {
             )?;
         }
 
-        // compute the sum of the merkle sum tree as sum of the leaf balance and the sum of the path elements balances
+        if self.membership_only {
+            return chip.expose_public(layouter.namespace(|| "public root"), &next_hash, 1);
+        }
+
+        // the sum of the whole tree is the leaf balance plus the path elements balances, as every path element
+        // is the sum of a subtree, see `total_liabilities`
         let computed_sum = self.leaf_balance
             + self
                 .path_element_balances
@@ -94,30 +225,315 @@ impl<F: Field> Circuit<F> for MerkleSumTreeCircuit<F> {
         )?;
 
         chip.expose_public(layouter.namespace(|| "public root"), &next_hash, 2)?;
+
+        // flag whether the leaf is an empty account
+        let zero_balance_flag = chip
+            .assign_zero_balance_flag(layouter.namespace(|| "zero balance flag"), &leaf_balance)?;
+        chip.expose_public(
+            layouter.namespace(|| "public zero balance flag"),
+            &zero_balance_flag,
+            4,
+        )?;
         Ok(())
     }
 }
 
+// leaf hash, leaf balance, root hash, assets sum and zero balance flag, see `public_inputs`
+impl<F: Field> NumPublic for MerkleSumTreeCircuit<F> {
+    fn num_public(&self) -> usize {
+        if self.membership_only {
+            2
+        } else {
+            5
+        }
+    }
+}
+
+// Proves that a leaf of the tree has a balance greater than a public threshold without revealing which one:
+// the leaf hash and the leaf balance are private witnesses, only the root hash (row 0) and the threshold (row 1) are public
+#[derive(Default)]
+struct MerkleSumTreePrivateCircuit<F: Field> {
+    pub leaf_hash: F,
+    pub leaf_balance: F,
+    pub path_element_hashes: Vec<F>,
+    pub path_element_balances: Vec<F>,
+    pub path_indices: Vec<F>,
+    pub threshold: F,
+}
+
+impl<F: Field> Circuit<F> for MerkleSumTreePrivateCircuit<F> {
+    type Config = MerkleSumTreeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MerkleSumTreeCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        check_path_lengths(
+            self.path_element_hashes.len(),
+            &[
+                ("path_element_balances", self.path_element_balances.len()),
+                ("path_indices", self.path_indices.len()),
+            ],
+        )?;
+
+        let chip = MerkleSumTreeChip::construct(config);
+        chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+
+        // the leaf cells are not exposed
+        let (leaf_hash, leaf_balance) = chip.assing_leaf_hash_and_balance(
+            layouter.namespace(|| "assign leaf"),
+            self.leaf_hash,
+            self.leaf_balance,
+        )?;
+
+        let (mut next_hash, mut next_sum) = (leaf_hash, leaf_balance.clone());
+        for i in 0..self.path_element_hashes.len() {
+            (next_hash, next_sum) = chip.merkle_prove_layer(
+                layouter.namespace(|| format!("level {} merkle proof", i)),
+                i,
+                &next_hash,
+                &next_sum,
+                self.path_element_hashes[i],
+                self.path_element_balances[i],
+                self.path_indices[i],
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "public root"), &next_hash, 0)?;
+
+        chip.enforce_greater_than(
+            layouter.namespace(|| "enforce greater than"),
+            &leaf_balance,
+            self.leaf_balance,
+            self.threshold,
+            1,
+        )
+    }
+}
+
+// root hash and threshold
+impl<F: Field> NumPublic for MerkleSumTreePrivateCircuit<F> {
+    fn num_public(&self) -> usize {
+        2
+    }
+}
+
+// Hashes a raw username and balance into a leaf inside the circuit, the leaf hash is exposed at row 0 and the balance at row 1
+#[derive(Default)]
+struct LeafHashCircuit<F: Field> {
+    pub username: F,
+    pub balance: F,
+}
+
+impl<F: Field> Circuit<F> for LeafHashCircuit<F> {
+    type Config = MerkleSumTreeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MerkleSumTreeCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // the lt config is part of the merkle sum tree config, its u8 table is loaded even if not used here
+        LtChip::construct(config.lt_config).load(&mut layouter)?;
+
+        let chip = MerkleSumTreeChip::construct(config);
+        chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+
+        let (leaf_hash, balance) = chip.assign_and_hash_leaf(
+            layouter.namespace(|| "hash leaf"),
+            self.username,
+            self.balance,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "public leaf hash"), &leaf_hash, 0)?;
+        chip.expose_public(layouter.namespace(|| "public balance"), &balance, 1)
+    }
+}
+
+// Compares a sum cell against the assets sum at row 3 of the instance column. The `computed_sum` witness
+// passed to LtChip may differ from the value of the sum cell, to check that the comparison is bound to the cell
+#[derive(Default)]
+struct EnforceLessThanCircuit<F: Field> {
+    pub sum: F,
+    pub computed_sum: F,
+    pub assets_sum: F,
+}
+
+impl<F: Field> Circuit<F> for EnforceLessThanCircuit<F> {
+    type Config = MerkleSumTreeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MerkleSumTreeCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MerkleSumTreeChip::construct(config);
+        chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+
+        // the sum is the balance of a leaf
+        let (_, sum_cell) = chip.assing_leaf_hash_and_balance(
+            layouter.namespace(|| "assign sum"),
+            F::zero(),
+            self.sum,
+        )?;
+
+        chip.enforce_less_than(
+            layouter.namespace(|| "enforce less than"),
+            &sum_cell,
+            self.computed_sum,
+            self.assets_sum,
+        )
+    }
+}
+
+// Tiered solvency: on top of the comparison of the total sum against the assets sum, the sum computed at every
+// level must be within the capacity of that node. The public inputs are the leaf hash (row 0), the leaf balance
+// (row 1), the root hash (row 2), the assets sum (row 3) and the capacity of the node computed at level i (row 4 + i)
+#[derive(Default)]
+struct MerkleSumTreeCapacityCircuit<F: Field> {
+    pub leaf_hash: F,
+    pub leaf_balance: F,
+    pub path_element_hashes: Vec<F>,
+    pub path_element_balances: Vec<F>,
+    pub path_indices: Vec<F>,
+    pub assets_sum: F,
+    pub node_capacities: Vec<F>,
+}
+
+impl<F: Field> Circuit<F> for MerkleSumTreeCapacityCircuit<F> {
+    type Config = MerkleSumTreeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MerkleSumTreeCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        check_path_lengths(
+            self.path_element_hashes.len(),
+            &[
+                ("path_element_balances", self.path_element_balances.len()),
+                ("path_indices", self.path_indices.len()),
+                ("node_capacities", self.node_capacities.len()),
+            ],
+        )?;
+
+        let chip = MerkleSumTreeChip::construct(config);
+        chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+
+        let (leaf_hash, leaf_balance) = chip.assing_leaf_hash_and_balance(
+            layouter.namespace(|| "assign leaf"),
+            self.leaf_hash,
+            self.leaf_balance,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "public leaf hash"), &leaf_hash, 0)?;
+        chip.expose_public(
+            layouter.namespace(|| "public leaf balance"),
+            &leaf_balance,
+            1,
+        )?;
+
+        let (mut next_hash, mut next_sum) = (leaf_hash, leaf_balance);
+        let mut computed_sum = self.leaf_balance;
+        for i in 0..self.path_element_hashes.len() {
+            (next_hash, next_sum) = chip.merkle_prove_layer(
+                layouter.namespace(|| format!("level {} merkle proof", i)),
+                i,
+                &next_hash,
+                &next_sum,
+                self.path_element_hashes[i],
+                self.path_element_balances[i],
+                self.path_indices[i],
+            )?;
+            computed_sum += self.path_element_balances[i];
+
+            chip.enforce_node_capacity(
+                layouter.namespace(|| format!("level {} node capacity", i)),
+                &next_sum,
+                computed_sum,
+                self.node_capacities[i],
+                4 + i,
+            )?;
+        }
+
+        // the LtChip table is loaded here, it is shared with the capacity checks
+        chip.enforce_less_than(
+            layouter.namespace(|| "enforce less than"),
+            &next_sum,
+            computed_sum,
+            self.assets_sum,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "public root"), &next_hash, 2)
+    }
+}
+
+// leaf hash, leaf balance, root hash, assets sum and one capacity per level
+impl<F: Field> NumPublic for MerkleSumTreeCapacityCircuit<F> {
+    fn num_public(&self) -> usize {
+        4 + self.node_capacities.len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::circuits::utils::full_prover;
+    use crate::circuits::utils::{full_prover, run_checked};
 
     use super::super::super::chips::poseidon::spec::MySpec;
-    use super::MerkleSumTreeCircuit;
+    use super::{
+        EnforceLessThanCircuit, LeafHashCircuit, MerkleSumTreeCapacityCircuit,
+        MerkleSumTreeCircuit, MerkleSumTreePrivateCircuit,
+    };
+    use crate::error::ExperimentError;
+    use crate::merkle_sum_tree::{hash_leaf, total_liabilities, MerkleSumTree, Node};
     use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
-    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as Fp};
-    use std::marker::PhantomData;
+    use halo2_proofs::{
+        dev::{CellValue, MockProver},
+        halo2curves::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
 
     const WIDTH: usize = 5;
     const RATE: usize = 4;
     const L: usize = 4;
 
-    #[derive(Debug, Clone)]
-    struct Node {
-        pub hash: Fp,
-        pub balance: Fp,
-    }
-
     fn compute_merkle_sum_root(node: &Node, elements: &Vec<Node>, indices: &Vec<Fp>) -> Node {
         let k = elements.len();
         let mut digest = node.clone();
@@ -158,15 +574,14 @@ mod tests {
         let element_hashes: Vec<Fp> = elements.iter().map(|node| node.hash).collect();
         let element_balances: Vec<Fp> = elements.iter().map(|node| node.balance).collect();
 
-        MerkleSumTreeCircuit {
-            leaf_hash: leaf.hash,
-            leaf_balance: leaf.balance,
-            path_element_hashes: element_hashes,
-            path_element_balances: element_balances,
-            path_indices: indices,
+        MerkleSumTreeCircuit::new(
+            leaf.hash,
+            leaf.balance,
+            element_hashes,
+            element_balances,
+            indices,
             assets_sum,
-            _marker: PhantomData,
-        }
+        )
     }
 
     fn build_merkle_tree() -> (Node, Vec<Node>, Vec<Fp>, Node) {
@@ -217,22 +632,101 @@ mod tests {
 
         let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum];
+        let circuit = instantiate_circuit(leaf.clone(), elements, indices, assets_sum);
 
-        let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
+        let public_input = circuit.public_inputs();
+        assert_eq!(
+            public_input,
+            vec![leaf.hash, leaf.balance, root.hash, assets_sum, Fp::zero()]
+        );
 
         let valid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
 
         valid_prover.assert_satisfied();
     }
 
+    #[test]
+    fn test_membership_only() {
+        let (leaf, elements, indices, root) = build_merkle_tree();
+
+        // the assets sum is not compared in membership only mode, so it can be less than the liabilities sum
+        let mut circuit = instantiate_circuit(leaf.clone(), elements, indices, Fp::zero());
+        circuit.membership_only = true;
+
+        let public_input = circuit.public_inputs();
+        assert_eq!(public_input, vec![leaf.hash, root.hash]);
+
+        let valid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        valid_prover.assert_satisfied();
+
+        // a wrong root fails
+        let invalid_prover = run_checked(10, &circuit, vec![vec![leaf.hash, Fp::zero()]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+
+        // the public input of the full mode has too many rows
+        assert!(matches!(
+            run_checked(10, &circuit, vec![vec![leaf.hash, leaf.balance, root.hash]]),
+            Err(ExperimentError::InstanceLengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_public_inputs_length() {
+        let (leaf, elements, indices, _) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
+
+        let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
+
+        // leaf hash, leaf balance, root and zero balance flag are exposed with `expose_public`, assets sum is copied from row 3
+        let public_input = circuit.public_inputs();
+        assert_eq!(public_input.len(), 5);
+
+        let truncated_input = public_input[..3].to_vec();
+        let invalid_prover = MockProver::run(10, &circuit, vec![truncated_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_run_checked_instance_length() {
+        let (leaf, elements, indices, _) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
+
+        let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
+
+        let public_input = circuit.public_inputs();
+        let prover = run_checked(10, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // the truncated input is rejected before running the MockProver
+        let truncated_input = public_input[..3].to_vec();
+        let result = run_checked(10, &circuit, vec![truncated_input]);
+        assert!(matches!(
+            result,
+            Err(ExperimentError::InstanceLengthMismatch {
+                expected: 5,
+                found: 3
+            })
+        ));
+    }
+
     #[test]
     fn test_invalid_root_hash() {
         let (leaf, elements, indices, root) = build_merkle_tree();
 
         let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, leaf.balance, Fp::from(1000u64), assets_sum];
+        let public_input = vec![
+            leaf.hash,
+            leaf.balance,
+            Fp::from(1000u64),
+            assets_sum,
+            Fp::zero(),
+        ];
 
         let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
 
@@ -249,7 +743,13 @@ mod tests {
 
         let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
-        let public_input = vec![Fp::from(1000u64), leaf.balance, root.hash, assets_sum];
+        let public_input = vec![
+            Fp::from(1000u64),
+            leaf.balance,
+            root.hash,
+            assets_sum,
+            Fp::zero(),
+        ];
 
         let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
 
@@ -266,7 +766,13 @@ mod tests {
 
         let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, Fp::from(1000u64), root.hash, assets_sum];
+        let public_input = vec![
+            leaf.hash,
+            Fp::from(1000u64),
+            root.hash,
+            assets_sum,
+            Fp::zero(),
+        ];
 
         let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
 
@@ -283,7 +789,7 @@ mod tests {
 
         let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum];
+        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum, Fp::zero()];
 
         indices[0] = Fp::from(2);
 
@@ -296,13 +802,133 @@ mod tests {
         assert!(invalid_prover.verify().is_err());
     }
 
+    #[test]
+    fn test_non_binary_index_at_any_level() {
+        let (leaf, elements, indices, root) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
+
+        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum, Fp::zero()];
+
+        for level in 0..indices.len() {
+            let mut invalid_indices = indices.clone();
+            invalid_indices[level] = Fp::from(2);
+
+            let circuit =
+                instantiate_circuit(leaf.clone(), elements.clone(), invalid_indices, assets_sum);
+
+            let invalid_prover = MockProver::run(10, &circuit, vec![public_input.clone()]).unwrap();
+
+            // the bool constraint must be among the failures, whatever the level is
+            let failures = invalid_prover.verify().unwrap_err();
+            assert!(
+                failures
+                    .iter()
+                    .any(|failure| failure.to_string().contains("bool constraint")),
+                "non-binary index at level {} is not rejected by the bool constraint",
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn test_failure_region_names_level() {
+        let (leaf, elements, mut indices, _) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
+
+        // break level 2 only
+        indices[2] = Fp::from(2);
+        let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
+        let invalid_prover = MockProver::run(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+
+        // the failing bool constraint points to the region of level 2
+        let bool_failures = invalid_prover
+            .verify()
+            .unwrap_err()
+            .iter()
+            .map(|failure| failure.to_string())
+            .filter(|failure| failure.contains("bool constraint"))
+            .collect::<Vec<_>>();
+        assert!(!bool_failures.is_empty());
+        for failure in bool_failures {
+            assert!(
+                failure.contains("merkle prove layer level 2 (swap bit set)"),
+                "{}",
+                failure
+            );
+        }
+    }
+
+    // Returns the cells assigned to the row below the level 0 row, namely the four cells passed to the hash.
+    // The level 0 row is found as the only row containing the element hash, the element balance and the index
+    fn assigned_swap_row(prover: &MockProver<Fp>, element: &Node, index: Fp) -> [Fp; 4] {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let config = MerkleSumTreeCircuit::<Fp>::configure(&mut meta);
+
+        let value = |col: usize, row: usize| match prover.advice_values(config.advice[col])[row] {
+            CellValue::Assigned(value) => Some(value),
+            _ => None,
+        };
+
+        let rows = (0..prover.advice_values(config.advice[0]).len() - 1)
+            .filter(|row| {
+                value(2, *row) == Some(element.hash)
+                    && value(3, *row) == Some(element.balance)
+                    && value(4, *row) == Some(index)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows.len(), 1);
+
+        [0, 1, 2, 3].map(|col| value(col, rows[0] + 1).unwrap())
+    }
+
+    #[test]
+    fn test_swap_row_follows_index() {
+        let (leaf, elements, indices, _) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
+
+        // index = 0, the previous node is the left child
+        let circuit =
+            instantiate_circuit(leaf.clone(), elements.clone(), indices.clone(), assets_sum);
+        let prover = MockProver::run(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+        prover.assert_satisfied();
+        assert_eq!(
+            assigned_swap_row(&prover, &elements[0], Fp::zero()),
+            [
+                leaf.hash,
+                leaf.balance,
+                elements[0].hash,
+                elements[0].balance
+            ]
+        );
+
+        // index = 1, the hash columns and the balance columns are both swapped
+        let mut swapped_indices = indices;
+        swapped_indices[0] = Fp::one();
+        let circuit =
+            instantiate_circuit(leaf.clone(), elements.clone(), swapped_indices, assets_sum);
+        let prover = MockProver::run(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+        prover.assert_satisfied();
+        assert_eq!(
+            assigned_swap_row(&prover, &elements[0], Fp::one()),
+            [
+                elements[0].hash,
+                elements[0].balance,
+                leaf.hash,
+                leaf.balance
+            ]
+        );
+    }
+
     #[test]
     fn test_swapping_index() {
         let (leaf, elements, mut indices, root) = build_merkle_tree();
 
         let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum];
+        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum, Fp::zero()];
 
         indices[0] = Fp::from(1);
 
@@ -315,16 +941,55 @@ mod tests {
         assert!(invalid_prover.verify().is_err());
     }
 
+    #[test]
+    fn test_rewitnessed_sum() {
+        let assets_sum = Fp::from(500u64);
+        let public_input = vec![Fp::zero(), Fp::zero(), Fp::zero(), assets_sum];
+
+        let circuit = EnforceLessThanCircuit {
+            sum: Fp::from(400u64),
+            computed_sum: Fp::from(400u64),
+            assets_sum,
+        };
+        let valid_prover = MockProver::run(10, &circuit, vec![public_input.clone()]).unwrap();
+        valid_prover.assert_satisfied();
+
+        // the proven sum exceeds the assets, a smaller sum re-witnessed for LtChip is rejected
+        let circuit = EnforceLessThanCircuit {
+            sum: Fp::from(600u64),
+            computed_sum: Fp::from(100u64),
+            assets_sum,
+        };
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_oversized_assets_sum() {
+        // 2^64 + 500 is out of the 8 bytes range of LtChip, the diff of 600 < 2^64 + 500 wraps to 100
+        // and would satisfy LtChip, but the assets sum fails the range check
+        let assets_sum = Fp::from(u64::MAX) + Fp::from(501u64);
+        let public_input = vec![Fp::zero(), Fp::zero(), Fp::zero(), assets_sum];
+
+        let circuit = EnforceLessThanCircuit {
+            sum: Fp::from(600u64),
+            computed_sum: Fp::from(600u64),
+            assets_sum,
+        };
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
     #[test]
     fn test_is_not_less_than() {
-        let (leaf, elements, indices, root) = build_merkle_tree();
+        let (leaf, elements, indices, _) = build_merkle_tree();
 
         let assets_sum = Fp::from(200u64); // less than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum];
-
         let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
 
+        let public_input = circuit.public_inputs();
+
         let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
 
         // error: constraint not satisfied
@@ -343,20 +1008,432 @@ mod tests {
     }
 
     #[test]
-    fn test_full_prover() {
-        let k = 9;
+    fn test_proven_sum_is_total_liabilities() {
+        let leaves = (1..=8u64)
+            .map(|i| Node {
+                hash: Fp::from(i),
+                balance: Fp::from(i * 100),
+            })
+            .collect::<Vec<Node>>();
+        let tree = MerkleSumTree::new(leaves.clone());
+
+        // 100 + 200 + ... + 800, the sum of the whole tree
+        let liabilities = total_liabilities(&leaves);
+        assert_eq!(liabilities, Fp::from(3600u64));
+        assert_eq!(tree.root().balance, liabilities);
+
+        // whatever the leaf is, the proven sum is the total and not only the balances along its path:
+        // it is less than total + 1 but not less than total
+        for index in 0..leaves.len() {
+            let proof = tree.generate_proof(index);
+
+            let circuit = MerkleSumTreeCircuit::from_proof(&proof, liabilities + Fp::one());
+            let valid_prover =
+                MockProver::run(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+            valid_prover.assert_satisfied();
+
+            let circuit = MerkleSumTreeCircuit::from_proof(&proof, liabilities);
+            let invalid_prover =
+                MockProver::run(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+            assert!(invalid_prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_identical_siblings() {
+        // all the leaves are equal, so at every level the node and its sibling are identical
+        let leaves = vec![
+            Node {
+                hash: Fp::from(9u64),
+                balance: Fp::from(90u64),
+            };
+            16
+        ];
+        let tree = MerkleSumTree::new(leaves);
+        let assets_sum = Fp::from(2000u64); // greater than liabilities sum (1440)
+
+        // the swap is a no-op on equal values, so any leaf position gives a valid proof
+        for index in [0, 5, 10, 15] {
+            let proof = tree.generate_proof(index);
+            assert_eq!(proof.path_elements[0], proof.leaf);
+
+            let circuit = MerkleSumTreeCircuit::from_proof(&proof, assets_sum);
+            let public_input = circuit.public_inputs();
+            assert_eq!(public_input[2], tree.root().hash);
+
+            let valid_prover = run_checked(10, &circuit, vec![public_input.clone()]).unwrap();
+            valid_prover.assert_satisfied();
+
+            // a wrong root is still rejected
+            let mut wrong_input = public_input.clone();
+            wrong_input[2] = Fp::zero();
+            let invalid_prover = run_checked(10, &circuit, vec![wrong_input]).unwrap();
+            assert!(invalid_prover.verify().is_err());
+
+            // and so is a non-binary index, even though it doesn't change the swapped values
+            let mut circuit = circuit;
+            circuit.path_indices[1] = Fp::from(2);
+            let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+            assert!(invalid_prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_mismatched_path_lengths() {
+        let (leaf, elements, mut indices, root) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
+
+        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum, Fp::zero()];
+
+        // one index is missing
+        indices.pop();
+
+        let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
+
+        assert!(matches!(
+            circuit.validate(),
+            Err(ExperimentError::PathLengthMismatch {
+                name: "path_indices",
+                expected: 5,
+                found: 4,
+            })
+        ));
+
+        // the shape error is returned by synthesize instead of panicking
+        assert!(matches!(
+            MockProver::run(10, &circuit, vec![public_input]),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[test]
+    fn test_empty_path() {
+        let (leaf, _, _, _) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64);
+
+        let circuit = instantiate_circuit(leaf.clone(), vec![], vec![], assets_sum);
+
+        assert!(matches!(
+            circuit.validate(),
+            Err(ExperimentError::EmptyPath)
+        ));
+
+        let public_input = vec![leaf.hash, leaf.balance, leaf.hash, assets_sum, Fp::zero()];
+        assert!(matches!(
+            MockProver::run(10, &circuit, vec![public_input]),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        let (leaf, mut elements, indices, _) = build_merkle_tree();
+
+        // leaf balance + first element balance = 2^64, which doesn't fit in MAX_BITS (64)
+        let leaf = Node {
+            hash: leaf.hash,
+            balance: Fp::from(1u64 << 63),
+        };
+        elements[0].balance = Fp::from(1u64 << 63);
+        for element in elements.iter_mut().skip(1) {
+            element.balance = Fp::zero();
+        }
+
+        // 2^64 + 1, so that the less than check alone would accept the sum
+        let assets_sum = Fp::from(u64::MAX) + Fp::from(2u64);
+
+        let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
+
+        let public_input = circuit.public_inputs();
+
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .any(|failure| failure.to_string().contains("range check decomposition")));
+    }
 
+    #[test]
+    fn test_wrapping_element_balance() {
+        let (leaf, mut elements, indices, _) = build_merkle_tree();
+
+        // the balance of the first element is p - 50, so the sum of the first level is 100 + (p - 50) = 50,
+        // which is in range. The root is recomputed with the same balance so that the path is consistent
+        elements[0].balance = -Fp::from(50u64);
+        let root = compute_merkle_sum_root(&leaf, &elements, &indices);
+        assert_eq!(root.balance, Fp::from(340u64));
+
+        let assets_sum = Fp::from(500u64); // greater than the wrapped sum (340)
+
+        let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
+
+        let public_input = circuit.public_inputs();
+        assert_eq!(public_input[2], root.hash);
+
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .any(|failure| failure.to_string().contains("range check decomposition")));
+    }
+
+    fn instantiate_private_circuit(threshold: Fp) -> (MerkleSumTreePrivateCircuit<Fp>, Vec<Fp>) {
         let (leaf, elements, indices, root) = build_merkle_tree();
 
+        let circuit = MerkleSumTreePrivateCircuit {
+            leaf_hash: leaf.hash,
+            leaf_balance: leaf.balance,
+            path_element_hashes: elements.iter().map(|node| node.hash).collect(),
+            path_element_balances: elements.iter().map(|node| node.balance).collect(),
+            path_indices: indices,
+            threshold,
+        };
+
+        // the leaf hash and the leaf balance are not part of the public inputs
+        (circuit, vec![root.hash, threshold])
+    }
+
+    #[test]
+    fn test_private_leaf_above_threshold() {
+        // the hidden leaf balance is 100
+        let (circuit, public_input) = instantiate_private_circuit(Fp::from(50u64));
+
+        let valid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        valid_prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_private_leaf_below_threshold() {
+        // the balance must be strictly greater than the threshold
+        for threshold in [100u64, 150u64] {
+            let (circuit, public_input) = instantiate_private_circuit(Fp::from(threshold));
+
+            let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+            assert!(invalid_prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_private_threshold_out_of_range() {
+        // the threshold is 100 - 2^64, so the diff of LtChip wraps to 0 and `threshold < balance` would hold
+        let threshold = Fp::from(100u64) - Fp::from_u128(1u128 << 64);
+        let (circuit, public_input) = instantiate_private_circuit(threshold);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .any(|failure| failure.to_string().contains("range check decomposition")));
+    }
+
+    fn instantiate_capacity_circuit(
+        capacities: [u64; 5],
+    ) -> (MerkleSumTreeCapacityCircuit<Fp>, Vec<Fp>) {
+        let (leaf, elements, indices, root) = build_merkle_tree();
         let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum];
+        let circuit = MerkleSumTreeCapacityCircuit {
+            leaf_hash: leaf.hash,
+            leaf_balance: leaf.balance,
+            path_element_hashes: elements.iter().map(|node| node.hash).collect(),
+            path_element_balances: elements.iter().map(|node| node.balance).collect(),
+            path_indices: indices,
+            assets_sum,
+            node_capacities: capacities.map(Fp::from).to_vec(),
+        };
+
+        let mut public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum];
+        public_input.extend(circuit.node_capacities.iter());
+
+        (circuit, public_input)
+    }
+
+    #[test]
+    fn test_node_capacity() {
+        // the sums computed at each level are 110, 160, 220, 310 and 400, a sum equal to its capacity is allowed
+        let (circuit, public_input) = instantiate_capacity_circuit([110, 200, 300, 400, 500]);
+
+        let valid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        valid_prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_node_capacity_exceeded() {
+        // the subtree of level 1 sums to 160, while the root total (400) is still less than the assets sum (500)
+        let (circuit, public_input) = instantiate_capacity_circuit([200, 150, 300, 400, 500]);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_leaf_hash() {
+        let username = Fp::from(12345u64);
+        let balance = Fp::from(100u64);
+
+        // the leaf hash computed in the circuit matches the off-circuit one
+        let leaf = hash_leaf(username, balance);
+
+        let circuit = LeafHashCircuit { username, balance };
+
+        let prover = MockProver::run(10, &circuit, vec![vec![leaf.hash, leaf.balance]]).unwrap();
+        prover.assert_satisfied();
+
+        // the tag separates the leaf hash from the hash of the raw values
+        let untagged_hash =
+            poseidon::Hash::<_, MySpec<Fp, WIDTH, RATE>, ConstantLength<2>, WIDTH, RATE>::init()
+                .hash([username, balance]);
+        let invalid_prover =
+            MockProver::run(10, &circuit, vec![vec![untagged_hash, balance]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_leaf_binding() {
+        let username = Fp::from(12345u64);
+        let leaf = hash_leaf(username, Fp::from(100u64));
+        let (_, elements, indices, _) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
+
+        let mut circuit =
+            instantiate_circuit(leaf.clone(), elements.clone(), indices.clone(), assets_sum);
+        circuit.leaf_username = Some(username);
+
+        let valid_prover = run_checked(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+        valid_prover.assert_satisfied();
+
+        // the leaf hash of a balance of 100 paired with a balance of 90
+        let mismatched_leaf = Node {
+            hash: leaf.hash,
+            balance: Fp::from(90u64),
+        };
+
+        // without the binding the mismatched pair is accepted
+        let circuit = instantiate_circuit(
+            mismatched_leaf.clone(),
+            elements.clone(),
+            indices.clone(),
+            assets_sum,
+        );
+        let prover = run_checked(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+        prover.assert_satisfied();
+
+        // with the binding the leaf hash computed in the circuit doesn't match the public one
+        let mut circuit = instantiate_circuit(mismatched_leaf, elements, indices, assets_sum);
+        circuit.leaf_username = Some(username);
+
+        let invalid_prover = run_checked(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_full_prover() {
+        let k = 9;
+
+        let (leaf, elements, indices, _) = build_merkle_tree();
+
+        let assets_sum = Fp::from(500u64); // greater than liabilities sum (400)
 
         let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
 
+        let public_input = circuit.public_inputs();
+
         full_prover(circuit, k, &public_input);
     }
 
+    #[test]
+    fn test_update_leaf() {
+        let leaves = (1..=8u64)
+            .map(|i| Node {
+                hash: Fp::from(i),
+                balance: Fp::from(i * 10),
+            })
+            .collect::<Vec<Node>>();
+
+        let mut tree = MerkleSumTree::new(leaves);
+        let index = 5;
+        let old_proof = tree.generate_proof(index);
+
+        let (new_root, new_proof) = tree.update_leaf(index, Fp::from(42u64), Fp::from(15u64));
+
+        // the incremental update matches a full rebuild of the tree
+        let rebuilt_tree = MerkleSumTree::new(tree.leaves().to_vec());
+        assert_eq!(rebuilt_tree.root(), &new_root);
+
+        let assets_sum = Fp::from(1000u64); // greater than liabilities sum (315)
+
+        // the fresh proof verifies against the new root
+        let circuit = instantiate_circuit(
+            new_proof.leaf,
+            new_proof.path_elements,
+            new_proof.path_indices,
+            assets_sum,
+        );
+        let public_input = circuit.public_inputs();
+        assert_eq!(public_input[2], new_root.hash);
+        let valid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        valid_prover.assert_satisfied();
+
+        // the old proof doesn't verify against the new root
+        let public_input = vec![
+            old_proof.leaf.hash,
+            old_proof.leaf.balance,
+            new_root.hash,
+            assets_sum,
+            Fp::zero(),
+        ];
+        let circuit = instantiate_circuit(
+            old_proof.leaf,
+            old_proof.path_elements,
+            old_proof.path_indices,
+            assets_sum,
+        );
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_zero_balance_flag() {
+        let leaves = (0..8u64)
+            .map(|i| Node {
+                hash: Fp::from(i + 1),
+                balance: Fp::from(i * 10),
+            })
+            .collect::<Vec<Node>>();
+
+        let tree = MerkleSumTree::new(leaves);
+        let assets_sum = Fp::from(1000u64); // greater than liabilities sum (280)
+
+        // leaf 0 is an empty account, leaf 1 is not
+        for (index, expected_flag) in [(0, Fp::one()), (1, Fp::zero())] {
+            let proof = tree.generate_proof(index);
+            let circuit = instantiate_circuit(
+                proof.leaf,
+                proof.path_elements,
+                proof.path_indices,
+                assets_sum,
+            );
+
+            let public_input = circuit.public_inputs();
+            assert_eq!(public_input[4], expected_flag);
+
+            let valid_prover = MockProver::run(10, &circuit, vec![public_input.clone()]).unwrap();
+            valid_prover.assert_satisfied();
+
+            // claiming the opposite flag fails
+            let mut invalid_input = public_input;
+            invalid_input[4] = Fp::one() - expected_flag;
+            let invalid_prover = MockProver::run(10, &circuit, vec![invalid_input]).unwrap();
+            assert!(invalid_prover.verify().is_err());
+        }
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn print_merkle_sum_tree() {
@@ -366,7 +1443,7 @@ mod tests {
 
         let assets_sum = Fp::from(200u64); // less than liabilities sum (400)
 
-        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum];
+        let public_input = vec![leaf.hash, leaf.balance, root.hash, assets_sum, Fp::zero()];
 
         let circuit = instantiate_circuit(leaf, elements, indices, assets_sum);
 
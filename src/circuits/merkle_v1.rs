@@ -1,15 +1,16 @@
 use super::super::chips::merkle_v1::{MerkleTreeV1Chip, MerkleTreeV1Config};
 
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
-struct MerkleTreeV1Circuit<F> {
+pub struct MerkleTreeV1Circuit<F> {
     pub leaf: Value<F>,
     pub path_elements: Vec<Value<F>>,
     pub path_indices: Vec<Value<F>>,
 }
 
-impl<F: FieldExt> Circuit<F> for MerkleTreeV1Circuit<F> {
+impl<F: Field> Circuit<F> for MerkleTreeV1Circuit<F> {
     type Config = MerkleTreeV1Config;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -63,10 +64,55 @@ impl<F: FieldExt> Circuit<F> for MerkleTreeV1Circuit<F> {
     }
 }
 
+// the digest of each level must be less than the bound, so the dummy hash can't wrap around the modulus
+const DIGEST_BOUND: usize = 256;
+
+#[derive(Default)]
+struct MerkleTreeV1RangeCircuit<F> {
+    pub circuit: MerkleTreeV1Circuit<F>,
+}
+
+impl<F: Field> Circuit<F> for MerkleTreeV1RangeCircuit<F> {
+    type Config = MerkleTreeV1Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let instance = meta.instance_column();
+
+        MerkleTreeV1Chip::configure_with_range_check(
+            meta,
+            [col_a, col_b, col_c],
+            instance,
+            DIGEST_BOUND,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        MerkleTreeV1Chip::<F>::construct(config.clone()).load(&mut layouter)?;
+
+        self.circuit.synthesize(config, layouter)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::MerkleTreeV1Circuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use super::{MerkleTreeV1Circuit, MerkleTreeV1RangeCircuit};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+
+    fn to_values(v: &[u64]) -> Vec<Value<Fp>> {
+        v.iter().map(|x| Value::known(Fp::from(*x))).collect()
+    }
 
     #[test]
     fn test_merkle_tree_1() {
@@ -95,12 +141,44 @@ mod tests {
         let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
         prover.assert_satisfied();
     }
+
+    #[test]
+    fn test_merkle_tree_1_range_check() {
+        let leaf = 99u64;
+        let indices = [0u64, 1u64, 0u64, 1u64, 0u64];
+
+        // the root is 129, below the bound of 256
+        let elements = [1u64, 5u64, 6u64, 9u64, 9u64];
+        let circuit = MerkleTreeV1RangeCircuit {
+            circuit: MerkleTreeV1Circuit {
+                leaf: Value::known(Fp::from(leaf)),
+                path_elements: to_values(&elements),
+                path_indices: to_values(&indices),
+            },
+        };
+        let public_input = vec![Fp::from(leaf), Fp::from(129u64)];
+        let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the digest of level 1 is 99 + 100 + 100 = 299, out of the bound
+        let elements = [100u64, 100u64, 6u64, 9u64, 9u64];
+        let circuit = MerkleTreeV1RangeCircuit {
+            circuit: MerkleTreeV1Circuit {
+                leaf: Value::known(Fp::from(leaf)),
+                path_elements: to_values(&elements),
+                path_indices: to_values(&indices),
+            },
+        };
+        let public_input = vec![Fp::from(leaf), Fp::from(323u64)];
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
 }
 
 #[cfg(feature = "dev-graph")]
 #[test]
 fn print_merkle_tree_1() {
-    use halo2_proofs::halo2curves::pasta::Fp;
+    use halo2_proofs::halo2curves::bn256::Fr as Fp;
     use plotters::prelude::*;
 
     let root =
@@ -1,14 +1,15 @@
 use super::super::chips::merkle_v2::{MerkleTreeV2Chip, MerkleTreeV2Config};
-use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
-struct MerkleTreeV2Circuit<F> {
+pub struct MerkleTreeV2Circuit<F> {
     pub leaf: Value<F>,
     pub path_elements: Vec<Value<F>>,
     pub path_indices: Vec<Value<F>>,
 }
 
-impl<F: FieldExt> Circuit<F> for MerkleTreeV2Circuit<F> {
+impl<F: Field> Circuit<F> for MerkleTreeV2Circuit<F> {
     type Config = MerkleTreeV2Config;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -31,7 +32,7 @@ impl<F: FieldExt> Circuit<F> for MerkleTreeV2Circuit<F> {
     ) -> Result<(), Error> {
         let chip = MerkleTreeV2Chip::construct(config);
         let leaf_cell = chip.assing_leaf(layouter.namespace(|| "assign leaf"), self.leaf)?;
-        chip.expose_public(layouter.namespace(|| "public leaf"), &leaf_cell, 0);
+        chip.expose_public(layouter.namespace(|| "public leaf"), &leaf_cell, 0)?;
 
         // apply it for level 0 of the merkle tree
         // node cell passed as input is the leaf cell
@@ -60,7 +61,7 @@ impl<F: FieldExt> Circuit<F> for MerkleTreeV2Circuit<F> {
 #[cfg(test)]
 mod tests {
     use super::MerkleTreeV2Circuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
 
     #[test]
     fn test_merkle_tree_2() {
@@ -88,13 +89,48 @@ mod tests {
         let public_input = vec![Fp::from(leaf), Fp::from(digest)];
         let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
         prover.assert_satisfied();
+
+        let wrong_public_input = vec![Fp::from(leaf), Fp::from(0)];
+        let invalid_prover = MockProver::run(10, &circuit, vec![wrong_public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    // Hash2Chip and MerkleTreeV2Chip are generic over the field, so the same circuit works over bn256 too
+    #[test]
+    fn test_merkle_tree_2_bn256() {
+        use halo2_proofs::halo2curves::bn256::Fr;
+
+        let leaf = 99u64;
+        let elements = vec![1u64, 5u64, 6u64, 9u64, 9u64];
+        let indices = vec![0u64, 1u64, 0u64, 1u64, 0u64];
+        let digest: u64 = leaf + elements.iter().sum::<u64>();
+
+        let circuit = MerkleTreeV2Circuit {
+            leaf: Value::known(Fr::from(leaf)),
+            path_elements: elements
+                .iter()
+                .map(|x| Value::known(Fr::from(x.to_owned())))
+                .collect(),
+            path_indices: indices
+                .iter()
+                .map(|x| Value::known(Fr::from(x.to_owned())))
+                .collect(),
+        };
+
+        let public_input = vec![Fr::from(leaf), Fr::from(digest)];
+        let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        let wrong_public_input = vec![Fr::from(leaf), Fr::from(digest + 1)];
+        let invalid_prover = MockProver::run(10, &circuit, vec![wrong_public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
     }
 }
 
 #[cfg(feature = "dev-graph")]
 #[test]
 fn print_merkle_tree_2() {
-    use halo2_proofs::halo2curves::pasta::Fp;
+    use halo2_proofs::halo2curves::bn256::Fr as Fp;
     use plotters::prelude::*;
 
     let root =
@@ -1,19 +1,67 @@
 use super::super::chips::merkle_v3::{MerkleTreeV3Chip, MerkleTreeV3Config};
-use halo2_proofs::{circuit::*, arithmetic::FieldExt, plonk::*};
+use super::super::error::{check_path_lengths, ExperimentError};
+use super::utils::unknown_values;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
 
 #[derive(Default)]
-struct MerkleTreeV3Circuit <F: FieldExt>{
+pub struct MerkleTreeV3Circuit <F: Field>{
     pub leaf: Value<F>,
     pub path_elements: Vec<Value<F>>,
     pub path_indices: Vec<Value<F>>,
 }
 
-impl <F:FieldExt> Circuit<F> for MerkleTreeV3Circuit<F> {
+impl<F: Field> MerkleTreeV3Circuit<F> {
+    // Builds the circuit from the position of the leaf in the tree, the path indices are the bits of
+    // `leaf_index` in little-endian order, namely the bit of level 0 comes first
+    pub fn from_index(
+        leaf: Value<F>,
+        path_elements: Vec<Value<F>>,
+        leaf_index: u64,
+    ) -> Result<Self, ExperimentError> {
+        let levels = path_elements.len();
+        if levels == 0 {
+            return Err(ExperimentError::EmptyPath);
+        }
+        // a path of 64 levels or more fits any u64 index
+        if leaf_index.checked_shr(levels as u32).unwrap_or(0) != 0 {
+            return Err(ExperimentError::IndexOutOfRange {
+                index: leaf_index,
+                levels,
+            });
+        }
+
+        let path_indices = (0..levels)
+            .map(|i| Value::known(F::from(leaf_index.checked_shr(i as u32).unwrap_or(0) & 1)))
+            .collect();
+
+        Ok(Self {
+            leaf,
+            path_elements,
+            path_indices,
+        })
+    }
+
+    // Checks that the path is not empty and that there is one index per path element
+    pub fn validate(&self) -> Result<(), ExperimentError> {
+        check_path_lengths(
+            self.path_elements.len(),
+            &[("path_indices", self.path_indices.len())],
+        )
+    }
+}
+
+impl <F:Field> Circuit<F> for MerkleTreeV3Circuit<F> {
     type Config = MerkleTreeV3Config<F>;
     type FloorPlanner = SimpleFloorPlanner;
 
+    // the number of levels is kept, so the keys generated from it can prove a path of the same depth
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self {
+            leaf: Value::unknown(),
+            path_elements: unknown_values(&self.path_elements),
+            path_indices: unknown_values(&self.path_indices),
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -31,6 +79,9 @@ impl <F:FieldExt> Circuit<F> for MerkleTreeV3Circuit<F> {
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
+        // a shape error is returned as `Error::Synthesis` instead of panicking on the path vectors
+        self.validate()?;
+
         let chip = MerkleTreeV3Chip::construct(config);
         let leaf_cell = chip.assing_leaf(layouter.namespace(|| "assign leaf"), self.leaf)?;
         chip.expose_public(layouter.namespace(|| "public leaf"), &leaf_cell, 0)?;
@@ -59,11 +110,70 @@ impl <F:FieldExt> Circuit<F> for MerkleTreeV3Circuit<F> {
     }
 }
 
+#[derive(Default)]
+// proves that replacing old_leaf with new_leaf takes the old root to the new root
+struct MerkleTreeV3UpdateCircuit<F: Field> {
+    pub old_leaf: Value<F>,
+    pub new_leaf: Value<F>,
+    pub path_elements: Vec<Value<F>>,
+    pub path_indices: Vec<Value<F>>,
+}
+
+impl<F: Field> Circuit<F> for MerkleTreeV3UpdateCircuit<F> {
+    type Config = MerkleTreeV3Config<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            old_leaf: Value::unknown(),
+            new_leaf: Value::unknown(),
+            path_elements: unknown_values(&self.path_elements),
+            path_indices: unknown_values(&self.path_indices),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MerkleTreeV3Circuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        check_path_lengths(
+            self.path_elements.len(),
+            &[("path_indices", self.path_indices.len())],
+        )?;
+
+        let chip = MerkleTreeV3Chip::construct(config);
+        let (old_root, new_root) = chip.prove_update(
+            layouter.namespace(|| "prove update"),
+            self.old_leaf,
+            self.new_leaf,
+            &self.path_elements,
+            &self.path_indices,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "public old root"), &old_root, 0)?;
+        chip.expose_public(layouter.namespace(|| "public new root"), &new_root, 1)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::MerkleTreeV3Circuit;
-    use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength, P128Pow5T3};
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use super::{MerkleTreeV3Circuit, MerkleTreeV3UpdateCircuit};
+    use crate::chips::poseidon::spec::MySpec;
+    use crate::circuits::utils::{gen_proof, gen_srs, verify_kzg_proof};
+    use crate::error::ExperimentError;
+    use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        halo2curves::bn256::Fr as Fp,
+        plonk::{keygen_pk, keygen_vk, Circuit, Error},
+    };
 
     const WIDTH: usize = 3;
     const RATE: usize = 2;
@@ -80,7 +190,9 @@ mod tests {
                 message = [Fp::from(elements[i]), digest];
             }
 
-            digest = poseidon::Hash::<_, P128Pow5T3, ConstantLength<L>, WIDTH, RATE>::init()
+            digest =
+                poseidon::Hash::<_, MySpec<Fp, WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init(
+                )
                 .hash(message);
         }
         return digest;
@@ -118,12 +230,234 @@ mod tests {
         let invalid_prover = MockProver::run(10, &circuit, vec![wrong_public_input]).unwrap();
         assert!(invalid_prover.verify().is_err());
     }
+
+    #[test]
+    fn test_merkle_tree_3_keygen_without_witnesses() {
+        let k = 10;
+
+        let leaf = 99u64;
+        let elements = vec![1u64, 5u64, 6u64, 9u64, 9u64];
+        let indices = vec![0u64, 1u64, 0u64, 0u64, 1u64];
+
+        let root = compute_merkle_root(&leaf, &elements, &indices);
+
+        let circuit = MerkleTreeV3Circuit {
+            leaf: Value::known(Fp::from(leaf)),
+            path_elements: elements
+                .iter()
+                .map(|x| Value::known(Fp::from(*x)))
+                .collect(),
+            path_indices: indices.iter().map(|x| Value::known(Fp::from(*x))).collect(),
+        };
+
+        let params = gen_srs(k);
+
+        // the default circuit has no levels, so it can't even be synthesized
+        assert!(matches!(
+            keygen_vk(&params, &MerkleTreeV3Circuit::<Fp>::default()),
+            Err(Error::Synthesis)
+        ));
+
+        // the keys are generated without the witnesses, then used to prove the path with the witnesses
+        let empty_circuit = circuit.without_witnesses();
+        assert_eq!(empty_circuit.path_elements.len(), 5);
+        assert_eq!(empty_circuit.path_indices.len(), 5);
+
+        let vk = keygen_vk(&params, &empty_circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &empty_circuit).unwrap();
+
+        let public_input = vec![Fp::from(leaf), root];
+        let proof = gen_proof(&params, &pk, circuit, &public_input);
+        assert!(verify_kzg_proof(
+            &params,
+            pk.get_vk(),
+            &[public_input],
+            &proof
+        ));
+    }
+
+    #[test]
+    fn test_merkle_tree_3_alternating_indices() {
+        use halo2_proofs::halo2curves::bn256::Fr;
+
+        // the path alternates between left and right siblings
+        let leaf = Fr::from(99u64);
+        let elements = [1u64, 5u64, 6u64, 9u64, 9u64].map(Fr::from);
+        let indices = [0u64, 1u64, 0u64, 1u64, 0u64].map(Fr::from);
+
+        let mut root = leaf;
+        for (element, index) in elements.iter().zip(indices.iter()) {
+            let message = if *index == Fr::zero() {
+                [root, *element]
+            } else {
+                [*element, root]
+            };
+            root = poseidon::Hash::<
+                _,
+                MySpec<Fr, WIDTH, RATE>,
+                ConstantLength<L>,
+                WIDTH,
+                RATE,
+            >::init()
+            .hash(message);
+        }
+
+        let circuit = MerkleTreeV3Circuit {
+            leaf: Value::known(leaf),
+            path_elements: elements.map(Value::known).to_vec(),
+            path_indices: indices.map(Value::known).to_vec(),
+        };
+
+        let valid_prover = MockProver::run(10, &circuit, vec![vec![leaf, root]]).unwrap();
+        valid_prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(10, &circuit, vec![vec![leaf, Fr::zero()]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_merkle_tree_3_from_index() {
+        let leaf = 99u64;
+        let elements = vec![1u64, 5u64, 6u64, 9u64, 9u64];
+        // 5 = 0b00101
+        let indices = vec![1u64, 0u64, 1u64, 0u64, 0u64];
+
+        let root = compute_merkle_root(&leaf, &elements, &indices);
+
+        let elements_fp: Vec<Value<Fp>> = elements
+            .iter()
+            .map(|x| Value::known(Fp::from(*x)))
+            .collect();
+
+        let circuit =
+            MerkleTreeV3Circuit::from_index(Value::known(Fp::from(leaf)), elements_fp.clone(), 5)
+                .unwrap();
+
+        let public_input = vec![Fp::from(leaf), root];
+        let valid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        valid_prover.assert_satisfied();
+
+        // a tree of depth 5 has 32 leaves
+        assert!(matches!(
+            MerkleTreeV3Circuit::from_index(Value::known(Fp::from(leaf)), elements_fp, 32),
+            Err(ExperimentError::IndexOutOfRange {
+                index: 32,
+                levels: 5,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_merkle_tree_3_update() {
+        let old_leaf = 99u64;
+        let new_leaf = 42u64;
+        let elements = vec![1u64, 5u64, 6u64, 9u64, 9u64];
+        let indices = vec![0u64, 1u64, 0u64, 1u64, 0u64];
+
+        let old_root = compute_merkle_root(&old_leaf, &elements, &indices);
+        let new_root = compute_merkle_root(&new_leaf, &elements, &indices);
+
+        let to_values =
+            |v: &Vec<u64>| v.iter().map(|x| Value::known(Fp::from(*x))).collect::<Vec<_>>();
+
+        let circuit = MerkleTreeV3UpdateCircuit {
+            old_leaf: Value::known(Fp::from(old_leaf)),
+            new_leaf: Value::known(Fp::from(new_leaf)),
+            path_elements: to_values(&elements),
+            path_indices: to_values(&indices),
+        };
+
+        let public_input = vec![old_root, new_root];
+        let valid_prover = MockProver::run(10, &circuit, vec![public_input.clone()]).unwrap();
+        valid_prover.assert_satisfied();
+
+        // a different new leaf doesn't lead to the new root
+        let circuit = MerkleTreeV3UpdateCircuit {
+            old_leaf: Value::known(Fp::from(old_leaf)),
+            new_leaf: Value::known(Fp::from(new_leaf + 1)),
+            path_elements: to_values(&elements),
+            path_indices: to_values(&indices),
+        };
+        let invalid_prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_merkle_tree_3_mismatched_path_lengths() {
+        let leaf = Value::known(Fp::from(99u64));
+        let elements = vec![Value::known(Fp::from(1u64)); 5];
+        let indices = vec![Value::known(Fp::from(0u64)); 4];
+
+        let circuit = MerkleTreeV3Circuit {
+            leaf,
+            path_elements: elements,
+            path_indices: indices,
+        };
+
+        assert!(matches!(
+            circuit.validate(),
+            Err(ExperimentError::PathLengthMismatch {
+                name: "path_indices",
+                expected: 5,
+                found: 4,
+            })
+        ));
+
+        // the shape error is returned by synthesize instead of panicking
+        let public_input = vec![Fp::from(99u64), Fp::from(0)];
+        assert!(matches!(
+            MockProver::run(10, &circuit, vec![public_input]),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[test]
+    fn test_layout_diff_merkle_1() {
+        use crate::circuits::merkle_v1::MerkleTreeV1Circuit;
+        use crate::circuits::utils::layout_diff;
+        use halo2_proofs::halo2curves::bn256::Fr;
+
+        // same tree for both circuits, only the hash function changes
+        let leaf = Value::known(Fr::from(99u64));
+        let elements = vec![Value::known(Fr::from(1u64)); 5];
+        let indices = vec![Value::known(Fr::from(0u64)); 5];
+
+        let merkle_1 = MerkleTreeV1Circuit {
+            leaf,
+            path_elements: elements.clone(),
+            path_indices: indices.clone(),
+        };
+        let merkle_3 = MerkleTreeV3Circuit {
+            leaf,
+            path_elements: elements,
+            path_indices: indices,
+        };
+
+        let diff = layout_diff(&merkle_1, &merkle_3, 10).unwrap();
+
+        // poseidon needs its own advice columns and round constants, and many rows for each level
+        assert!(diff.advice_columns > 0);
+        assert!(diff.fixed_columns > 0);
+        assert_eq!(diff.instance_columns, 0);
+        assert!(diff.rows > 0);
+        assert_eq!(diff.rows, diff.b.rows as isize - diff.a.rows as isize);
+
+        // switching back is the opposite cost
+        let reverse = layout_diff(&merkle_3, &merkle_1, 10).unwrap();
+        assert_eq!(reverse.rows, -diff.rows);
+
+        // the poseidon tree doesn't fit into 2^6 rows
+        assert!(matches!(
+            layout_diff(&merkle_1, &merkle_3, 6),
+            Err(ExperimentError::Plonk(Error::NotEnoughRowsAvailable { current_k: 6 }))
+        ));
+    }
 }
 
 #[cfg(feature = "dev-graph")]
 #[test]
 fn print_merkle_tree_3() {
-    use halo2_proofs::halo2curves::pasta::Fp;
+    use halo2_proofs::halo2curves::bn256::Fr as Fp;
     use plotters::prelude::*;
 
     let root =
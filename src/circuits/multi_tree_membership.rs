@@ -0,0 +1,206 @@
+use super::super::chips::merkle_sum_tree::{MerkleSumTreeChip, MerkleSumTreeConfig};
+use super::merkle_sum_tree::MerkleSumTreeCircuit;
+use super::utils::NumPublic;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, halo2curves::bn256::Fr as Fp, plonk::*};
+
+#[derive(Debug, Clone)]
+pub struct MultiTreeMembershipConfig<F: Field> {
+    tree_a_config: MerkleSumTreeConfig<F>,
+    tree_b_config: MerkleSumTreeConfig<F>,
+}
+
+// Proves that a leaf is in tree A and that a related leaf is in tree B within one proof, e.g. for audits across
+// two exchanges. Each tree has its own `MerkleSumTreeChip` configuration with distinct advice columns, both of
+// them sharing the instance column. The paths are the ones of `MerkleSumTreeCircuit` in membership only mode:
+// the public inputs are the leaf hash (row 0) and the root hash (row 1) of tree A, followed by the leaf hash
+// (row 2) and the root hash (row 3) of tree B, see `public_inputs`
+#[derive(Default)]
+pub struct MultiTreeMembershipCircuit<F: Field> {
+    pub tree_a: MerkleSumTreeCircuit<F>,
+    pub tree_b: MerkleSumTreeCircuit<F>,
+}
+
+impl<F: Field> MultiTreeMembershipCircuit<F> {
+    pub fn new(mut tree_a: MerkleSumTreeCircuit<F>, mut tree_b: MerkleSumTreeCircuit<F>) -> Self {
+        // the balances are not compared against the assets sum of the trees
+        tree_a.membership_only = true;
+        tree_b.membership_only = true;
+
+        Self { tree_a, tree_b }
+    }
+
+    // Proves the path of `tree` and exposes its leaf hash at `first_row` and its root hash at `first_row + 1`
+    fn prove_membership(
+        config: MerkleSumTreeConfig<F>,
+        mut layouter: impl Layouter<F>,
+        tree: &MerkleSumTreeCircuit<F>,
+        first_row: usize,
+    ) -> Result<(), Error> {
+        tree.validate()?;
+
+        let chip = MerkleSumTreeChip::construct(config);
+        chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+
+        let (leaf_hash, leaf_balance) = chip.assing_leaf_hash_and_balance(
+            layouter.namespace(|| "assign leaf"),
+            tree.leaf_hash,
+            tree.leaf_balance,
+        )?;
+        chip.expose_public(
+            layouter.namespace(|| "public leaf hash"),
+            &leaf_hash,
+            first_row,
+        )?;
+
+        let (mut next_hash, mut next_sum) = (leaf_hash, leaf_balance);
+        for i in 0..tree.path_element_hashes.len() {
+            (next_hash, next_sum) = chip.merkle_prove_layer(
+                layouter.namespace(|| format!("level {} merkle proof", i)),
+                i,
+                &next_hash,
+                &next_sum,
+                tree.path_element_hashes[i],
+                tree.path_element_balances[i],
+                tree.path_indices[i],
+            )?;
+        }
+
+        chip.expose_public(
+            layouter.namespace(|| "public root"),
+            &next_hash,
+            first_row + 1,
+        )
+    }
+}
+
+impl MultiTreeMembershipCircuit<Fp> {
+    // Returns the public inputs in the same order as they are exposed by `synthesize`
+    pub fn public_inputs(&self) -> Vec<Fp> {
+        let mut public_inputs = self.tree_a.public_inputs();
+        public_inputs.extend(self.tree_b.public_inputs());
+        public_inputs
+    }
+}
+
+impl<F: Field> Circuit<F> for MultiTreeMembershipCircuit<F> {
+    type Config = MultiTreeMembershipConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            tree_a: self.tree_a.without_witnesses(),
+            tree_b: self.tree_b.without_witnesses(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+
+        let tree_a_advice = [(); 5].map(|_| meta.advice_column());
+        let tree_a_config = MerkleSumTreeChip::configure(meta, tree_a_advice, instance);
+
+        let tree_b_advice = [(); 5].map(|_| meta.advice_column());
+        let tree_b_config = MerkleSumTreeChip::configure(meta, tree_b_advice, instance);
+
+        MultiTreeMembershipConfig {
+            tree_a_config,
+            tree_b_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        Self::prove_membership(
+            config.tree_a_config,
+            layouter.namespace(|| "tree a"),
+            &self.tree_a,
+            0,
+        )?;
+        Self::prove_membership(
+            config.tree_b_config,
+            layouter.namespace(|| "tree b"),
+            &self.tree_b,
+            2,
+        )
+    }
+}
+
+// leaf hash and root hash of both trees
+impl<F: Field> NumPublic for MultiTreeMembershipCircuit<F> {
+    fn num_public(&self) -> usize {
+        4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiTreeMembershipCircuit;
+    use crate::circuits::merkle_sum_tree::MerkleSumTreeCircuit;
+    use crate::circuits::utils::run_checked;
+    use crate::merkle_sum_tree::{hash_leaf, MerkleSumTree, Node};
+    use halo2_proofs::halo2curves::bn256::Fr as Fp;
+
+    // two trees of 4 leaves, the leaf at index `i` of tree B belongs to the same user as the one of tree A
+    fn build_trees() -> (MerkleSumTree, MerkleSumTree) {
+        let leaves = |balances: [u64; 4]| {
+            balances
+                .iter()
+                .enumerate()
+                .map(|(i, balance)| hash_leaf(Fp::from(i as u64 + 1), Fp::from(*balance)))
+                .collect::<Vec<Node>>()
+        };
+
+        (
+            MerkleSumTree::new(leaves([10, 20, 30, 40])),
+            MerkleSumTree::new(leaves([5, 15, 25, 35])),
+        )
+    }
+
+    fn instantiate_circuit(
+        tree_a: &MerkleSumTree,
+        tree_b: &MerkleSumTree,
+        index: usize,
+    ) -> MultiTreeMembershipCircuit<Fp> {
+        MultiTreeMembershipCircuit::new(
+            MerkleSumTreeCircuit::from_proof(&tree_a.generate_proof(index), Fp::zero()),
+            MerkleSumTreeCircuit::from_proof(&tree_b.generate_proof(index), Fp::zero()),
+        )
+    }
+
+    #[test]
+    fn test_multi_tree_membership() {
+        let (tree_a, tree_b) = build_trees();
+        let circuit = instantiate_circuit(&tree_a, &tree_b, 2);
+
+        let public_input = circuit.public_inputs();
+        assert_eq!(
+            public_input,
+            vec![
+                tree_a.leaves()[2].hash,
+                tree_a.root().hash,
+                tree_b.leaves()[2].hash,
+                tree_b.root().hash,
+            ]
+        );
+
+        let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_multi_tree_membership_wrong_path() {
+        let (tree_a, tree_b) = build_trees();
+        let mut circuit = instantiate_circuit(&tree_a, &tree_b, 2);
+        let public_input = circuit.public_inputs();
+
+        // a wrong sibling at level 0 of tree B, so the path doesn't lead to the root of tree B
+        circuit.tree_b.path_element_hashes[0] = tree_b.leaves()[0].hash;
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use super::super::chips::gadgets::{MuxChip, MuxConfig};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+#[derive(Debug, Clone)]
+struct MuxCircuitConfig {
+    mux_config: MuxConfig,
+    inputs: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+// loads cond, a and b and exposes cond ? a : b
+#[derive(Default)]
+struct MuxCircuit<F: Field> {
+    pub cond: Value<F>,
+    pub a: Value<F>,
+    pub b: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for MuxCircuit<F> {
+    type Config = MuxCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let inputs = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(inputs);
+        meta.enable_equality(instance);
+
+        let cond = meta.advice_column();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let out = meta.advice_column();
+        let mux_config = MuxChip::configure(meta, cond, a, b, out);
+
+        MuxCircuitConfig {
+            mux_config,
+            inputs,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let (cond, a, b) = layouter.assign_region(
+            || "load inputs",
+            |mut region| {
+                let cond = region.assign_advice(|| "cond", config.inputs, 0, || self.cond)?;
+                let a = region.assign_advice(|| "a", config.inputs, 1, || self.a)?;
+                let b = region.assign_advice(|| "b", config.inputs, 2, || self.b)?;
+                Ok((cond, a, b))
+            },
+        )?;
+
+        let chip = MuxChip::construct(config.mux_config);
+        let out = chip.assign(layouter.namespace(|| "mux"), &cond, &a, &b)?;
+
+        layouter.constrain_instance(out.cell(), config.instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MuxCircuit;
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+
+    fn mux_circuit(cond: u64) -> MuxCircuit<Fp> {
+        MuxCircuit {
+            cond: Value::known(Fp::from(cond)),
+            a: Value::known(Fp::from(5u64)),
+            b: Value::known(Fp::from(7u64)),
+        }
+    }
+
+    #[test]
+    fn test_mux() {
+        let k = 4;
+
+        // cond is 1, a is selected
+        let circuit = mux_circuit(1);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(5u64)]]).unwrap();
+        prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(7u64)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+
+        // cond is 0, b is selected
+        let circuit = mux_circuit(0);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(7u64)]]).unwrap();
+        prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(5u64)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_mux_non_boolean_cond() {
+        let k = 4;
+
+        // the witness of out is b, which is exposed, but cond = 2 fails the mux gate
+        let circuit = mux_circuit(2);
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(7u64)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
@@ -1,15 +1,39 @@
 use eth_types::Field;
 use halo2_proofs::{circuit::*, plonk::*};
 
+use super::super::chips::is_zero::IsZeroChip;
 use super::super::chips::overflow_check::{OverFlowCheckConfig, OverFlowChip};
 
+// Builds the columns of an overflow chip with ACC_COLS accumulator columns of MAX_BITS bits
+fn configure_overflow_chip<const MAX_BITS: u8, const ACC_COLS: usize, F: Field>(
+    meta: &mut ConstraintSystem<F>,
+) -> OverFlowCheckConfig<MAX_BITS, ACC_COLS, F> {
+    let update_value = meta.advice_column();
+    let left_most_inv = meta.advice_column();
+    let accumulate = [(); ACC_COLS].map(|_| meta.advice_column());
+    let constant = meta.fixed_column();
+    let carry_selector = meta.selector();
+    let overflow_selector = meta.selector();
+    let instance = meta.instance_column();
+
+    OverFlowChip::configure(
+        meta,
+        update_value,
+        left_most_inv,
+        accumulate,
+        constant,
+        [carry_selector, overflow_selector],
+        instance,
+    )
+}
+
 #[derive(Default)]
-struct OverflowCheckCircuit<F: Field> {
+pub struct OverflowCheckCircuit<F: Field> {
     pub a: Value<F>,
 }
 
 impl<F: Field> Circuit<F> for OverflowCheckCircuit<F> {
-    type Config = OverFlowCheckConfig<F>;
+    type Config = OverFlowCheckConfig<16, 3, F>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
@@ -17,21 +41,7 @@ impl<F: Field> Circuit<F> for OverflowCheckCircuit<F> {
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let col_a = meta.advice_column();
-        let col_b_inv = meta.advice_column();
-        let col_b = meta.advice_column();
-        let col_c = meta.advice_column();
-        let col_d = meta.advice_column();
-        let carry_selector = meta.selector();
-        let overflow_selector = meta.selector();
-        let instance = meta.instance_column();
-
-        OverFlowChip::configure(
-            meta,
-            [col_a, col_b_inv, col_b, col_c, col_d],
-            [carry_selector, overflow_selector],
-            instance,
-        )
+        configure_overflow_chip(meta)
     }
 
     fn synthesize(
@@ -41,21 +51,216 @@ impl<F: Field> Circuit<F> for OverflowCheckCircuit<F> {
     ) -> Result<(), Error> {
         let chip = OverFlowChip::construct(config);
 
-        let (prev_b, prev_c, prev_d) =
-            chip.assign_first_row(layouter.namespace(|| "load first row"))?;
+        chip.load(&mut layouter)?;
 
-        let (b, c, d) = chip.assign_advice_row(
-            layouter.namespace(|| "load row"),
-            self.a,
-            prev_b.clone(),
-            prev_c.clone(),
-            prev_d.clone(),
-        )?;
+        let prev = chip.assign_first_row(layouter.namespace(|| "load first row"))?;
+
+        let sum = chip.assign_advice_row(layouter.namespace(|| "load row"), self.a, prev)?;
 
         // check computation result
-        chip.expose_public(layouter.namespace(|| "overflow check"), &b, 2)?;
-        chip.expose_public(layouter.namespace(|| "sum_high check"), &c, 3)?;
-        chip.expose_public(layouter.namespace(|| "sum_low check"), &d, 4)?;
+        chip.expose_public(layouter.namespace(|| "overflow check"), &sum[0], 2)?;
+        chip.expose_public(layouter.namespace(|| "sum_high check"), &sum[1], 3)?;
+        chip.expose_public(layouter.namespace(|| "sum_low check"), &sum[2], 4)?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+// sums all the values in a chain of regions. The accumulator is split into ACC_COLS columns of MAX_BITS bits,
+// where the most significant column is the overflow column and must stay zero, so the sum is capped at
+// MAX_BITS * (ACC_COLS - 1) bits. The initial accumulator is taken from the rows 0..ACC_COLS - 1 of the
+// instance column, and the final sum is exposed in the next ACC_COLS rows, starting with the overflow column
+struct OverflowAccumulateCircuit<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
+    pub values: Vec<Value<F>>,
+}
+
+impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> Circuit<F>
+    for OverflowAccumulateCircuit<MAX_BITS, ACC_COLS, F>
+{
+    type Config = OverFlowCheckConfig<MAX_BITS, ACC_COLS, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        configure_overflow_chip(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = OverFlowChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let mut sum = chip.assign_first_row(layouter.namespace(|| "load first row"))?;
+
+        // the cells of each row are carried to the next one
+        for (i, value) in self.values.iter().enumerate() {
+            sum = chip.assign_advice_row(
+                layouter.namespace(|| format!("accumulate value {}", i)),
+                *value,
+                sum,
+            )?;
+        }
+
+        // only the final sum and the overflow flag are exposed
+        for (i, cell) in sum.iter().enumerate() {
+            chip.expose_public(
+                layouter.namespace(|| format!("sum acc[{}] check", i)),
+                cell,
+                ACC_COLS - 1 + i,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+// accumulates `first`, exposes the checkpoint at rows 2, 3 and 4, then resets the accumulator to zero
+// and accumulates `second`, whose sum is exposed at rows 5, 6 and 7
+struct OverflowResetCircuit<F: Field> {
+    pub first: Vec<Value<F>>,
+    pub second: Vec<Value<F>>,
+}
+
+impl<F: Field> Circuit<F> for OverflowResetCircuit<F> {
+    type Config = OverFlowCheckConfig<16, 3, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            first: vec![Value::unknown(); self.first.len()],
+            second: vec![Value::unknown(); self.second.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        configure_overflow_chip(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = OverFlowChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let mut sum = chip.assign_first_row(layouter.namespace(|| "load first row"))?;
+        for (i, value) in self.first.iter().enumerate() {
+            sum = chip.assign_advice_row(
+                layouter.namespace(|| format!("accumulate first segment value {}", i)),
+                *value,
+                sum,
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "checkpoint overflow"), &sum[0], 2)?;
+        chip.expose_public(layouter.namespace(|| "checkpoint sum_high"), &sum[1], 3)?;
+        chip.expose_public(layouter.namespace(|| "checkpoint sum_low"), &sum[2], 4)?;
+
+        sum = chip.reset_to_zero(layouter.namespace(|| "reset"))?;
+        for (i, value) in self.second.iter().enumerate() {
+            sum = chip.assign_advice_row(
+                layouter.namespace(|| format!("accumulate second segment value {}", i)),
+                *value,
+                sum,
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "overflow check"), &sum[0], 5)?;
+        chip.expose_public(layouter.namespace(|| "sum_high check"), &sum[1], 6)?;
+        chip.expose_public(layouter.namespace(|| "sum_low check"), &sum[2], 7)?;
+        Ok(())
+    }
+}
+
+// Adds 1 to the accumulator 0xffff_ffff taken from the instance column, but witnesses the sum 2^32 as 0x1_0000 in
+// sum_hi instead of carrying it into the overflow column. The accumulation constraint is satisfied and the
+// overflow column stays zero, so only the range check on sum_hi rejects the witness
+#[derive(Default)]
+struct ForgedSumCircuit<F: Field> {
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: Field> Circuit<F> for ForgedSumCircuit<F> {
+    type Config = OverFlowCheckConfig<16, 3, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        configure_overflow_chip(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = OverFlowChip::construct(config.clone());
+
+        chip.load(&mut layouter)?;
+
+        let prev = chip.assign_first_row(layouter.namespace(|| "load first row"))?;
+
+        let forged_sum = [0u64, 1 << 16, 0];
+
+        let sum = layouter.assign_region(
+            || "forged sum",
+            |mut region| {
+                config.selector[0].enable(&mut region, 1)?;
+                config.selector[1].enable(&mut region, 1)?;
+                config.range_selector.enable(&mut region, 1)?;
+
+                for (i, cell) in prev.iter().enumerate() {
+                    cell.copy_advice(
+                        || format!("prev acc[{}]", i),
+                        &mut region,
+                        config.accumulate[i],
+                        0,
+                    )?;
+                }
+
+                region.assign_advice(|| "a", config.update_value, 1, || Value::known(F::one()))?;
+
+                let mut cells = vec![];
+                for (i, limb) in forged_sum.iter().enumerate() {
+                    cells.push(region.assign_advice(
+                        || format!("forged acc[{}]", i),
+                        config.accumulate[i],
+                        1,
+                        || Value::known(F::from(*limb)),
+                    )?);
+                }
+
+                IsZeroChip::construct(config.is_zero.clone()).assign(
+                    &mut region,
+                    1,
+                    Value::known(F::zero()),
+                )?;
+
+                Ok(cells)
+            },
+        )?;
+
+        for (i, cell) in sum.iter().enumerate() {
+            chip.expose_public(
+                layouter.namespace(|| format!("sum acc[{}] check", i)),
+                cell,
+                2 + i,
+            )?;
+        }
         Ok(())
     }
 }
@@ -63,14 +268,21 @@ impl<F: Field> Circuit<F> for OverflowCheckCircuit<F> {
 #[cfg(test)]
 mod tests {
     use std::panic;
-    use super::OverflowCheckCircuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+    use super::{
+        ForgedSumCircuit, OverflowAccumulateCircuit, OverflowCheckCircuit, OverflowResetCircuit,
+    };
+    use halo2_proofs::{
+        circuit::Value,
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr as Fp,
+    };
     #[test]
     fn test_none_overflow_case() {
-        let k = 4;
+        // the range table of 16 bits needs 2^17 rows
+        let k = 17;
 
         // a: new value
-        let a = Value::known(Fp::from((1 << 16) + 3));
+        let a = Value::known(Fp::from(3));
         let public_inputs = vec![
             // initial values for A[3], A[4], last two columns
             Fp::from(0),
@@ -78,7 +290,7 @@ mod tests {
             //
             // checking value
             Fp::from(0), // 2^32 <- 0 means not overflowed
-            Fp::from(2), // 2^16
+            Fp::from(1), // 2^16
             Fp::from(1), // 2^0
         ];
 
@@ -90,18 +302,18 @@ mod tests {
 
     #[test]
     fn test_overflow_case() {
-        let k = 4;
+        let k = 17;
 
         // a: new value
-        let a = Value::known(Fp::from((1 << 32) + 2));
+        let a = Value::known(Fp::from(2));
         let public_inputs = vec![
             // initial values for A[3], A[4], last two columns
-            Fp::from(0),             // 0 * 2^16
-            Fp::from((1 << 16) - 1), // only for testing, over 2^16 is not allowed on accumulated columns
+            Fp::from((1 << 16) - 1), // 0xffff * 2^16
+            Fp::from((1 << 16) - 1),
             //
             // checking value
             Fp::from(1), // 2^32 <- not 0 means overflowed
-            Fp::from(1), // 2^16
+            Fp::from(0), // 2^16
             Fp::from(1), // 2^0
         ];
 
@@ -112,4 +324,205 @@ mod tests {
         let panic_result = panic::catch_unwind(|| prover.assert_satisfied());
         assert!(panic_result.is_err());
     }
+
+    #[test]
+    fn test_double_boundary_case() {
+        let k = 17;
+
+        // the initial accumulator is 2^32 - 2
+        let verify = |a: u64, sum: [u64; 3]| {
+            let circuit = OverflowCheckCircuit {
+                a: Value::known(Fp::from(a)),
+            };
+            let mut public_inputs = vec![Fp::from((1 << 16) - 1), Fp::from((1 << 16) - 2)];
+            public_inputs.extend(sum.map(Fp::from));
+            MockProver::run(k, &circuit, vec![public_inputs])
+                .unwrap()
+                .verify()
+                .is_ok()
+        };
+
+        // 2^32 - 1 fills both columns
+        assert!(verify(1, [0, (1 << 16) - 1, (1 << 16) - 1]));
+
+        // 2^32 carries through both columns into the overflow column, which is rejected
+        assert!(!verify(2, [1, 0, 0]));
+    }
+
+    #[test]
+    fn test_new_value_out_of_range() {
+        let k = 17;
+
+        // 2^16 + 3 doesn't fit into 16 bits, even though the sum doesn't overflow
+        let circuit = OverflowCheckCircuit {
+            a: Value::known(Fp::from((1 << 16) + 3)),
+        };
+        let public_inputs = vec![
+            Fp::from(0),
+            Fp::from((1 << 16) - 2),
+            Fp::from(0),
+            Fp::from(2),
+            Fp::from(1),
+        ];
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn test_forged_sum() {
+        let k = 17;
+
+        // the sum 2^32 is witnessed as 0x1_0000 in sum_hi, which keeps the overflow column at zero
+        let public_inputs = vec![
+            Fp::from((1 << 16) - 1),
+            Fp::from((1 << 16) - 1),
+            Fp::from(0),
+            Fp::from(1 << 16),
+            Fp::from(0),
+        ];
+
+        let circuit = ForgedSumCircuit::<Fp>::default();
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn test_accumulate_none_overflow_case() {
+        let k = 17;
+
+        // 0xffff_0000 + 0x8000 + 0x7fff = 2^32 - 1, the largest sum that doesn't overflow
+        let values = vec![
+            Value::known(Fp::from(0x8000)),
+            Value::known(Fp::from(0x7fff)),
+        ];
+
+        let public_inputs = vec![
+            // initial accumulator
+            Fp::from((1 << 16) - 1),
+            Fp::from(0),
+            //
+            // checking value
+            Fp::from(0),             // 2^32 <- 0 means not overflowed
+            Fp::from((1 << 16) - 1), // 2^16
+            Fp::from((1 << 16) - 1), // 2^0
+        ];
+
+        let circuit = OverflowAccumulateCircuit::<16, 3, Fp> { values };
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_accumulate_overflow_case() {
+        let k = 17;
+
+        // the running sum reaches 2^32 at the second value, each value is valid on its own
+        let values = vec![Value::known(Fp::from(1)); 6];
+
+        let public_inputs = vec![
+            // initial accumulator, 2^32 - 2
+            Fp::from((1 << 16) - 1),
+            Fp::from((1 << 16) - 2),
+            //
+            // checking value, 2^32 - 2 + 6 = 2^32 + 4
+            Fp::from(1), // 2^32 <- not 0 means overflowed
+            Fp::from(0), // 2^16
+            Fp::from(4), // 2^0
+        ];
+
+        // the exposed sum is the actual one, so only the overflow check rejects the accumulation
+        let circuit = OverflowAccumulateCircuit::<16, 3, Fp> { values };
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::ConstraintNotSatisfied { .. })));
+    }
+
+    #[test]
+    fn test_accumulate_four_columns() {
+        let k = 17;
+
+        // 4 columns of 16 bits, the sum is capped at 2^48. (2^48 - 2) + 1 = 2^48 - 1
+        let values = vec![Value::known(Fp::from(1))];
+
+        let public_inputs = vec![
+            // initial accumulator
+            Fp::from((1 << 16) - 1),
+            Fp::from((1 << 16) - 1),
+            Fp::from((1 << 16) - 2),
+            //
+            // checking value
+            Fp::from(0),             // 2^48 <- 0 means not overflowed
+            Fp::from((1 << 16) - 1), // 2^32
+            Fp::from((1 << 16) - 1), // 2^16
+            Fp::from((1 << 16) - 1), // 2^0
+        ];
+
+        let circuit = OverflowAccumulateCircuit::<16, 4, Fp> {
+            values: values.clone(),
+        };
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        prover.assert_satisfied();
+
+        // one more reaches 2^48, which carries into the overflow column
+        let mut overflowed = values;
+        overflowed.push(Value::known(Fp::from(1)));
+        let public_inputs = vec![
+            Fp::from((1 << 16) - 1),
+            Fp::from((1 << 16) - 1),
+            Fp::from((1 << 16) - 2),
+            Fp::from(1),
+            Fp::from(0),
+            Fp::from(0),
+            Fp::from(0),
+        ];
+
+        let circuit = OverflowAccumulateCircuit::<16, 4, Fp> { values: overflowed };
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_accumulate_reset_accumulate() {
+        let k = 17;
+
+        // 0xfffe_0000 + 5 * 0x8000 would overflow, but the accumulator is reset after the third value
+        let first = vec![Value::known(Fp::from(0x8000)); 3];
+        let second = vec![Value::known(Fp::from(0x8000)); 2];
+
+        let public_inputs = vec![
+            // initial accumulator
+            Fp::from(0xfffe),
+            Fp::from(0),
+            //
+            // checkpoint, 0xfffe_0000 + 3 * 0x8000
+            Fp::from(0),
+            Fp::from(0xffff),
+            Fp::from(0x8000),
+            //
+            // sum after the reset, 2 * 0x8000
+            Fp::from(0),
+            Fp::from(1),
+            Fp::from(0),
+        ];
+
+        let circuit = OverflowResetCircuit { first, second };
+        let prover = MockProver::run(k, &circuit, vec![public_inputs.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // the reset can't keep the checkpoint value, the final sum starts from zero
+        let mut wrong_inputs = public_inputs;
+        wrong_inputs[5] = Fp::from(1);
+        wrong_inputs[6] = Fp::from(0);
+        wrong_inputs[7] = Fp::from(0x8000);
+        let invalid_prover = MockProver::run(k, &circuit, vec![wrong_inputs]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
 }
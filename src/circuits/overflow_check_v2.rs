@@ -5,13 +5,13 @@ use super::super::chips::overflow_check_v2::{OverflowCheckV2Config, OverflowChip
 // use crate::chips::utils::{decompose_bigInt_to_ubits, value_f_to_big_uint};
 
 #[derive(Default)]
-struct OverflowCheckCircuitV2<F: Field> {
+pub struct OverflowCheckCircuitV2<F: Field> {
     pub a: Value<F>,
     pub b: Value<F>,
 }
 
 impl<F: Field> Circuit<F> for OverflowCheckCircuitV2<F> {
-    type Config = OverflowCheckV2Config<4, 4>;
+    type Config = OverflowCheckV2Config<4, 4, 4>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
@@ -50,19 +50,134 @@ impl<F: Field> Circuit<F> for OverflowCheckCircuitV2<F> {
         // check overflow
         chip.assign(layouter.namespace(|| "checking overflow value a"), self.a)?;
         chip.assign(layouter.namespace(|| "checking overflow value b"), self.b)?;
-        chip.assign(
+        let (_, sum_limbs) = chip.assign(
             layouter.namespace(|| "checking overflow value a + b"),
             self.a + self.b,
         )?;
 
+        // the most significant limb of a + b is exposed
+        chip.expose_public(layouter.namespace(|| "top limb of a + b"), &sum_limbs[0], 0)?;
+
         Ok(())
     }
 }
 
+// 2 limbs of 8 bits, each range checked as 2 chunks of 4 bits, so the table has 16 rows instead of 256
+#[derive(Default)]
+struct OverflowCheckSmallTableCircuit<F: Field> {
+    pub a: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for OverflowCheckSmallTableCircuit<F> {
+    type Config = OverflowCheckV2Config<8, 2, 4>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let decomposed_values = [meta.advice_column(), meta.advice_column()];
+        let range = meta.fixed_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+
+        OverflowChipV2::configure(meta, value, decomposed_values, range, instance, selector)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = OverflowChipV2::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let (_, limbs) = chip.assign(layouter.namespace(|| "checking overflow value a"), self.a)?;
+
+        chip.expose_public(layouter.namespace(|| "top limb of a"), &limbs[0], 0)?;
+
+        Ok(())
+    }
+}
+
+// Assigns the limbs of 0xabcd like `OverflowCheckSmallTableCircuit`, but the top limb 0xab is split into the chunks
+// 0x1b and 0x9, which recompose to 0xab while 0x1b doesn't fit into TABLE_BITS
+#[derive(Default)]
+struct ForgedChunkCircuit<F: Field> {
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: Field> Circuit<F> for ForgedChunkCircuit<F> {
+    type Config = OverflowCheckV2Config<8, 2, 4>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        OverflowCheckSmallTableCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = OverflowChipV2::<8, 2, 4, F>::construct(config.clone());
+
+        chip.load(&mut layouter)?;
+
+        let top_limb = layouter.assign_region(
+            || "assign forged chunks",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                config.lookup_selector.enable(&mut region, 1)?;
+                config.lookup_selector.enable(&mut region, 2)?;
+
+                region.assign_advice(
+                    || "assign value",
+                    config.value,
+                    0,
+                    || Value::known(F::from(0xabcd)),
+                )?;
+
+                // limb, then its chunks little-endian
+                let columns = [[0xab, 0x1b, 0x9], [0xcd, 0xd, 0xc]];
+                let mut limbs = vec![];
+                for (column, values) in config.decomposed_values.iter().zip(columns) {
+                    for (row, value) in values.iter().enumerate() {
+                        let cell = region.assign_advice(
+                            || format!("assign row {}", row),
+                            *column,
+                            row,
+                            || Value::known(F::from(*value)),
+                        )?;
+                        if row == 0 {
+                            limbs.push(cell);
+                        }
+                    }
+                }
+
+                Ok(limbs.remove(0))
+            },
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "top limb of a"), &top_limb, 0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::OverflowCheckCircuitV2;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+    use super::{ForgedChunkCircuit, OverflowCheckCircuitV2, OverflowCheckSmallTableCircuit};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr as Fp,
+    };
     #[test]
     fn test_none_overflow_case() {
         let k = 5;
@@ -72,7 +187,8 @@ mod tests {
         let b = Value::known(Fp::from(1));
 
         let circuit = OverflowCheckCircuitV2::<Fp> { a, b };
-        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        // top limb of 0xffff
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0xf)]]).unwrap();
         prover.assert_satisfied();
     }
 
@@ -85,7 +201,50 @@ mod tests {
         let b = Value::known(Fp::from(3));
 
         let circuit = OverflowCheckCircuitV2 { a, b };
-        let invalid_prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_expose_top_limb() {
+        let k = 5;
+
+        // a + b = 0x0fff, the top limb is zero
+        let a = Value::known(Fp::from(0x0ffe));
+        let b = Value::known(Fp::from(1));
+
+        let circuit = OverflowCheckCircuitV2 { a, b };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0xf)]]).unwrap();
         assert!(invalid_prover.verify().is_err());
     }
+
+    #[test]
+    fn test_small_table() {
+        let k = 5;
+
+        // 0xabcd fits into 16 bits, the top limb is 0xab
+        let circuit = OverflowCheckSmallTableCircuit {
+            a: Value::known(Fp::from(0xabcd)),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0xab)]]).unwrap();
+        prover.assert_satisfied();
+
+        // 2^16 doesn't fit into two limbs of 8 bits, even though the table only covers 4 bits
+        let circuit = OverflowCheckSmallTableCircuit {
+            a: Value::known(Fp::from(1 << 16)),
+        };
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+
+        // the chunks of the top limb recompose to 0xab, but 0x1b is out of the 4 bits table
+        let circuit = ForgedChunkCircuit::<Fp>::default();
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0xab)]]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
 }
@@ -0,0 +1,110 @@
+use super::super::chips::gadgets::{PackLimbsChip, PackLimbsConfig};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+#[derive(Debug, Clone)]
+struct PackLimbsCircuitConfig {
+    pack_limbs_config: PackLimbsConfig,
+    limbs: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+// loads little-endian limbs of `max_bits` bits and exposes the packed value
+#[derive(Default)]
+struct PackLimbsCircuit<F: Field> {
+    pub limbs: Vec<Value<F>>,
+    pub max_bits: usize,
+}
+
+impl<F: Field> Circuit<F> for PackLimbsCircuit<F> {
+    type Config = PackLimbsCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            limbs: vec![Value::unknown(); self.limbs.len()],
+            max_bits: self.max_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let limbs = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(limbs);
+        meta.enable_equality(instance);
+
+        let limb = meta.advice_column();
+        let acc = meta.advice_column();
+        let shift = meta.fixed_column();
+        let pack_limbs_config = PackLimbsChip::configure(meta, limb, acc, shift);
+
+        PackLimbsCircuitConfig {
+            pack_limbs_config,
+            limbs,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let limb_cells = layouter.assign_region(
+            || "load limbs",
+            |mut region| {
+                self.limbs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        region.assign_advice(|| format!("limb {}", i), config.limbs, i, || *value)
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        let chip = PackLimbsChip::construct(config.pack_limbs_config);
+        let packed = chip.pack_limbs_to_cell(
+            layouter.namespace(|| "pack limbs"),
+            &limb_cells,
+            self.max_bits,
+        )?;
+
+        layouter.constrain_instance(packed.cell(), config.instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PackLimbsCircuit;
+    use crate::chips::utils::decompose_bigInt_to_ubits;
+    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+    use num_bigint::BigUint;
+
+    fn round_trip(value: u128, number_of_limbs: usize, max_bits: usize) {
+        let limbs =
+            decompose_bigInt_to_ubits::<Fp>(&BigUint::from(value), number_of_limbs, max_bits);
+
+        let circuit = PackLimbsCircuit {
+            limbs: limbs.into_iter().map(Value::known).collect(),
+            max_bits,
+        };
+
+        let packed = Fp::from((value >> 64) as u64) * Fp::from(2).pow(&[64, 0, 0, 0])
+            + Fp::from(value as u64);
+
+        let prover = MockProver::run(5, &circuit, vec![vec![packed]]).unwrap();
+        prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(5, &circuit, vec![vec![packed + Fp::one()]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_pack_limbs_round_trip() {
+        // 4 limbs of 16 bits
+        round_trip(0x1234_5678_9abc_def0, 4, 16);
+        // 4 limbs of 32 bits, the value doesn't fit into a u64
+        round_trip(0xa_1234_5678_9abc_def0_1234_5678, 4, 32);
+    }
+}
@@ -1,20 +1,33 @@
 use super::super::chips::poseidon::hash_with_instance::{PoseidonChip, PoseidonConfig};
+use super::super::chips::poseidon::spec::MySpec;
 use halo2_gadgets::poseidon::primitives::*;
 use halo2_proofs::{circuit::*, arithmetic::FieldExt, plonk::*};
 use std::marker::PhantomData;
 
-struct PoseidonCircuit<
+pub struct PoseidonCircuit<
     F: FieldExt,
     S: Spec<F, WIDTH, RATE>,
     const WIDTH: usize,
     const RATE: usize,
     const L: usize,
 > {
-    hash_input: [Value<F>; L],
-    digest: Value<F>,
+    pub hash_input: [Value<F>; L],
+    pub digest: Value<F>,
     _spec: PhantomData<S>,
 }
 
+impl<F: FieldExt, S: Spec<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize, const L: usize>
+    PoseidonCircuit<F, S, WIDTH, RATE, L>
+{
+    pub fn new(hash_input: [Value<F>; L], digest: Value<F>) -> Self {
+        Self {
+            hash_input,
+            digest,
+            _spec: PhantomData,
+        }
+    }
+}
+
 impl<F:FieldExt, S: Spec<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize, const L: usize> Circuit<F>
     for PoseidonCircuit<F, S, WIDTH, RATE, L>
 {
@@ -59,12 +72,61 @@ impl<F:FieldExt, S: Spec<F, WIDTH, RATE>, const WIDTH: usize, const RATE: usize,
     }
 }
 
+// hashes (a, b), then hashes the carried digest together with the fresh value c with `hash_mixed`,
+// the final digest H(H(a, b), c) is exposed at row 0
+struct PoseidonMixedCircuit<F: FieldExt> {
+    a: Value<F>,
+    b: Value<F>,
+    c: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for PoseidonMixedCircuit<F> {
+    type Config = PoseidonConfig<F, 3, 2, 2>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: Value::unknown(),
+            b: Value::unknown(),
+            c: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        let hash_inputs = (0..3).map(|_| meta.advice_column()).collect::<Vec<_>>();
+
+        PoseidonChip::<F, MySpec<F, 3, 2>, 3, 2, 2>::configure(meta, hash_inputs, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let poseidon_chip = PoseidonChip::<F, MySpec<F, 3, 2>, 3, 2, 2>::construct(config);
+
+        let input_cells = poseidon_chip
+            .load_private_inputs(layouter.namespace(|| "load a and b"), [self.a, self.b])?;
+        let carried = poseidon_chip.hash(layouter.namespace(|| "hash a and b"), &input_cells)?;
+
+        let digest = poseidon_chip.hash_mixed(
+            layouter.namespace(|| "hash carried digest and c"),
+            &[carried],
+            &[self.c],
+        )?;
+        poseidon_chip.expose_public(layouter.namespace(|| "expose result"), &digest, 0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::super::chips::poseidon::spec::MySpec;
-    use super::PoseidonCircuit;
+    use super::super::super::chips::poseidon::spec::{ConfigurableSpec, MySpec, SpecParams};
+    use super::{PoseidonCircuit, PoseidonMixedCircuit};
     use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+    use halo2_proofs::{
+        arithmetic::FieldExt, circuit::Value, dev::MockProver, halo2curves::pasta::Fp,
+    };
     use std::marker::PhantomData;
     #[test]
     fn test_poseidon() {
@@ -99,6 +161,93 @@ mod tests {
         prover.assert_satisfied();
     }
 
+    // INSECURE: the number of rounds is far below the security level of poseidon,
+    // only meant for performance experiments
+    #[derive(Debug, Clone, Copy)]
+    struct ReducedRounds;
+
+    impl<F: FieldExt, const WIDTH: usize, const RATE: usize> SpecParams<F, WIDTH, RATE>
+        for ReducedRounds
+    {
+        const FULL_ROUNDS: usize = 4;
+        const PARTIAL_ROUNDS: usize = 10;
+    }
+
+    // same rounds as MySpec
+    #[derive(Debug, Clone, Copy)]
+    struct DefaultRounds;
+
+    impl<F: FieldExt, const WIDTH: usize, const RATE: usize> SpecParams<F, WIDTH, RATE>
+        for DefaultRounds
+    {
+        const FULL_ROUNDS: usize = 8;
+        const PARTIAL_ROUNDS: usize = 56;
+    }
+
+    #[test]
+    fn test_poseidon_reduced_rounds() {
+        const WIDTH: usize = 3;
+        const RATE: usize = 2;
+        const L: usize = 2;
+
+        type InsecureSpec = ConfigurableSpec<Fp, ReducedRounds, WIDTH, RATE>;
+
+        let hash_input = [Fp::from(1u64), Fp::from(2u64)];
+
+        // compute the hash outside of the circuit with the same reduced round spec
+        let digest = poseidon::Hash::<_, InsecureSpec, ConstantLength<L>, WIDTH, RATE>::init()
+            .hash(hash_input);
+
+        // the reduced round hash is different from the one of MySpec, which matches the default rounds
+        let my_spec_digest =
+            poseidon::Hash::<_, MySpec<Fp, WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init()
+                .hash(hash_input);
+        let default_digest = poseidon::Hash::<
+            _,
+            ConfigurableSpec<Fp, DefaultRounds, WIDTH, RATE>,
+            ConstantLength<L>,
+            WIDTH,
+            RATE,
+        >::init()
+        .hash(hash_input);
+        assert_ne!(digest, my_spec_digest);
+        assert_eq!(default_digest, my_spec_digest);
+
+        let circuit = PoseidonCircuit::<Fp, InsecureSpec, WIDTH, RATE, L> {
+            hash_input: hash_input.map(Value::known),
+            digest: Value::known(digest),
+            _spec: PhantomData,
+        };
+        let prover = MockProver::run(6, &circuit, vec![vec![digest]]).unwrap();
+        prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(6, &circuit, vec![vec![my_spec_digest]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_hash_mixed() {
+        let [a, b, c] = [1u64, 2, 3].map(Fp::from);
+
+        let hash = |message: [Fp; 2]| {
+            poseidon::Hash::<_, MySpec<Fp, 3, 2>, ConstantLength<2>, 3, 2>::init().hash(message)
+        };
+        let digest = hash([hash([a, b]), c]);
+
+        let circuit = PoseidonMixedCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+            c: Value::known(c),
+        };
+        let prover = MockProver::run(8, &circuit, vec![vec![digest]]).unwrap();
+        prover.assert_satisfied();
+
+        // the fresh value comes after the carried cell
+        let invalid_prover =
+            MockProver::run(8, &circuit, vec![vec![hash([c, hash([a, b])])]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn print_poseidon() {
@@ -0,0 +1,187 @@
+use super::super::chips::poseidon::hash::{PoseidonChip, PoseidonConfig};
+use super::super::chips::poseidon::spec::MySpec;
+use halo2_gadgets::poseidon::primitives::ConstantLength;
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+const WIDTH: usize = 5;
+const RATE: usize = 4;
+const L: usize = 4;
+const MESSAGE_LEN: usize = 7;
+
+#[derive(Debug, Clone)]
+struct PoseidonSpongeConfig<F: FieldExt> {
+    poseidon_config: PoseidonConfig<F, WIDTH, RATE, L>,
+    message: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+// hashes a message of 7 elements in two absorbs, the first one of 4 cells and the second one of 3 cells.
+// The squeeze fails if the message doesn't have MESSAGE_LEN elements
+struct PoseidonSpongeCircuit<F: FieldExt> {
+    message: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for PoseidonSpongeCircuit<F> {
+    type Config = PoseidonSpongeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            message: vec![Value::unknown(); self.message.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let message = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(message);
+        meta.enable_equality(instance);
+
+        let hash_inputs = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let poseidon_config =
+            PoseidonChip::<F, MySpec<F, WIDTH, RATE>, WIDTH, RATE, L>::configure(meta, hash_inputs);
+
+        PoseidonSpongeConfig {
+            poseidon_config,
+            message,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let message_cells = layouter.assign_region(
+            || "load message",
+            |mut region| {
+                self.message
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        region.assign_advice(
+                            || format!("message {}", i),
+                            config.message,
+                            i,
+                            || *value,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        let poseidon_chip = PoseidonChip::<F, MySpec<F, WIDTH, RATE>, WIDTH, RATE, L>::construct(
+            config.poseidon_config,
+        );
+
+        let mut sponge =
+            poseidon_chip.sponge::<ConstantLength<MESSAGE_LEN>>(layouter.namespace(|| "sponge"))?;
+        for (i, chunk) in message_cells.chunks(RATE).enumerate() {
+            sponge.absorb(layouter.namespace(|| format!("chunk {}", i)), chunk)?;
+        }
+        let digest = sponge.squeeze(layouter.namespace(|| "squeeze"))?;
+
+        layouter.constrain_instance(digest.cell(), config.instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::chips::poseidon::{
+        hash::{poseidon_footprint, PoseidonChip},
+        spec::MySpec,
+    };
+    use super::{PoseidonSpongeCircuit, L, MESSAGE_LEN, RATE, WIDTH};
+    use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        halo2curves::bn256::Fr as Fp,
+        plonk::{ConstraintSystem, Error},
+    };
+
+    #[test]
+    fn test_sponge_two_absorbs() {
+        let message: [Fp; MESSAGE_LEN] = [1, 2, 3, 4, 5, 6, 7].map(|x: u64| Fp::from(x));
+
+        // compute the hash outside of the circuit
+        let digest = poseidon::Hash::<
+            _,
+            MySpec<Fp, WIDTH, RATE>,
+            ConstantLength<MESSAGE_LEN>,
+            WIDTH,
+            RATE,
+        >::init()
+        .hash(message);
+
+        let circuit = PoseidonSpongeCircuit {
+            message: message.map(Value::known).to_vec(),
+        };
+
+        let prover = MockProver::run(8, &circuit, vec![vec![digest]]).unwrap();
+        prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(8, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_squeeze_wrong_length() {
+        // the domain hashes MESSAGE_LEN elements, one is missing
+        let circuit = PoseidonSpongeCircuit {
+            message: (1..MESSAGE_LEN as u64)
+                .map(|x| Value::known(Fp::from(x)))
+                .collect(),
+        };
+
+        let result = MockProver::run(8, &circuit, vec![vec![Fp::from(0)]]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+
+    #[test]
+    #[should_panic(expected = "poseidon chip requires WIDTH = 5 hash input columns, got 4")]
+    fn test_wrong_number_of_hash_inputs() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        // one column is missing
+        let hash_inputs = (0..WIDTH - 1).map(|_| meta.advice_column()).collect();
+
+        PoseidonChip::<Fp, MySpec<Fp, WIDTH, RATE>, WIDTH, RATE, L>::configure(
+            &mut meta,
+            hash_inputs,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "poseidon chip requires the number of inputs L = 5 to be less than WIDTH = 5"
+    )]
+    fn test_too_many_hash_inputs() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let hash_inputs = (0..WIDTH).map(|_| meta.advice_column()).collect();
+
+        // L = WIDTH is rejected when configuring the chip
+        PoseidonChip::<Fp, MySpec<Fp, WIDTH, RATE>, WIDTH, RATE, WIDTH>::configure(
+            &mut meta,
+            hash_inputs,
+        );
+    }
+
+    #[test]
+    fn test_poseidon_footprint() {
+        // the poseidon chip hashing 4 inputs with WIDTH = 5
+        assert_eq!(poseidon_footprint::<WIDTH>(), (6, 10));
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let hash_inputs = (0..WIDTH).map(|_| meta.advice_column()).collect();
+        PoseidonChip::<Fp, MySpec<Fp, WIDTH, RATE>, WIDTH, RATE, L>::configure(
+            &mut meta,
+            hash_inputs,
+        );
+
+        assert_eq!(
+            (meta.num_advice_columns(), meta.num_fixed_columns()),
+            poseidon_footprint::<WIDTH>()
+        );
+    }
+}
@@ -0,0 +1,430 @@
+use super::super::chips::adjacent_leaves::AdjacentLeavesChip;
+use super::super::chips::merkle_sum_tree::{MerkleSumTreeChip, MerkleSumTreeConfig};
+use super::super::chips::safe_accumulator::{SafeACcumulatorChip, SafeAccumulatorConfig};
+use super::super::error::{check_path_lengths, ExperimentError};
+use super::utils::NumPublic;
+use arrayvec::ArrayVec;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+// 8 bits for each accumulate column and 9 columns, the left most one is the overflow column,
+// so the liabilities sum must fit into 64 bits like the sums of the merkle sum tree.
+// Every leaf balance is decomposed into limbs of 8 bits before being accumulated
+const MAX_BITS: u8 = 8;
+const ACC_COLS: usize = 9;
+
+#[derive(Debug, Clone)]
+pub struct ProofOfLiabilitiesConfig<F: Field> {
+    merkle_sum_tree_config: MerkleSumTreeConfig<F>,
+    accumulator_config: SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F>,
+}
+
+// Closes the gap between the sum of a merkle path and the total liabilities:
+// 1. the whole merkle sum tree is rebuilt from the leaf cells, level by level, up to the root
+// 2. the balance cells of the same leaves are accumulated with the overflow protected `SafeACcumulatorChip`, which
+//    decomposes each of them into limbs of MAX_BITS bits. The accumulation starts from zero cells of the constant
+//    column, every update copying the accumulate cells of the previous one
+// 3. the recomposed total is constrained to be equal to the balance of the root
+// The public inputs are the root hash (row 0) and the total liabilities (row 1).
+// The number of leaves must be a power of two, as for `MerkleSumTree`, otherwise the synthesis fails
+#[derive(Default)]
+pub struct ProofOfLiabilitiesCircuit<F: Field> {
+    pub leaf_hashes: Vec<F>,
+    pub leaf_balances: Vec<F>,
+}
+
+impl<F: Field> ProofOfLiabilitiesCircuit<F> {
+    pub fn validate(&self) -> Result<(), ExperimentError> {
+        check_path_lengths(
+            self.leaf_hashes.len(),
+            &[("leaf_balances", self.leaf_balances.len())],
+        )
+    }
+
+    // Returns the hash and the balance cells of every leaf
+    fn assign_leaves(
+        &self,
+        merkle_chip: &MerkleSumTreeChip<F>,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<Vec<(AssignedCell<F, F>, AssignedCell<F, F>)>, Error> {
+        self.leaf_hashes
+            .iter()
+            .zip(self.leaf_balances.iter())
+            .enumerate()
+            .map(|(i, (hash, balance))| {
+                merkle_chip.assing_leaf_hash_and_balance(
+                    layouter.namespace(|| format!("assign leaf {}", i)),
+                    *hash,
+                    *balance,
+                )
+            })
+            .collect()
+    }
+
+    // Copies the balance cells of the leaves into the accumulator, every update decomposing the balance into limbs
+    // and copying the accumulate cells of the previous one. Returns the updated accumulate cells
+    fn accumulate_balances(
+        accumulator_chip: &SafeACcumulatorChip<MAX_BITS, ACC_COLS, F>,
+        mut layouter: impl Layouter<F>,
+        mut accumulate_cells: ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+        leaves: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
+        for (i, (_, balance)) in leaves.iter().enumerate() {
+            accumulate_cells = accumulator_chip.assign_limbs_from_cell_chained(
+                layouter.namespace(|| format!("accumulate leaf balance {}", i)),
+                balance,
+                &accumulate_cells,
+            )?;
+        }
+        Ok(accumulate_cells)
+    }
+
+    // Rebuilds the tree from the leaves, constrains the recomposed total to be equal to the root balance and
+    // exposes the root hash and the total liabilities
+    fn prove_total(
+        merkle_chip: &MerkleSumTreeChip<F>,
+        adjacent_chip: &AdjacentLeavesChip<F>,
+        accumulator_chip: &SafeACcumulatorChip<MAX_BITS, ACC_COLS, F>,
+        mut layouter: impl Layouter<F>,
+        leaves: Vec<(AssignedCell<F, F>, AssignedCell<F, F>)>,
+        accumulate_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+    ) -> Result<(), Error> {
+        let total_liabilities = accumulator_chip.recompose_accumulator(
+            layouter.namespace(|| "recompose liabilities"),
+            accumulate_cells,
+        )?;
+
+        // every pair of sibling nodes is hashed into its parent, until only the root is left
+        let mut nodes = leaves;
+        let mut level = 0;
+        while nodes.len() > 1 {
+            nodes = nodes
+                .chunks(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    adjacent_chip.prove_adjacent_leaves(
+                        layouter.namespace(|| format!("level {} parent {}", level, i)),
+                        &pair[0].0,
+                        &pair[0].1,
+                        &pair[1].0,
+                        &pair[1].1,
+                        &[],
+                        &[],
+                        &[],
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            level += 1;
+        }
+        let (root_hash, root_balance) = &nodes[0];
+
+        // the accumulated liabilities are the ones committed by the root
+        layouter.assign_region(
+            || "total liabilities equal to root balance",
+            |mut region| region.constrain_equal(total_liabilities.cell(), root_balance.cell()),
+        )?;
+
+        merkle_chip.expose_public(layouter.namespace(|| "public root"), root_hash, 0)?;
+        merkle_chip.expose_public(
+            layouter.namespace(|| "public total liabilities"),
+            &total_liabilities,
+            1,
+        )
+    }
+}
+
+impl<F: Field> Circuit<F> for ProofOfLiabilitiesCircuit<F> {
+    type Config = ProofOfLiabilitiesConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+
+        let advice = [(); 5].map(|_| meta.advice_column());
+        let merkle_sum_tree_config = MerkleSumTreeChip::configure(meta, advice, instance);
+
+        let update_value = meta.advice_column();
+        let left_most_inv = meta.advice_column();
+        let add_carries = [(); ACC_COLS].map(|_| meta.advice_column());
+        let accumulate = [(); ACC_COLS].map(|_| meta.advice_column());
+        let selectors = [(); 3].map(|_| meta.selector());
+        let accumulator_config = SafeACcumulatorChip::configure(
+            meta,
+            update_value,
+            left_most_inv,
+            add_carries,
+            accumulate,
+            selectors,
+            instance,
+        );
+
+        // the accumulation starts from zero cells copied from the constant column
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        ProofOfLiabilitiesConfig {
+            merkle_sum_tree_config,
+            accumulator_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.validate()?;
+        if !self.leaf_hashes.len().is_power_of_two() {
+            return Err(Error::Synthesis);
+        }
+
+        let merkle_chip = MerkleSumTreeChip::construct(config.merkle_sum_tree_config.clone());
+        let adjacent_chip = AdjacentLeavesChip::construct(config.merkle_sum_tree_config);
+        let accumulator_chip = SafeACcumulatorChip::construct(config.accumulator_config);
+
+        merkle_chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+        accumulator_chip.load(&mut layouter)?;
+
+        let leaves = self.assign_leaves(&merkle_chip, layouter.namespace(|| "leaves"))?;
+
+        // 1. accumulation of the balance cells of the leaves, starting from zero
+        let zero_cells =
+            accumulator_chip.assign_zero_accumulate(layouter.namespace(|| "zero accumulate"))?;
+        let accumulate_cells = Self::accumulate_balances(
+            &accumulator_chip,
+            layouter.namespace(|| "accumulate leaf balances"),
+            zero_cells,
+            &leaves,
+        )?;
+
+        // 2. and 3. the tree is rebuilt up to the root, whose balance is the accumulated total
+        Self::prove_total(
+            &merkle_chip,
+            &adjacent_chip,
+            &accumulator_chip,
+            layouter.namespace(|| "total liabilities"),
+            leaves,
+            &accumulate_cells,
+        )
+    }
+}
+
+// Accumulates the leaf balances of `circuit`, but the accumulate cells copied into the second update are witnessed
+// as `tampered_accumulate` instead of the output of the first update, as a prover changing an intermediate sum would do
+struct TamperedLiabilitiesCircuit<F: Field> {
+    pub circuit: ProofOfLiabilitiesCircuit<F>,
+    pub tampered_accumulate: [Value<F>; ACC_COLS],
+}
+
+impl<F: Field> Circuit<F> for TamperedLiabilitiesCircuit<F> {
+    type Config = ProofOfLiabilitiesConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.without_witnesses(),
+            tampered_accumulate: [Value::unknown(); ACC_COLS],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        ProofOfLiabilitiesCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let accumulate = config.accumulator_config.accumulate;
+        let merkle_chip = MerkleSumTreeChip::construct(config.merkle_sum_tree_config.clone());
+        let adjacent_chip = AdjacentLeavesChip::construct(config.merkle_sum_tree_config);
+        let accumulator_chip = SafeACcumulatorChip::construct(config.accumulator_config);
+
+        merkle_chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+        accumulator_chip.load(&mut layouter)?;
+
+        let leaves = self
+            .circuit
+            .assign_leaves(&merkle_chip, layouter.namespace(|| "leaves"))?;
+
+        let zero_cells =
+            accumulator_chip.assign_zero_accumulate(layouter.namespace(|| "zero accumulate"))?;
+        let accumulate_cells = ProofOfLiabilitiesCircuit::accumulate_balances(
+            &accumulator_chip,
+            layouter.namespace(|| "accumulate first leaf balance"),
+            zero_cells,
+            &leaves[..1],
+        )?;
+
+        // the tampered cells are still constrained to be equal to the output of the first update
+        let tampered_cells = layouter.assign_region(
+            || "tampered accumulate",
+            |mut region| {
+                let mut cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
+                for (i, cell) in accumulate_cells.iter().enumerate() {
+                    let idx = ACC_COLS - 1 - i;
+                    let tampered = region.assign_advice(
+                        || format!("tampered accumulate[{}]", idx),
+                        accumulate[idx],
+                        0,
+                        || self.tampered_accumulate[idx],
+                    )?;
+                    region.constrain_equal(cell.cell(), tampered.cell())?;
+                    cells.push(tampered);
+                }
+                Ok(cells)
+            },
+        )?;
+
+        let accumulate_cells = ProofOfLiabilitiesCircuit::accumulate_balances(
+            &accumulator_chip,
+            layouter.namespace(|| "accumulate other leaf balances"),
+            tampered_cells,
+            &leaves[1..],
+        )?;
+
+        ProofOfLiabilitiesCircuit::prove_total(
+            &merkle_chip,
+            &adjacent_chip,
+            &accumulator_chip,
+            layouter.namespace(|| "total liabilities"),
+            leaves,
+            &accumulate_cells,
+        )
+    }
+}
+
+// root hash and total liabilities
+impl<F: Field> NumPublic for ProofOfLiabilitiesCircuit<F> {
+    fn num_public(&self) -> usize {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProofOfLiabilitiesCircuit, TamperedLiabilitiesCircuit, ACC_COLS};
+    use crate::circuits::utils::run_checked;
+    use crate::merkle_sum_tree::{total_liabilities, MerkleSumTree, Node};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr as Fp,
+        plonk::Error,
+    };
+
+    // the balance of the leaf i is i * unit
+    fn build_circuit(unit: u64) -> (ProofOfLiabilitiesCircuit<Fp>, Vec<Fp>) {
+        let leaves = (1..=4u64)
+            .map(|i| Node {
+                hash: Fp::from(i),
+                balance: Fp::from(i * unit),
+            })
+            .collect::<Vec<Node>>();
+
+        let public_input = vec![
+            MerkleSumTree::new(leaves.clone()).root().hash,
+            total_liabilities(&leaves),
+        ];
+
+        let circuit = ProofOfLiabilitiesCircuit {
+            leaf_hashes: leaves.iter().map(|leaf| leaf.hash).collect(),
+            leaf_balances: leaves.iter().map(|leaf| leaf.balance).collect(),
+        };
+
+        (circuit, public_input)
+    }
+
+    #[test]
+    fn test_total_liabilities() {
+        // 100 + 200 + 300 + 400 = 1000
+        let (circuit, public_input) = build_circuit(100);
+        assert_eq!(public_input[1], Fp::from(1000u64));
+
+        let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_total_liabilities_wide_balances() {
+        // the leaf balances don't fit into 32 bits, the total is 10 * 10^12
+        let (circuit, public_input) = build_circuit(1_000_000_000_000);
+        assert_eq!(public_input[1], Fp::from(10_000_000_000_000u64));
+
+        let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_wrong_total_liabilities() {
+        // the total is less than the balance of the root
+        let (circuit, mut public_input) = build_circuit(100);
+        public_input[1] = Fp::from(900u64);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn test_leaf_not_in_tree() {
+        // a balance is changed, so the tree doesn't match the public root anymore
+        let (mut circuit, mut public_input) = build_circuit(100);
+        circuit.leaf_balances[2] += Fp::from(1u64);
+        public_input[1] += Fp::from(1u64);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn test_tampered_intermediate_accumulate() {
+        let (circuit, public_input) = build_circuit(100);
+
+        // the first accumulated balance is 100 = 0x64, it is witnessed as 0x63
+        let tampered = |value: u64| {
+            let mut accumulate = [Value::known(Fp::zero()); ACC_COLS];
+            accumulate[ACC_COLS - 1] = Value::known(Fp::from(value));
+            accumulate
+        };
+
+        let tampered_circuit = TamperedLiabilitiesCircuit {
+            circuit,
+            tampered_accumulate: tampered(0x63),
+        };
+        let invalid_prover =
+            MockProver::run(10, &tampered_circuit, vec![public_input.clone()]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+
+        // the same circuit witnessing the actual intermediate accumulate is satisfied
+        let honest_circuit = TamperedLiabilitiesCircuit {
+            tampered_accumulate: tampered(0x64),
+            ..tampered_circuit
+        };
+        let prover = MockProver::run(10, &honest_circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_leaves_not_power_of_two() {
+        let (mut circuit, public_input) = build_circuit(100);
+        circuit.leaf_hashes.pop();
+        circuit.leaf_balances.pop();
+
+        let result = MockProver::run(10, &circuit, vec![public_input]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
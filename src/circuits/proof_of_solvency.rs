@@ -0,0 +1,432 @@
+use super::super::chips::merkle_sum_tree::{MerkleSumTreeChip, MerkleSumTreeConfig};
+use super::super::chips::safe_accumulator::{SafeACcumulatorChip, SafeAccumulatorConfig};
+use super::super::error::{check_path_lengths, ExperimentError};
+use super::utils::NumPublic;
+use arrayvec::ArrayVec;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+// 8 bits for each accumulate column and 9 columns, the left most one is the overflow column,
+// so the liabilities sum must fit into 64 bits like the sums of the merkle sum tree.
+// Every balance is decomposed into limbs of 8 bits before being accumulated
+const MAX_BITS: u8 = 8;
+const ACC_COLS: usize = 9;
+
+#[derive(Debug, Clone)]
+pub struct ProofOfSolvencyConfig<F: Field> {
+    merkle_sum_tree_config: MerkleSumTreeConfig<F>,
+    accumulator_config: SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F>,
+}
+
+// End-to-end circuit chaining the existing chips:
+// 1. the user leaf is included in the merkle sum tree (`MerkleSumTreeChip`)
+// 2. the balances committed by the root, namely the leaf balance and the balances of the path elements, are copied
+//    into the overflow protected `SafeACcumulatorChip`, which decomposes each of them into limbs of MAX_BITS bits.
+//    The accumulation starts from zero cells of the constant column and every update copies the accumulate cells
+//    of the previous one, the total is constrained to be equal to the balance of the root
+// 3. the total liabilities are less than the assets (`LtChip` inside `MerkleSumTreeChip::enforce_less_than`)
+// The public inputs are the leaf hash (row 0), the leaf balance (row 1), the root hash (row 2) and the assets sum (row 3)
+#[derive(Default)]
+pub struct ProofOfSolvencyCircuit<F: Field> {
+    pub leaf_hash: F,
+    pub leaf_balance: F,
+    pub path_element_hashes: Vec<F>,
+    pub path_element_balances: Vec<F>,
+    pub path_indices: Vec<F>,
+    pub assets_sum: F,
+}
+
+impl<F: Field> ProofOfSolvencyCircuit<F> {
+    pub fn validate(&self) -> Result<(), ExperimentError> {
+        check_path_lengths(
+            self.path_element_hashes.len(),
+            &[
+                ("path_element_balances", self.path_element_balances.len()),
+                ("path_indices", self.path_indices.len()),
+            ],
+        )
+    }
+
+    // Proves the inclusion of the user leaf and exposes the leaf hash, the leaf balance and the root hash.
+    // Returns the balance cell of the root and the balance cells it commits to: the leaf balance followed by
+    // the balance of the path element of every level
+    fn prove_inclusion(
+        &self,
+        merkle_chip: &MerkleSumTreeChip<F>,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        let (leaf_hash, leaf_balance) = merkle_chip.assing_leaf_hash_and_balance(
+            layouter.namespace(|| "assign leaf"),
+            self.leaf_hash,
+            self.leaf_balance,
+        )?;
+
+        merkle_chip.expose_public(layouter.namespace(|| "public leaf hash"), &leaf_hash, 0)?;
+        merkle_chip.expose_public(
+            layouter.namespace(|| "public leaf balance"),
+            &leaf_balance,
+            1,
+        )?;
+
+        let mut balances = vec![leaf_balance.clone()];
+        let (mut root_hash, mut root_balance) = (leaf_hash, leaf_balance);
+        for i in 0..self.path_element_hashes.len() {
+            let element_balance;
+            (root_hash, root_balance, element_balance) = merkle_chip
+                .merkle_prove_layer_with_element_balance(
+                    layouter.namespace(|| format!("level {} merkle proof", i)),
+                    i,
+                    &root_hash,
+                    &root_balance,
+                    self.path_element_hashes[i],
+                    self.path_element_balances[i],
+                    self.path_indices[i],
+                )?;
+            balances.push(element_balance);
+        }
+
+        merkle_chip.expose_public(layouter.namespace(|| "public root"), &root_hash, 2)?;
+
+        Ok((root_balance, balances))
+    }
+
+    // Copies the balance cells into the accumulator, every update decomposing the balance into limbs and copying
+    // the accumulate cells of the previous one. Returns the updated accumulate cells
+    fn accumulate_balances(
+        accumulator_chip: &SafeACcumulatorChip<MAX_BITS, ACC_COLS, F>,
+        mut layouter: impl Layouter<F>,
+        mut accumulate_cells: ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+        balances: &[AssignedCell<F, F>],
+    ) -> Result<ArrayVec<AssignedCell<F, F>, ACC_COLS>, Error> {
+        for (i, balance) in balances.iter().enumerate() {
+            accumulate_cells = accumulator_chip.assign_limbs_from_cell_chained(
+                layouter.namespace(|| format!("accumulate balance {}", i)),
+                balance,
+                &accumulate_cells,
+            )?;
+        }
+        Ok(accumulate_cells)
+    }
+
+    // Recomposes the total liabilities, constrains it to be equal to the root balance and to be less than
+    // the assets sum at row 3 of the instance column
+    fn enforce_solvency(
+        &self,
+        merkle_chip: &MerkleSumTreeChip<F>,
+        accumulator_chip: &SafeACcumulatorChip<MAX_BITS, ACC_COLS, F>,
+        mut layouter: impl Layouter<F>,
+        accumulate_cells: &ArrayVec<AssignedCell<F, F>, ACC_COLS>,
+        root_balance: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let total_liabilities = accumulator_chip.recompose_accumulator(
+            layouter.namespace(|| "recompose liabilities"),
+            accumulate_cells,
+        )?;
+
+        // the accumulated liabilities are the ones committed by the root
+        layouter.assign_region(
+            || "total liabilities equal to root balance",
+            |mut region| region.constrain_equal(total_liabilities.cell(), root_balance.cell()),
+        )?;
+
+        let mut computed_total = F::zero();
+        total_liabilities.value().map(|v| computed_total = *v);
+        merkle_chip.enforce_less_than(
+            layouter.namespace(|| "enforce less than"),
+            &total_liabilities,
+            computed_total,
+            self.assets_sum,
+        )
+    }
+}
+
+impl<F: Field> Circuit<F> for ProofOfSolvencyCircuit<F> {
+    type Config = ProofOfSolvencyConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+
+        let advice = [(); 5].map(|_| meta.advice_column());
+        let merkle_sum_tree_config = MerkleSumTreeChip::configure(meta, advice, instance);
+
+        let update_value = meta.advice_column();
+        let left_most_inv = meta.advice_column();
+        let add_carries = [(); ACC_COLS].map(|_| meta.advice_column());
+        let accumulate = [(); ACC_COLS].map(|_| meta.advice_column());
+        let selectors = [(); 3].map(|_| meta.selector());
+        let accumulator_config = SafeACcumulatorChip::configure(
+            meta,
+            update_value,
+            left_most_inv,
+            add_carries,
+            accumulate,
+            selectors,
+            instance,
+        );
+
+        // the accumulation starts from zero cells copied from the constant column
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        ProofOfSolvencyConfig {
+            merkle_sum_tree_config,
+            accumulator_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.validate()?;
+
+        let merkle_chip = MerkleSumTreeChip::construct(config.merkle_sum_tree_config);
+        let accumulator_chip = SafeACcumulatorChip::construct(config.accumulator_config);
+
+        merkle_chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+        accumulator_chip.load(&mut layouter)?;
+
+        // 1. inclusion of the user leaf
+        let (root_balance, balances) =
+            self.prove_inclusion(&merkle_chip, layouter.namespace(|| "inclusion"))?;
+
+        // 2. accumulation of the balances committed by the root, starting from zero
+        let zero_cells =
+            accumulator_chip.assign_zero_accumulate(layouter.namespace(|| "zero accumulate"))?;
+        let accumulate_cells = Self::accumulate_balances(
+            &accumulator_chip,
+            layouter.namespace(|| "accumulate balances"),
+            zero_cells,
+            &balances,
+        )?;
+
+        // 3. liabilities are equal to the root balance and less than the assets sum
+        self.enforce_solvency(
+            &merkle_chip,
+            &accumulator_chip,
+            layouter.namespace(|| "solvency"),
+            &accumulate_cells,
+            &root_balance,
+        )
+    }
+}
+
+// Accumulates the balances of `circuit`, but the accumulate cells copied into the second update are witnessed as
+// `tampered_accumulate` instead of the output of the first update, as a prover changing an intermediate sum would do
+struct TamperedSolvencyCircuit<F: Field> {
+    pub circuit: ProofOfSolvencyCircuit<F>,
+    pub tampered_accumulate: [Value<F>; ACC_COLS],
+}
+
+impl<F: Field> Circuit<F> for TamperedSolvencyCircuit<F> {
+    type Config = ProofOfSolvencyConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.without_witnesses(),
+            tampered_accumulate: [Value::unknown(); ACC_COLS],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        ProofOfSolvencyCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let accumulate = config.accumulator_config.accumulate;
+        let merkle_chip = MerkleSumTreeChip::construct(config.merkle_sum_tree_config);
+        let accumulator_chip = SafeACcumulatorChip::construct(config.accumulator_config);
+
+        merkle_chip.load_range_check_table(layouter.namespace(|| "load range check table"))?;
+        accumulator_chip.load(&mut layouter)?;
+
+        let (root_balance, balances) = self
+            .circuit
+            .prove_inclusion(&merkle_chip, layouter.namespace(|| "inclusion"))?;
+
+        let zero_cells =
+            accumulator_chip.assign_zero_accumulate(layouter.namespace(|| "zero accumulate"))?;
+        let accumulate_cells = ProofOfSolvencyCircuit::accumulate_balances(
+            &accumulator_chip,
+            layouter.namespace(|| "accumulate first balance"),
+            zero_cells,
+            &balances[..1],
+        )?;
+
+        // the tampered cells are still constrained to be equal to the output of the first update
+        let tampered_cells = layouter.assign_region(
+            || "tampered accumulate",
+            |mut region| {
+                let mut cells: ArrayVec<AssignedCell<F, F>, ACC_COLS> = ArrayVec::new();
+                for (i, cell) in accumulate_cells.iter().enumerate() {
+                    let idx = ACC_COLS - 1 - i;
+                    let tampered = region.assign_advice(
+                        || format!("tampered accumulate[{}]", idx),
+                        accumulate[idx],
+                        0,
+                        || self.tampered_accumulate[idx],
+                    )?;
+                    region.constrain_equal(cell.cell(), tampered.cell())?;
+                    cells.push(tampered);
+                }
+                Ok(cells)
+            },
+        )?;
+
+        let accumulate_cells = ProofOfSolvencyCircuit::accumulate_balances(
+            &accumulator_chip,
+            layouter.namespace(|| "accumulate other balances"),
+            tampered_cells,
+            &balances[1..],
+        )?;
+
+        self.circuit.enforce_solvency(
+            &merkle_chip,
+            &accumulator_chip,
+            layouter.namespace(|| "solvency"),
+            &accumulate_cells,
+            &root_balance,
+        )
+    }
+}
+
+// leaf hash, leaf balance, root hash and assets sum
+impl<F: Field> NumPublic for ProofOfSolvencyCircuit<F> {
+    fn num_public(&self) -> usize {
+        4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProofOfSolvencyCircuit, TamperedSolvencyCircuit, ACC_COLS};
+    use crate::circuits::utils::run_checked;
+    use crate::merkle_sum_tree::{MerkleSumTree, Node};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr as Fp,
+    };
+
+    // the balance of the leaf i is i * unit
+    fn build_circuit(unit: u64, assets_sum: Fp) -> (ProofOfSolvencyCircuit<Fp>, Vec<Fp>) {
+        let leaves = (1..=8u64)
+            .map(|i| Node {
+                hash: Fp::from(i),
+                balance: Fp::from(i * unit),
+            })
+            .collect::<Vec<Node>>();
+
+        let tree = MerkleSumTree::new(leaves);
+        let proof = tree.generate_proof(3);
+
+        let public_input = vec![
+            proof.leaf.hash,
+            proof.leaf.balance,
+            proof.root.hash,
+            assets_sum,
+        ];
+
+        let circuit = ProofOfSolvencyCircuit {
+            leaf_hash: proof.leaf.hash,
+            leaf_balance: proof.leaf.balance,
+            path_element_hashes: proof.path_elements.iter().map(|node| node.hash).collect(),
+            path_element_balances: proof
+                .path_elements
+                .iter()
+                .map(|node| node.balance)
+                .collect(),
+            path_indices: proof.path_indices,
+            assets_sum,
+        };
+
+        (circuit, public_input)
+    }
+
+    #[test]
+    fn test_solvent() {
+        // liabilities sum is 100 + 200 + ... + 800 = 3600
+        let (circuit, public_input) = build_circuit(100, Fp::from(4000u64));
+
+        let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_solvent_wide_balances() {
+        // the balances don't fit into 32 bits, liabilities sum is 36 * 10^12
+        let (circuit, public_input) =
+            build_circuit(1_000_000_000_000, Fp::from(40_000_000_000_000u64));
+
+        let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_insolvent() {
+        // assets are less than the liabilities sum (3600)
+        let (circuit, public_input) = build_circuit(100, Fp::from(3000u64));
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| failure.to_string().contains("is_lt from LtChip")));
+    }
+
+    #[test]
+    fn test_path_balance_not_in_tree() {
+        // a path element balance is changed, so the path doesn't lead to the public root anymore
+        let (mut circuit, public_input) = build_circuit(100, Fp::from(4000u64));
+        circuit.path_element_balances[1] += Fp::from(1u64);
+
+        let invalid_prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn test_tampered_intermediate_accumulate() {
+        let (circuit, public_input) = build_circuit(100, Fp::from(4000u64));
+
+        // the first accumulated balance is the leaf balance 400 = 0x190, it is witnessed as 0x100
+        let tampered = |low: u64| {
+            let mut accumulate = [Value::known(Fp::zero()); ACC_COLS];
+            accumulate[ACC_COLS - 2] = Value::known(Fp::from(1u64));
+            accumulate[ACC_COLS - 1] = Value::known(Fp::from(low));
+            accumulate
+        };
+
+        let tampered_circuit = TamperedSolvencyCircuit {
+            circuit,
+            tampered_accumulate: tampered(0x00),
+        };
+        let invalid_prover =
+            MockProver::run(10, &tampered_circuit, vec![public_input.clone()]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+
+        // the same circuit witnessing the actual intermediate accumulate is satisfied
+        let honest_circuit = TamperedSolvencyCircuit {
+            tampered_accumulate: tampered(0x90),
+            ..tampered_circuit
+        };
+        let prover = MockProver::run(10, &honest_circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+}
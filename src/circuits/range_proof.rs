@@ -0,0 +1,108 @@
+use super::super::chips::range_proof::{RangeProofChip, RangeProofConfig};
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+use std::marker::PhantomData;
+
+#[derive(Default)]
+// the value stays private, while min and max are exposed as public inputs
+pub struct RangeProofCircuit<F> {
+    pub value: u64,
+    pub min: u64,
+    pub max: u64,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> RangeProofCircuit<F> {
+    pub fn new(value: u64, min: u64, max: u64) -> Self {
+        Self {
+            value,
+            min,
+            max,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Circuit<F> for RangeProofCircuit<F> {
+    type Config = RangeProofConfig<F, 8>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let min = meta.advice_column();
+        let max = meta.advice_column();
+        let instance = meta.instance_column();
+
+        RangeProofChip::configure(meta, value, min, max, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = RangeProofChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let (_, min_cell, max_cell) = chip.assign(
+            layouter.namespace(|| "range proof"),
+            F::from(self.value),
+            F::from(self.min),
+            F::from(self.max),
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "public min"), &min_cell, 0)?;
+        chip.expose_public(layouter.namespace(|| "public max"), &max_cell, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeProofCircuit;
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as Fp};
+
+    fn instantiate_circuit(value: u64, min: u64, max: u64) -> RangeProofCircuit<Fp> {
+        RangeProofCircuit::new(value, min, max)
+    }
+
+    #[test]
+    fn test_value_in_range() {
+        let k = 9;
+
+        let public_input = vec![Fp::from(100), Fp::from(200)];
+
+        // bounds are inclusive
+        for value in [100, 150, 200] {
+            let circuit = instantiate_circuit(value, 100, 200);
+            let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_value_below_min() {
+        let k = 9;
+
+        let circuit = instantiate_circuit(99, 100, 200);
+        let public_input = vec![Fp::from(100), Fp::from(200)];
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_value_above_max() {
+        let k = 9;
+
+        let circuit = instantiate_circuit(201, 100, 200);
+        let public_input = vec![Fp::from(100), Fp::from(200)];
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+}
@@ -2,49 +2,59 @@ use arrayvec::ArrayVec;
 use eth_types::Field;
 use halo2_proofs::{circuit::*, plonk::*};
 
+use super::super::chips::is_zero::IsZeroChip;
 use super::super::chips::safe_accumulator::{SafeACcumulatorChip, SafeAccumulatorConfig};
 
-#[derive(Default)]
-struct SafeAccumulatorCircuit<F: Field> {
+// MAX_BITS bits for each accumulate column and ACC_COLS columns
+// The accumulate cells are exposed followed by the recomposed accumulator, or only the recomposed accumulator
+// at row 0 if `packed` is set, e.g. for a contract reading a single uint. It requires MAX_BITS * ACC_COLS <= 128
+pub struct SafeAccumulatorCircuit<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> {
     pub values: Vec<Value<F>>,
-    pub accumulated_value: [Value<F>; 4],
+    pub accumulated_value: [Value<F>; ACC_COLS],
+    pub packed: bool,
+}
+
+impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> Default
+    for SafeAccumulatorCircuit<MAX_BITS, ACC_COLS, F>
+{
+    fn default() -> Self {
+        Self {
+            values: vec![],
+            accumulated_value: [Value::unknown(); ACC_COLS],
+            packed: false,
+        }
+    }
 }
 
-impl<F: Field> Circuit<F> for SafeAccumulatorCircuit<F> {
-    type Config = SafeAccumulatorConfig<4, 4, F>; // 4 bits for each column and 4 columns
+impl<const MAX_BITS: u8, const ACC_COLS: usize, F: Field> Circuit<F>
+    for SafeAccumulatorCircuit<MAX_BITS, ACC_COLS, F>
+{
+    type Config = SafeAccumulatorConfig<MAX_BITS, ACC_COLS, F>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self {
+            packed: self.packed,
+            ..Self::default()
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let new_value = meta.advice_column();
         let left_most_acc_inv = meta.advice_column();
-        let carry_cols = [
-            meta.advice_column(),
-            meta.advice_column(),
-            meta.advice_column(),
-            meta.advice_column(),
-        ];
-        let acc_cols = [
-            meta.advice_column(),
-            meta.advice_column(),
-            meta.advice_column(),
-            meta.advice_column(),
-        ];
-        let add_selector = meta.selector();
-        let overflow_selector = meta.selector();
-        let boolean_selector = meta.selector();
+        let carry_cols = [(); ACC_COLS].map(|_| meta.advice_column());
+        let acc_cols = [(); ACC_COLS].map(|_| meta.advice_column());
+        // boolean, add and overflow selectors
+        let selectors = [(); 3].map(|_| meta.selector());
         let instance = meta.instance_column();
 
-        SafeACcumulatorChip::<4, 4, F>::configure(
+        SafeACcumulatorChip::<MAX_BITS, ACC_COLS, F>::configure(
             meta,
             new_value,
             left_most_acc_inv,
             carry_cols,
             acc_cols,
-            [boolean_selector, add_selector, overflow_selector],
+            selectors,
             instance,
         )
     }
@@ -56,6 +66,8 @@ impl<F: Field> Circuit<F> for SafeAccumulatorCircuit<F> {
     ) -> Result<(), Error> {
         let chip = SafeACcumulatorChip::construct(config);
 
+        chip.load(&mut layouter)?;
+
         let (mut assigned_cells, mut previous_accumulates) = chip
             .assign(
                 layouter.namespace(|| "initial rows"),
@@ -68,7 +80,7 @@ impl<F: Field> Circuit<F> for SafeAccumulatorCircuit<F> {
         // Actually, there is no need to multiple values for a single user.
         // It may need multiple values who has multiple accounts in same identity
         // so, I just keep this code for now.
-        let mut latest_accumulates: [Value<F>; 4];
+        let mut latest_accumulates: [Value<F>; ACC_COLS];
         for (i, v) in self.values.iter().skip(1).enumerate() {
             (assigned_cells, latest_accumulates) = chip
                 .assign(
@@ -81,19 +93,169 @@ impl<F: Field> Circuit<F> for SafeAccumulatorCircuit<F> {
             previous_accumulates = latest_accumulates;
         }
 
+        let total =
+            chip.recompose_accumulator(layouter.namespace(|| "recompose"), &assigned_cells)?;
+
+        if self.packed {
+            assert!(
+                MAX_BITS as usize * ACC_COLS <= 128,
+                "the packed accumulator of {} bits doesn't fit into a u128",
+                MAX_BITS as usize * ACC_COLS
+            );
+            return chip.expose_public(layouter.namespace(|| "packed total"), &total, 0);
+        }
+
         // check assigned cells values are correct with instance
         for (i, cell) in assigned_cells.iter().rev().enumerate() {
             chip.expose_public(layouter.namespace(|| format!("accumulate_{}", i)), cell, i)?;
         }
 
+        // the recomposed accumulator is exposed after the accumulate columns
+        chip.expose_public(layouter.namespace(|| "total"), &total, assigned_cells.len())?;
+
+        Ok(())
+    }
+}
+
+// Adds a single value and exposes the carry cells, little-endian, instead of the accumulate cells
+#[derive(Default)]
+struct CarriesCircuit<F: Field> {
+    pub value: Value<F>,
+    pub accumulated_value: [Value<F>; 4],
+}
+
+impl<F: Field> Circuit<F> for CarriesCircuit<F> {
+    type Config = SafeAccumulatorConfig<4, 4, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SafeAccumulatorCircuit::<4, 4, F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SafeACcumulatorChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let (_, carry_cells, _) = chip.assign_with_carries(
+            layouter.namespace(|| "accumulate"),
+            0,
+            self.value,
+            self.accumulated_value,
+        )?;
+
+        for (i, cell) in carry_cells.iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| format!("carry_{}", i)), cell, i)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Adds 1 to a zero accumulator, but witnesses 2 as the updated accumulator. The carries are field elements
+// chosen so that the accumulation constraint is satisfied, c_3 = -1/16, c_2 = -1/256, ... so only the bool
+// constraint on the carries rejects the witness
+#[derive(Default)]
+struct ForgedCarriesCircuit<F: Field> {
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: Field> Circuit<F> for ForgedCarriesCircuit<F> {
+    type Config = SafeAccumulatorConfig<4, 4, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SafeAccumulatorCircuit::<4, 4, F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SafeACcumulatorChip::construct(config.clone());
+
+        chip.load(&mut layouter)?;
+
+        let shift_inv = F::from(1 << 4).invert().unwrap();
+        let forged_total = [0u64, 0, 0, 2];
+
+        let accumulate_cells = layouter.assign_region(
+            || "forged accumulates",
+            |mut region| {
+                config.selector[0].enable(&mut region, 1)?;
+                config.selector[1].enable(&mut region, 1)?;
+                config.bool_selector.enable(&mut region, 1)?;
+                config.range_selector.enable(&mut region, 1)?;
+
+                region.assign_advice(
+                    || "value",
+                    config.update_value,
+                    1,
+                    || Value::known(F::one()),
+                )?;
+
+                let mut cells = vec![];
+                for i in 0..4 {
+                    region.assign_advice(
+                        || "previous accumulate",
+                        config.accumulate[i],
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                    region.assign_advice(
+                        || "forged carry",
+                        config.add_carries[i],
+                        1,
+                        || Value::known(-shift_inv.pow_vartime([4 - i as u64])),
+                    )?;
+                    cells.push(region.assign_advice(
+                        || "forged accumulate",
+                        config.accumulate[i],
+                        1,
+                        || Value::known(F::from(forged_total[i])),
+                    )?);
+                }
+
+                IsZeroChip::construct(config.is_zero.clone()).assign(
+                    &mut region,
+                    1,
+                    Value::known(F::zero()),
+                )?;
+
+                Ok(cells)
+            },
+        )?;
+
+        for (i, cell) in accumulate_cells.iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| format!("accumulate_{}", i)), cell, i)?;
+        }
+
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::SafeAccumulatorCircuit;
-    use halo2_proofs::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr as Fp};
+    use super::{CarriesCircuit, ForgedCarriesCircuit, SafeAccumulatorCircuit};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::{CellValue, MockProver, VerifyFailure},
+        halo2curves::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem},
+    };
 
     #[test]
     fn test_none_overflow_case() {
@@ -112,11 +274,13 @@ mod tests {
             Fp::from(0),
             Fp::from((1 << 4) - 1), // 0xf
             Fp::from(1),            // 0x1
+            Fp::from(0xf1),         // recomposed total
         ];
 
-        let circuit = SafeAccumulatorCircuit::<Fp> {
+        let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
             values,
             accumulated_value,
+            packed: false,
         };
         let prover = MockProver::run(k, &circuit, vec![result_accumulated]).unwrap();
         prover.assert_satisfied();
@@ -139,11 +303,13 @@ mod tests {
             Fp::from(0),
             Fp::from((1 << 4) - 1), // 0xf
             Fp::from(1),            // 0x1
+            Fp::from(0xf1),         // recomposed total
         ];
 
-        let circuit = SafeAccumulatorCircuit {
+        let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
             values,
             accumulated_value,
+            packed: false,
         };
         let prover = MockProver::run(k, &circuit, vec![result_accumulated]).unwrap();
         prover.assert_satisfied();
@@ -161,9 +327,10 @@ mod tests {
             Value::known(Fp::from((1 << 4) - 3)), // 0xd
         ];
 
-        let circuit = SafeAccumulatorCircuit {
+        let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
             values,
             accumulated_value,
+            packed: false,
         };
         let invalid_prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         assert!(invalid_prover.verify().is_err());
@@ -181,11 +348,339 @@ mod tests {
             Value::known(Fp::from((1 << 4) - 1)), // 0xf
         ];
 
-        let circuit = SafeAccumulatorCircuit {
+        let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
             values: invalid_values,
             accumulated_value,
+            packed: false,
         };
         let invalid_prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
         assert!(invalid_prover.verify().is_err());
     }
+
+    #[test]
+    fn test_against_reference_accumulator() {
+        use crate::test_support::accumulate_reference;
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let k = 8;
+
+        for seed in 0..10u64 {
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            // keep the left most column empty, otherwise the initial accumulator is already overflowed
+            let mut init = [0u64; 4];
+            for limb in init.iter_mut().skip(1) {
+                *limb = rng.gen_range(0..1 << 4);
+            }
+            let values: Vec<u64> = (0..rng.gen_range(1..4))
+                .map(|_| rng.gen_range(0..1 << 4))
+                .collect();
+
+            let (expected, overflow) = accumulate_reference(&values, init, 4);
+
+            let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
+                values: values.iter().map(|v| Value::known(Fp::from(*v))).collect(),
+                accumulated_value: init.map(|v| Value::known(Fp::from(v))),
+                packed: false,
+            };
+            let total = expected.iter().fold(0u64, |acc, v| (acc << 4) + v);
+            let mut public_input = expected.iter().map(|v| Fp::from(*v)).collect::<Vec<Fp>>();
+            public_input.push(Fp::from(total));
+
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            if overflow {
+                assert!(prover.verify().is_err(), "seed {} should overflow", seed);
+            } else {
+                assert_eq!(prover.verify(), Ok(()), "seed {} should not overflow", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_witnesses() {
+        use crate::test_support::{
+            random_accumulator_witness, random_overflow_accumulator_witness,
+        };
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let k = 8;
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let build = |values: &[u64], init: [u64; 4], expected: [u64; 4]| {
+            let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
+                values: values.iter().map(|v| Value::known(Fp::from(*v))).collect(),
+                accumulated_value: init.map(|v| Value::known(Fp::from(v))),
+                packed: false,
+            };
+            let total = expected.iter().fold(0u64, |acc, v| (acc << 4) + v);
+            let mut public_input = expected.iter().map(|v| Fp::from(*v)).collect::<Vec<Fp>>();
+            public_input.push(Fp::from(total));
+            (circuit, public_input)
+        };
+
+        for i in 0..100 {
+            let (values, init, expected) = random_accumulator_witness(&mut rng, 4);
+            let (circuit, public_input) = build(&values, init, expected);
+
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            assert_eq!(
+                prover.verify(),
+                Ok(()),
+                "case {}: {:?} added to {:?} should not overflow",
+                i,
+                values,
+                init
+            );
+        }
+
+        for i in 0..100 {
+            let (values, init, expected) = random_overflow_accumulator_witness(&mut rng, 4);
+            let (circuit, public_input) = build(&values, init, expected);
+
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            assert!(
+                prover.verify().is_err(),
+                "case {}: {:?} added to {:?} should overflow",
+                i,
+                values,
+                init
+            );
+        }
+    }
+
+    #[test]
+    fn test_recomposed_accumulator() {
+        let k = 8;
+
+        let values = vec![Value::known(Fp::from(4))];
+        let accumulated_value = [
+            Value::known(Fp::from(0)),
+            Value::known(Fp::from(0x3)),
+            Value::known(Fp::from(0xe)),
+            Value::known(Fp::from(0xd)),
+        ];
+
+        let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
+            values,
+            accumulated_value,
+            packed: false,
+        };
+
+        // 0x3ed + 0x4 = 0x3f1
+        let public_input = vec![
+            Fp::from(0),
+            Fp::from(0x3),
+            Fp::from(0xf),
+            Fp::from(0x1),
+            Fp::from(0x3f1),
+        ];
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // a wrong total fails
+        let mut invalid_input = public_input;
+        invalid_input[4] = Fp::from(0x3f2);
+        let invalid_prover = MockProver::run(k, &circuit, vec![invalid_input]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_range_check_lookup() {
+        use crate::test_support::accumulate_reference;
+
+        let k = 8;
+
+        // the lookup accepts exactly the values the polynomial range check accepted, 0 to 2^MAX_BITS - 1
+        for value in 0..(1 << 5) {
+            let init = [0u64; 4];
+            let (expected, overflow) = accumulate_reference(&[value], init, 4);
+
+            let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
+                values: vec![Value::known(Fp::from(value))],
+                accumulated_value: init.map(|v| Value::known(Fp::from(v))),
+                packed: false,
+            };
+            let mut public_input = expected.iter().map(|v| Fp::from(*v)).collect::<Vec<Fp>>();
+            public_input.push(Fp::from(value));
+
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            assert_eq!(overflow, value >= 1 << 4);
+            if overflow {
+                assert!(prover.verify().is_err(), "{} should be out of range", value);
+            } else {
+                assert_eq!(prover.verify(), Ok(()), "{} should be in range", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_constraint_degree() {
+        // with the polynomial range check the degree was 2^MAX_BITS + 1 = 17
+        let mut meta = ConstraintSystem::<Fp>::default();
+        SafeAccumulatorCircuit::<4, 4, Fp>::configure(&mut meta);
+        assert!(meta.degree() <= 5);
+    }
+
+    #[test]
+    fn test_gate_report() {
+        use crate::circuits::utils::gate_report;
+
+        let circuit = SafeAccumulatorCircuit::<4, 4, Fp> {
+            values: vec![Value::known(Fp::from(1))],
+            accumulated_value: [Value::known(Fp::zero()); 4],
+            packed: false,
+        };
+
+        // the range checks are lookups, so no gate reaches the degree of the former polynomial range check.
+        // The overflow check multiplies the selector by the is_zero expression of degree 2
+        assert_eq!(
+            gate_report(&circuit),
+            vec![
+                ("is_zero".to_string(), 4),
+                ("bool constraint".to_string(), 3),
+                ("accumulation constraint".to_string(), 3),
+                ("recompose constraint".to_string(), 2),
+                ("limb accumulation constraint".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_64_bits_accumulator() {
+        // the range table has 2^8 rows
+        let k = 9;
+
+        // 8 bits for each column and 8 columns, the left most column is the overflow column
+        let initial: u64 = 0x00fe_dcba_9876_54f0;
+        let accumulated_value = (0..8)
+            .rev()
+            .map(|i| Value::known(Fp::from((initial >> (8 * i)) & 0xff)))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        let circuit = SafeAccumulatorCircuit::<8, 8, Fp> {
+            values: vec![Value::known(Fp::from(0x20))],
+            accumulated_value,
+            packed: false,
+        };
+
+        // 0x00fedcba987654f0 + 0x20 = 0x00fedcba98765510, the carry propagates over two columns
+        let total: u64 = 0x00fe_dcba_9876_5510;
+        let mut public_input = (0..8)
+            .rev()
+            .map(|i| Fp::from((total >> (8 * i)) & 0xff))
+            .collect::<Vec<Fp>>();
+        public_input.push(Fp::from(total));
+
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // a carry into the left most column is an overflow
+        let overflow_circuit = SafeAccumulatorCircuit::<8, 8, Fp> {
+            values: vec![Value::known(Fp::from(0xff))],
+            accumulated_value: [0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+                .map(|v| Value::known(Fp::from(v))),
+            packed: false,
+        };
+
+        // 0x00ffffffffffffff + 0xff = 0x01000000000000fe, the public input is the actual sum,
+        // so only the overflow check rejects it
+        let total: u64 = 0x0100_0000_0000_00fe;
+        let mut public_input = (0..8)
+            .rev()
+            .map(|i| Fp::from((total >> (8 * i)) & 0xff))
+            .collect::<Vec<Fp>>();
+        public_input.push(Fp::from(total));
+
+        let invalid_prover = MockProver::run(k, &overflow_circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::ConstraintNotSatisfied { .. })));
+    }
+
+    #[test]
+    fn test_packed_accumulator() {
+        use halo2_proofs::arithmetic::FieldExt;
+
+        let k = 9;
+
+        let init = [0u64, 0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0xf0];
+        let circuit = SafeAccumulatorCircuit::<8, 8, Fp> {
+            values: vec![Value::known(Fp::from(0x20))],
+            accumulated_value: init.map(|v| Value::known(Fp::from(v))),
+            packed: true,
+        };
+
+        // the single public input is the whole accumulator as a u128
+        let total = init.iter().fold(0u128, |acc, v| (acc << 8) + *v as u128) + 0x20;
+        assert_eq!(total, 0x00fe_dcba_9876_5510);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from_u128(total)]]).unwrap();
+        prover.assert_satisfied();
+
+        // a tampered packed value fails
+        let invalid_prover =
+            MockProver::run(k, &circuit, vec![vec![Fp::from_u128(total + 1)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_carry_cells() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let k = 8;
+
+        // 0x0ed + 0x4 = 0x0f1, the carry is only in the right most column
+        let circuit = CarriesCircuit::<Fp> {
+            value: Value::known(Fp::from(4)),
+            accumulated_value: [0u64, 0, 0xe, 0xd].map(|v| Value::known(Fp::from(v))),
+        };
+        let carries = vec![Fp::from(1), Fp::from(0), Fp::from(0), Fp::from(0)];
+        let prover = MockProver::run(k, &circuit, vec![carries]).unwrap();
+        prover.assert_satisfied();
+
+        // every carry cell is either 0 or 1
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let config = CarriesCircuit::<Fp>::configure(&mut meta);
+
+        for seed in 0..10u64 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let circuit = CarriesCircuit::<Fp> {
+                value: Value::known(Fp::from(rng.gen_range(0..1u64 << 4))),
+                accumulated_value: [0; 4]
+                    .map(|_| Value::known(Fp::from(rng.gen_range(0..1u64 << 4)))),
+            };
+
+            let prover = MockProver::run(k, &circuit, vec![vec![Fp::zero(); 4]]).unwrap();
+            for column in config.add_carries {
+                for cell in prover.advice_values(column) {
+                    if let CellValue::Assigned(carry) = cell {
+                        assert!(*carry == Fp::zero() || *carry == Fp::one());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_forged_carries() {
+        let k = 8;
+
+        // 0 + 1 is witnessed as 2 with carries that are neither 0 nor 1
+        let circuit = ForgedCarriesCircuit::<Fp>::default();
+        let public_input = vec![Fp::from(0), Fp::from(0), Fp::from(0), Fp::from(2)];
+
+        let invalid_prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert_eq!(failures.len(), 4);
+        for failure in failures {
+            match failure {
+                VerifyFailure::ConstraintNotSatisfied { constraint, .. } => {
+                    assert!(constraint.to_string().contains("bool constraint"))
+                }
+                _ => panic!("unexpected failure {:?}", failure),
+            }
+        }
+    }
 }
@@ -0,0 +1,217 @@
+use arrayvec::ArrayVec;
+use eth_types::Field;
+use halo2_proofs::{circuit::*, plonk::*};
+
+use super::super::chips::signed_accumulator::{SignedAccumulatorChip, SignedAccumulatorConfig};
+
+#[derive(Default)]
+pub struct SignedAccumulatorCircuit<F: Field> {
+    pub values: Vec<Value<F>>,
+}
+
+impl<F: Field> Circuit<F> for SignedAccumulatorCircuit<F> {
+    type Config = SignedAccumulatorConfig<4, 2, F>; // 4 bits for each column and 2 columns, so each sum is below 2^4
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let constant = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        SignedAccumulatorChip::configure(meta, advice, constant, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SignedAccumulatorChip::construct(config);
+
+        chip.load(&mut layouter)?;
+
+        let balance = chip.accumulate(layouter.namespace(|| "accumulate"), &self.values)?;
+
+        chip.expose_public(layouter.namespace(|| "balance"), &balance, 0)?;
+
+        Ok(())
+    }
+}
+
+// Accumulates two values, but the positive accumulate cells copied into the second step are witnessed as
+// `tampered_positive` instead of the output of the first step, as a prover changing an intermediate sum would do
+#[derive(Default)]
+struct TamperedSignedAccumulatorCircuit<F: Field> {
+    pub values: [Value<F>; 2],
+    pub tampered_positive: [Value<F>; 2],
+}
+
+impl<F: Field> Circuit<F> for TamperedSignedAccumulatorCircuit<F> {
+    type Config = SignedAccumulatorConfig<4, 2, F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SignedAccumulatorCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SignedAccumulatorChip::construct(config.clone());
+
+        chip.load(&mut layouter)?;
+
+        let (positive_cells, negative_cells) =
+            chip.assign_zero_accumulators(layouter.namespace(|| "zero accumulators"))?;
+        let (positive_cells, negative_cells) = chip.accumulate_step(
+            layouter.namespace(|| "accumulate 0"),
+            self.values[0],
+            &positive_cells,
+            &negative_cells,
+        )?;
+
+        // the tampered cells are still constrained to be equal to the output of the first step
+        let tampered_cells = layouter.assign_region(
+            || "tampered positive accumulate",
+            |mut region| {
+                let mut cells: ArrayVec<AssignedCell<F, F>, 2> = ArrayVec::new();
+                for (i, cell) in positive_cells.iter().enumerate() {
+                    let tampered = region.assign_advice(
+                        || format!("tampered accumulate[{}]", 1 - i),
+                        config.positive.accumulate[1 - i],
+                        0,
+                        || self.tampered_positive[1 - i],
+                    )?;
+                    region.constrain_equal(cell.cell(), tampered.cell())?;
+                    cells.push(tampered);
+                }
+                Ok(cells)
+            },
+        )?;
+
+        let (positive_cells, negative_cells) = chip.accumulate_step(
+            layouter.namespace(|| "accumulate 1"),
+            self.values[1],
+            &tampered_cells,
+            &negative_cells,
+        )?;
+
+        let balance = chip.assign_balance(
+            layouter.namespace(|| "balance"),
+            &positive_cells,
+            &negative_cells,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "balance"), &balance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SignedAccumulatorCircuit, TamperedSignedAccumulatorCircuit};
+    use halo2_proofs::{
+        circuit::Value,
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr as Fp,
+    };
+
+    fn signed_values(values: &[i64]) -> Vec<Value<Fp>> {
+        values
+            .iter()
+            .map(|v| {
+                if *v < 0 {
+                    Value::known(-Fp::from(v.unsigned_abs()))
+                } else {
+                    Value::known(Fp::from(*v as u64))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_net_positive() {
+        let k = 8;
+
+        // 7 - 3 + 5 = 9
+        let circuit = SignedAccumulatorCircuit::<Fp> {
+            values: signed_values(&[7, -3, 5]),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(9)]]).unwrap();
+        prover.assert_satisfied();
+
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(15)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_net_negative() {
+        let k = 8;
+
+        // 2 - 7 - 4 = -9
+        let circuit = SignedAccumulatorCircuit::<Fp> {
+            values: signed_values(&[2, -7, -4]),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![-Fp::from(9)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_underflow() {
+        let k = 8;
+
+        // the negative sum reaches 2^4, which doesn't fit into the negative accumulator
+        let circuit = SignedAccumulatorCircuit::<Fp> {
+            values: signed_values(&[1, -8, -8]),
+        };
+
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![-Fp::from(15)]]).unwrap();
+        assert!(invalid_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_tampered_intermediate_sum() {
+        let k = 8;
+
+        // 7 + 5 = 12, the intermediate positive sum 7 is witnessed as 2, so the balance would be 2 + 5 = 7
+        let values = signed_values(&[7, 5]);
+        let circuit = TamperedSignedAccumulatorCircuit::<Fp> {
+            values: [values[0], values[1]],
+            tampered_positive: [Value::known(Fp::zero()), Value::known(Fp::from(2))],
+        };
+
+        let invalid_prover = MockProver::run(k, &circuit, vec![vec![Fp::from(7)]]).unwrap();
+        let failures = invalid_prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+
+        // the same circuit witnessing the actual intermediate sum is satisfied
+        let circuit = TamperedSignedAccumulatorCircuit::<Fp> {
+            values: [values[0], values[1]],
+            tampered_positive: [Value::known(Fp::zero()), Value::known(Fp::from(7))],
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(12)]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
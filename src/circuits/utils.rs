@@ -1,7 +1,7 @@
 use halo2_proofs::{
-    halo2curves::bn256::{Fr as Fp, Bn256, G1Affine}, 
+    halo2curves::{bn256::{Fr as Fp, Bn256, G1Affine}, ff::PrimeField}, 
     poly::{
-        commitment::ParamsProver,
+        commitment::{Params, ParamsProver},
         kzg::{
         commitment::{
             ParamsKZG,
@@ -11,13 +11,138 @@ use halo2_proofs::{
         multiopen::{ProverSHPLONK, VerifierSHPLONK}
         },
     },
+    arithmetic::Field,
+    circuit::Value,
     plonk::{
-        create_proof, verify_proof, keygen_pk, keygen_vk, Circuit
+        create_proof, verify_proof, keygen_pk, keygen_vk, Advice, Any, Assigned, Assignment, Challenge,
+        Circuit, Column, ConstraintSystem, Error, Fixed, FloorPlanner, Instance, ProvingKey, Selector,
+        VerifyingKey,
     },
     transcript::{Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer},
+    SerdeFormat,
 };
+use halo2_proofs::dev::MockProver;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
 use std::time::Instant;
-use rand::rngs::OsRng;
+use rand::{rngs::OsRng, RngCore};
+use crate::error::ExperimentError;
+
+// Enters a `tracing` span until the end of the current block when the `trace` feature is enabled, does nothing otherwise
+macro_rules! trace_span {
+    ($name:expr, $($fields:tt)*) => {
+        #[cfg(feature = "trace")]
+        let _span = tracing::info_span!($name, $($fields)*).entered();
+    };
+}
+
+// Implemented by the circuits to declare how many rows of the instance column they constrain with `expose_public`
+pub trait NumPublic {
+    fn num_public(&self) -> usize;
+}
+
+// Implemented by the circuits that compute their own public inputs, one vector per instance column.
+// It mirrors the `instances` method of the `CircuitExt` trait of snark-verifier, which the crate doesn't depend on
+pub trait CircuitExt<F: Field>: Circuit<F> {
+    fn instances(&self) -> Vec<Vec<F>>;
+}
+
+// Runs the MockProver after checking that the instance column has exactly one value per exposed row,
+// so a hand built public input of the wrong length fails with a readable error instead of an equality failure
+pub fn run_checked<C: Circuit<Fp> + NumPublic>(
+    k: u32,
+    circuit: &C,
+    instances: Vec<Vec<Fp>>,
+) -> Result<MockProver<Fp>, ExperimentError> {
+    let expected = circuit.num_public();
+    let found = instances.first().map_or(0, Vec::len);
+    if found != expected {
+        return Err(ExperimentError::InstanceLengthMismatch { expected, found });
+    }
+
+    Ok(MockProver::run(k, circuit, instances)?)
+}
+
+// Fingerprint of the verifying key, the blake2b hash of its serialization. A verifier can pin it to check
+// that a stored vk belongs to the expected version of the circuit
+pub fn vk_hash(vk: &VerifyingKey<G1Affine>) -> [u8; 32] {
+    let bytes = vk.to_bytes(SerdeFormat::RawBytes);
+    let hash = blake2b_simd::Params::new().hash_length(32).hash(&bytes);
+
+    let mut fingerprint = [0u8; 32];
+    fingerprint.copy_from_slice(hash.as_bytes());
+    fingerprint
+}
+
+// Maximum size of the code of a contract, see EIP-170
+pub const EVM_CONTRACT_SIZE_LIMIT: usize = 24576;
+
+// Size in bytes of the verifier contract. The deployment code also contains the constructor, so this is an upper
+// bound of the code that ends up on chain
+pub fn evm_verifier_size(deployment_code: &[u8]) -> usize {
+    deployment_code.len()
+}
+
+// Fails if the verifier contract is larger than `EVM_CONTRACT_SIZE_LIMIT`, so a verifier that can't be deployed is
+// caught before the deployment
+pub fn assert_deployable(deployment_code: &[u8]) -> Result<(), ExperimentError> {
+    let size = evm_verifier_size(deployment_code);
+    if size > EVM_CONTRACT_SIZE_LIMIT {
+        return Err(ExperimentError::ContractTooLarge {
+            size,
+            limit: EVM_CONTRACT_SIZE_LIMIT,
+        });
+    }
+    Ok(())
+}
+
+// Generates a fresh srs for circuits up to 2^k rows.
+// The toxic waste is known, so it is only meant for testing
+pub fn gen_srs(k: u32) -> ParamsKZG<Bn256> {
+    ParamsKZG::<Bn256>::setup(k, OsRng)
+}
+
+// Reads the srs from `path`, e.g. a ptau file converted to the raw halo2 format, when the file exists.
+// The srs must have at least 2^k rows and is downsized to k if it is larger.
+// Without a path, or if the file doesn't exist, a fresh srs is generated with `gen_srs`
+pub fn load_or_gen_srs(
+    k: u32,
+    path: Option<&Path>,
+) -> Result<ParamsKZG<Bn256>, ExperimentError> {
+    let path = match path {
+        Some(path) if path.exists() => path,
+        _ => return Ok(gen_srs(k)),
+    };
+
+    let mut reader = BufReader::new(File::open(path)?);
+    let params = ParamsKZG::<Bn256>::read(&mut reader)?;
+
+    if params.k() < k {
+        return Err(ExperimentError::SrsTooSmall {
+            expected: k,
+            found: params.k(),
+        });
+    }
+
+    Ok(downsize_params(&params, k))
+}
+
+// Returns a copy of the srs for circuits up to 2^k rows, so a large srs can be reused by small circuits
+pub fn downsize_params(params: &ParamsKZG<Bn256>, k: u32) -> ParamsKZG<Bn256> {
+    assert!(
+        k <= params.k(),
+        "can't downsize params of k = {} to the larger k = {}",
+        params.k(),
+        k
+    );
+
+    let mut params = params.clone();
+    if params.k() > k {
+        params.downsize(k);
+    }
+    params
+}
 
 pub fn full_prover <C: Circuit<Fp>> (
     circuit: C,
@@ -25,17 +150,78 @@ pub fn full_prover <C: Circuit<Fp>> (
     public_input: &[Fp]
 ) {
 
-    let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+    let params = gen_srs(k);
+
+    let (pk, vk_time, pk_time) = {
+        trace_span!("keygen", k = params.k());
+
+        let vk_time_start = Instant::now();
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let vk_time = vk_time_start.elapsed();
 
-    let vk_time_start = Instant::now();
-    let vk = keygen_vk(&params, &circuit).unwrap();
-    let vk_time = vk_time_start.elapsed();
+        let pk_time_start = Instant::now();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+        let pk_time = pk_time_start.elapsed();
 
-    let pk_time_start = Instant::now();
-    let pk = keygen_pk(&params, vk, &circuit).unwrap();
-    let pk_time = pk_time_start.elapsed();
+        (pk, vk_time, pk_time)
+    };
 
     let proof_time_start = Instant::now();
+    let proof = gen_proof(&params, &pk, circuit, public_input);
+    let proof_time = proof_time_start.elapsed();
+
+    let verify_time_start = Instant::now();
+    assert!(verify_kzg_proof(
+        &params,
+        pk.get_vk(),
+        &[public_input.to_vec()],
+        &proof
+    ));
+    let verify_time = verify_time_start.elapsed();
+
+    println!("Time to generate vk {:?}", vk_time);
+    println!("Time to generate pk {:?}", pk_time);
+    println!("Prover Time {:?}", proof_time);
+    println!("Verifier Time {:?}", verify_time);
+}
+
+// Generates the proof bytes of the circuit, they can be checked later on with `verify_kzg_proof`
+pub fn gen_proof<C: Circuit<Fp>>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    public_input: &[Fp],
+) -> Vec<u8> {
+    gen_proof_with_rng(params, pk, circuit, public_input, OsRng)
+}
+
+// Same as `gen_proof` with the public input taken from `circuit.instances()`, so it can't differ from the values
+// the circuit exposes. The proof is verified with `verify_kzg_proof` against the same instances
+pub fn gen_proof_ext<C: CircuitExt<Fp>>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+) -> Vec<u8> {
+    let instances = circuit.instances();
+    assert_eq!(
+        instances.len(),
+        1,
+        "gen_proof only supports circuits with a single instance column"
+    );
+
+    gen_proof(params, pk, circuit, &instances[0])
+}
+
+// Same as `gen_proof` with the blinding factors drawn from `rng`, a seeded rng gives byte-identical proofs
+pub fn gen_proof_with_rng<C: Circuit<Fp>, R: RngCore>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    public_input: &[Fp],
+    rng: R,
+) -> Vec<u8> {
+    trace_span!("prove", k = params.k(), instances = public_input.len());
+
     let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
     create_proof::<
         KZGCommitmentScheme<Bn256>,
@@ -44,27 +230,499 @@ pub fn full_prover <C: Circuit<Fp>> (
         _,
         Blake2bWrite<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
         _,
-    >(&params, &pk, &[circuit], &[&[public_input]], OsRng, &mut transcript)
+    >(params, pk, &[circuit], &[&[public_input]], rng, &mut transcript)
     .expect("prover should not fail");
-    let proof = transcript.finalize();
-    let proof_time = proof_time_start.elapsed();
+    transcript.finalize()
+}
 
-    let verifier_params = params.verifier_params();
-    let verify_time_start = Instant::now();
-    let strategy = SingleStrategy::new(&params);
-    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
-    assert!(verify_proof::<
+// Generates a proof for each circuit in parallel, all circuits share the same proving key.
+// `instances[i]` is the public input of `circuits[i]`
+#[cfg(feature = "parallel")]
+pub fn gen_proofs_parallel<C: Circuit<Fp> + Send>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuits: Vec<C>,
+    instances: &[Vec<Fp>],
+) -> Vec<Vec<u8>> {
+    use rayon::prelude::*;
+
+    assert_eq!(circuits.len(), instances.len());
+
+    // params and pk are only read by the prover, so they can be shared across threads
+    circuits
+        .into_par_iter()
+        .zip(instances.par_iter())
+        .map(|(circuit, public_input)| gen_proof(params, pk, circuit, public_input))
+        .collect()
+}
+
+// Verifies the proof bytes against the verifying key and the public inputs of the circuit, one vector per
+// instance column
+pub fn verify_kzg_proof(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[Vec<Fp>],
+    proof: &[u8],
+) -> bool {
+    trace_span!("verify", k = params.k(), instances = instances.len());
+
+    let instances = instances
+        .iter()
+        .map(|column| column.as_slice())
+        .collect::<Vec<_>>();
+
+    let strategy = SingleStrategy::new(params);
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+    verify_proof::<
         KZGCommitmentScheme<Bn256>,
         VerifierSHPLONK<'_, Bn256>,
         Challenge255<G1Affine>,
         Blake2bRead<&[u8], G1Affine, Challenge255<G1Affine>>,
         SingleStrategy<'_, Bn256>,
-    >(verifier_params, pk.get_vk(), strategy, &[&[public_input]], &mut transcript)
-    .is_ok());
-    let verify_time = verify_time_start.elapsed();
+    >(params.verifier_params(), vk, strategy, &[instances.as_slice()], &mut transcript)
+    .is_ok()
+}
 
-    println!("Time to generate vk {:?}", vk_time);
-    println!("Time to generate pk {:?}", pk_time);
-    println!("Prover Time {:?}", proof_time);
-    println!("Verifier Time {:?}", verify_time);
-}
\ No newline at end of file
+// Proof together with the public inputs it was generated for, one vector per instance column,
+// so it can be shared as a single artifact and verified with `verify_bundle`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofBundle {
+    pub proof: Vec<u8>,
+    pub instances: Vec<Vec<Fp>>,
+}
+
+impl ProofBundle {
+    // Every length is a little endian u32: the number of instance columns, then for each column the number of
+    // values followed by the 32 bytes representation of each value, and at last the length of the proof and its bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend((self.instances.len() as u32).to_le_bytes());
+        for column in self.instances.iter() {
+            bytes.extend((column.len() as u32).to_le_bytes());
+            for value in column.iter() {
+                bytes.extend(value.to_repr().as_ref());
+            }
+        }
+        bytes.extend((self.proof.len() as u32).to_le_bytes());
+        bytes.extend(self.proof.iter());
+        bytes
+    }
+
+    // Reads a bundle written by `to_bytes`, the bytes must be consumed entirely
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ExperimentError> {
+        let mut reader = bytes;
+
+        let num_columns = read_len(&mut reader)?;
+        let mut instances = vec![];
+        for _ in 0..num_columns {
+            let num_values = read_len(&mut reader)?;
+            let mut column = vec![];
+            for _ in 0..num_values {
+                let mut repr = <Fp as PrimeField>::Repr::default();
+                repr.as_mut().copy_from_slice(read_bytes(&mut reader, 32)?);
+                let value = Option::from(Fp::from_repr(repr)).ok_or(
+                    ExperimentError::InvalidProofBundle("non canonical field element"),
+                )?;
+                column.push(value);
+            }
+            instances.push(column);
+        }
+
+        let proof_len = read_len(&mut reader)?;
+        let proof = read_bytes(&mut reader, proof_len)?.to_vec();
+
+        if !reader.is_empty() {
+            return Err(ExperimentError::InvalidProofBundle("trailing bytes"));
+        }
+
+        Ok(Self { proof, instances })
+    }
+}
+
+// Splits the first `len` bytes off the reader
+fn read_bytes<'a>(reader: &mut &'a [u8], len: usize) -> Result<&'a [u8], ExperimentError> {
+    if reader.len() < len {
+        return Err(ExperimentError::InvalidProofBundle(
+            "unexpected end of bytes",
+        ));
+    }
+    let (head, tail) = reader.split_at(len);
+    *reader = tail;
+    Ok(head)
+}
+
+// Reads a little endian u32 length
+fn read_len(reader: &mut &[u8]) -> Result<usize, ExperimentError> {
+    let mut len = [0u8; 4];
+    len.copy_from_slice(read_bytes(reader, 4)?);
+    Ok(u32::from_le_bytes(len) as usize)
+}
+
+// Verifies the proof of the bundle against the verifying key and the instances stored in the bundle
+pub fn verify_bundle(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    bundle: &ProofBundle,
+) -> bool {
+    verify_kzg_proof(params, vk, &bundle.instances, &bundle.proof)
+}
+
+// Number of columns and of used rows of a circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutStats {
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    pub rows: usize,
+}
+
+// Layouts of two circuits and their deltas, computed as `b - a`, so a positive delta is an extra cost of `b`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutDiff {
+    pub a: LayoutStats,
+    pub b: LayoutStats,
+    pub advice_columns: isize,
+    pub fixed_columns: isize,
+    pub instance_columns: isize,
+    pub selectors: isize,
+    pub rows: isize,
+}
+
+// Keeps track of the highest row assigned by the floor planner, the values of the cells are never evaluated
+struct RowCounter {
+    k: u32,
+    usable_rows: usize,
+    rows: usize,
+}
+
+impl RowCounter {
+    fn use_row(&mut self, row: usize) -> Result<(), Error> {
+        if row >= self.usable_rows {
+            return Err(Error::NotEnoughRowsAvailable { current_k: self.k });
+        }
+        self.rows = self.rows.max(row + 1);
+        Ok(())
+    }
+}
+
+impl<F: Field> Assignment<F> for RowCounter {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.use_row(row)
+    }
+
+    fn annotate_column<A, AR>(&mut self, _: A, _: Column<Any>)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.use_row(row)
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.use_row(row)
+    }
+
+    fn copy(
+        &mut self,
+        _: Column<Any>,
+        left_row: usize,
+        _: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.use_row(left_row)?;
+        self.use_row(right_row)
+    }
+
+    // only used to pad the lookup tables up to the usable rows, it doesn't count as used rows
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get_challenge(&self, _: Challenge) -> Value<F> {
+        Value::unknown()
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+// Counts the columns of the circuit and the rows used by its synthesis, which must fit into 2^k rows
+pub fn layout_stats<C: Circuit<Fp>>(circuit: &C, k: u32) -> Result<LayoutStats, ExperimentError> {
+    let mut cs = ConstraintSystem::default();
+    let config = C::configure(&mut cs);
+
+    let mut counter = RowCounter {
+        k,
+        usable_rows: (1 << k) - (cs.blinding_factors() + 1),
+        rows: 0,
+    };
+    C::FloorPlanner::synthesize(&mut counter, circuit, config, cs.constants().clone())?;
+
+    Ok(LayoutStats {
+        advice_columns: cs.num_advice_columns(),
+        fixed_columns: cs.num_fixed_columns(),
+        instance_columns: cs.num_instance_columns(),
+        selectors: cs.num_selectors(),
+        rows: counter.rows,
+    })
+}
+
+// Compares the layouts of two circuits, to quantify the cost of switching from `a` to `b`
+pub fn layout_diff<A: Circuit<Fp>, B: Circuit<Fp>>(
+    a: &A,
+    b: &B,
+    k: u32,
+) -> Result<LayoutDiff, ExperimentError> {
+    let a = layout_stats(a, k)?;
+    let b = layout_stats(b, k)?;
+    let delta = |a: usize, b: usize| b as isize - a as isize;
+
+    Ok(LayoutDiff {
+        a,
+        b,
+        advice_columns: delta(a.advice_columns, b.advice_columns),
+        fixed_columns: delta(a.fixed_columns, b.fixed_columns),
+        instance_columns: delta(a.instance_columns, b.instance_columns),
+        selectors: delta(a.selectors, b.selectors),
+        rows: delta(a.rows, b.rows),
+    })
+}
+
+// Unknown values with the same length as `values`, for the `without_witnesses` of circuits holding vectors of
+// witnesses: the length of the vectors fixes the shape of the circuit, e.g. the number of levels of a merkle path,
+// so the keys generated from `without_witnesses` match the ones of the circuits with witnesses
+pub fn unknown_values<V>(values: &[Value<V>]) -> Vec<Value<V>> {
+    values.iter().map(|_| Value::unknown()).collect()
+}
+
+// Largest k tried by `min_k`, the 2-adicity of the bn256 scalar field bounds the size of the evaluation domain
+const MAX_K: u32 = 28;
+
+// Returns the smallest k for which the circuit fits into 2^k rows: the rows reserved to the blinding factors
+// (`minimum_rows`) and the rows used by its synthesis, counted by `layout_stats` for increasing values of k.
+// Panics if the synthesis fails for another reason than the number of rows, or if it doesn't fit into 2^MAX_K rows
+pub fn min_k<C: Circuit<Fp>>(circuit: &C) -> u32 {
+    let mut cs = ConstraintSystem::<Fp>::default();
+    C::configure(&mut cs);
+
+    let mut k = 1;
+    while (1 << k) < cs.minimum_rows() {
+        k += 1;
+    }
+
+    while k <= MAX_K {
+        match layout_stats(circuit, k) {
+            Ok(_) => return k,
+            Err(ExperimentError::Plonk(Error::NotEnoughRowsAvailable { .. })) => k += 1,
+            Err(err) => panic!("synthesis failed at k = {}: {}", k, err),
+        }
+    }
+
+    panic!("the circuit doesn't fit into 2^{} rows", MAX_K)
+}
+
+// Returns the name and the degree of every gate of the circuit, the degree of a gate being the highest degree of its
+// polynomials. The degree of the circuit, which drives the size of the extended domain used by the prover, is at
+// least the highest degree of its gates, so a chip with an outlier gate can be spotted before proving
+pub fn gate_report<C: Circuit<Fp>>(_circuit: &C) -> Vec<(String, usize)> {
+    let mut cs = ConstraintSystem::<Fp>::default();
+    C::configure(&mut cs);
+
+    cs.gates()
+        .iter()
+        .map(|gate| {
+            let degree = gate
+                .polynomials()
+                .iter()
+                .map(|poly| poly.degree())
+                .max()
+                .unwrap_or(0);
+            (gate.name().to_string(), degree)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        assert_deployable, evm_verifier_size, gen_proof, verify_kzg_proof, vk_hash,
+        EVM_CONTRACT_SIZE_LIMIT,
+    };
+    use crate::circuits::{
+        hash_v1::Hash1Circuit, hash_v2::Hash2Circuit, range_proof::RangeProofCircuit,
+    };
+    use crate::error::ExperimentError;
+    use halo2_proofs::{
+        circuit::Value,
+        halo2curves::bn256::{Bn256, Fr as Fp},
+        plonk::{keygen_pk, keygen_vk},
+        poly::kzg::commitment::ParamsKZG,
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_vk_hash() {
+        let k = 4;
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+
+        let circuit = Hash1Circuit::<Fp> {
+            a: Value::unknown(),
+        };
+
+        // two keygens of the same circuit
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let same_vk = keygen_vk(&params, &circuit).unwrap();
+        assert_eq!(vk_hash(&vk), vk_hash(&same_vk));
+
+        // another circuit has other gates and columns
+        let other_circuit = Hash2Circuit::<Fp> {
+            a: Value::unknown(),
+            b: Value::unknown(),
+        };
+        let other_vk = keygen_vk(&params, &other_circuit).unwrap();
+        assert_ne!(vk_hash(&vk), vk_hash(&other_vk));
+    }
+
+    #[test]
+    fn test_evm_verifier_size() {
+        // a deployment code right at the limit of EIP-170 can be deployed
+        let code = vec![0u8; EVM_CONTRACT_SIZE_LIMIT];
+        assert_eq!(evm_verifier_size(&code), EVM_CONTRACT_SIZE_LIMIT);
+        assert!(assert_deployable(&code).is_ok());
+
+        // one byte above it can't
+        let code = vec![0u8; EVM_CONTRACT_SIZE_LIMIT + 1];
+        assert!(matches!(
+            assert_deployable(&code),
+            Err(ExperimentError::ContractTooLarge {
+                size: 24577,
+                limit: 24576
+            })
+        ));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_proofs() {
+        use super::gen_proofs_parallel;
+
+        let k = 9;
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+
+        let circuits = [100, 150, 200]
+            .map(|value| RangeProofCircuit::<Fp>::new(value, 100, 200))
+            .into_iter()
+            .collect::<Vec<_>>();
+        let public_inputs = vec![vec![Fp::from(100), Fp::from(200)]; 3];
+
+        let vk = keygen_vk(&params, &circuits[0]).unwrap();
+        let pk = keygen_pk(&params, vk, &circuits[0]).unwrap();
+
+        let proofs = gen_proofs_parallel(&params, &pk, circuits, &public_inputs);
+        assert_eq!(proofs.len(), 3);
+
+        for (proof, public_input) in proofs.iter().zip(public_inputs.iter()) {
+            assert!(verify_kzg_proof(
+                &params,
+                pk.get_vk(),
+                &[public_input.clone()],
+                proof
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verify_proof_bytes() {
+        let k = 9;
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+
+        let circuit = RangeProofCircuit::<Fp>::new(150, 100, 200);
+        let public_input = vec![Fp::from(100), Fp::from(200)];
+
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+        // the prover only hands over the proof bytes
+        let proof = gen_proof(&params, &pk, circuit, &public_input);
+        assert!(verify_kzg_proof(
+            &params,
+            pk.get_vk(),
+            &[public_input.clone()],
+            &proof
+        ));
+
+        let mut corrupted_proof = proof.clone();
+        corrupted_proof[0] ^= 1;
+        assert!(!verify_kzg_proof(
+            &params,
+            pk.get_vk(),
+            &[public_input],
+            &corrupted_proof
+        ));
+
+        // the proof doesn't hold for another range
+        let other_input = vec![Fp::from(100), Fp::from(300)];
+        assert!(!verify_kzg_proof(
+            &params,
+            pk.get_vk(),
+            &[other_input],
+            &proof
+        ));
+    }
+}
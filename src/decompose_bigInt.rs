@@ -1,15 +1,27 @@
-use halo2curves::{ff::PrimeField, pasta::*};
+use alloc::vec::Vec;
+use halo2_proofs::halo2curves::ff::PrimeField;
 use num_bigint::BigUint;
 
-pub fn decompose_bigInt_to_ubits(
+// `1u64 << 64` would overflow, so the masks and shifts of a whole u64 digit are handled separately
+fn low_bits_mask(bits: usize) -> u64 {
+    u64::MAX.checked_shr(64 - bits as u32).unwrap_or(0)
+}
+
+fn f_from_u128<F: PrimeField>(value: u128) -> F {
+    let shift = F::from(u64::MAX) + F::one();
+    F::from((value >> 64) as u64) * shift + F::from(value as u64)
+}
+
+pub fn decompose_bigInt_to_ubits<F: PrimeField>(
     e: &BigUint,
     number_of_limbs: usize,
     bit_len: usize,
-) -> Vec<Fp> {
+) -> Vec<F> {
     debug_assert!(bit_len <= 64);
 
     let mut e = e.iter_u64_digits();
-    let mask: u64 = (1u64 << bit_len) - 1u64;
+    // `1u64 << 64` would overflow when bit_len is 64
+    let mask: u64 = u64::MAX >> (64 - bit_len);
     let mut u64_digit = e.next().unwrap_or(0);
     let mut rem = 64;
     (0..number_of_limbs)
@@ -18,13 +30,13 @@ pub fn decompose_bigInt_to_ubits(
                 let limb = u64_digit & mask;
                 u64_digit >>= bit_len;
                 rem -= bit_len;
-                Fp::from(limb)
+                F::from(limb)
             }
             core::cmp::Ordering::Equal => {
                 let limb = u64_digit & mask;
                 u64_digit = e.next().unwrap_or(0);
                 rem = 64;
-                Fp::from(limb)
+                F::from(limb)
             }
             core::cmp::Ordering::Less => {
                 let mut limb = u64_digit;
@@ -32,24 +44,28 @@ pub fn decompose_bigInt_to_ubits(
                 limb |= (u64_digit & ((1 << (bit_len - rem)) - 1)) << rem; // *
                 u64_digit >>= bit_len - rem;
                 rem += 64 - bit_len;
-                Fp::from(limb)
+                F::from(limb)
             }
         })
         .collect()
 }
 
-pub fn decompose_biguint_u64(e: &BigUint, num_limbs: usize, bit_len: usize) -> Vec<Fp> {
+pub fn decompose_biguint_u64<F: PrimeField>(
+    e: &BigUint,
+    num_limbs: usize,
+    bit_len: usize,
+) -> Vec<F> {
     debug_assert!(bit_len > 64 && bit_len <= 128);
     let mut e = e.iter_u64_digits();
 
     let mut limb0 = e.next().unwrap_or(0) as u128;
     let mut rem = bit_len - 64;
     let mut u64_digit = e.next().unwrap_or(0);
-    limb0 |= ((u64_digit & ((1 << rem) - 1)) as u128) << 64;
-    u64_digit >>= rem;
+    limb0 |= ((u64_digit & low_bits_mask(rem)) as u128) << 64;
+    u64_digit = u64_digit.checked_shr(rem as u32).unwrap_or(0);
     rem = 64 - rem;
 
-    core::iter::once(Fp::from_u128(limb0))
+    core::iter::once(f_from_u128(limb0))
         .chain((1..num_limbs).map(|_| {
             let mut limb: u128 = u64_digit.into();
             let mut bits = rem;
@@ -60,31 +76,64 @@ pub fn decompose_biguint_u64(e: &BigUint, num_limbs: usize, bit_len: usize) -> V
                 bits += 64;
             }
             rem = bit_len - bits;
-            limb |= ((u64_digit & ((1 << rem) - 1)) as u128) << bits;
-            u64_digit >>= rem;
+            limb |= ((u64_digit & low_bits_mask(rem)) as u128) << bits;
+            u64_digit = u64_digit.checked_shr(rem as u32).unwrap_or(0);
             rem = 64 - rem;
-            Fp::from_u128(limb)
+            f_from_u128(limb)
         }))
         .collect()
 }
 
+fn f_from_biguint<F: PrimeField>(value: &BigUint) -> F {
+    let shift = F::from(u64::MAX) + F::one();
+    value
+        .to_u64_digits()
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, digit| acc * shift + F::from(*digit))
+}
+
+// Decomposes `e` into `num_limbs` little-endian limbs of `bit_len` bits, for any `bit_len` up to the capacity
+// of the field, e.g. a 256 bits balance into 64 bits limbs. The limbs are shifted out of `e` one at a time
+pub fn decompose_biguint_general<F: PrimeField>(
+    e: &BigUint,
+    num_limbs: usize,
+    bit_len: usize,
+) -> Vec<F> {
+    debug_assert!(bit_len > 0 && bit_len <= F::CAPACITY as usize);
+    let mask = (BigUint::from(1u8) << bit_len) - 1u8;
+
+    let mut e = e.clone();
+    (0..num_limbs)
+        .map(|_| {
+            let limb = &e & &mask;
+            e >>= bit_len;
+            f_from_biguint(&limb)
+        })
+        .collect()
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
-    use halo2curves::pasta::Fp;
+    use halo2_proofs::arithmetic::FieldExt;
+    use halo2_proofs::halo2curves::{bn256::Fr, pasta::Fp};
     use num_bigint::BigUint;
 
     #[test]
     fn test_decompose_bigInt_toU64() {
+        // digits are little-endian, so a is 0xfffffffe_ffffffff
         let a = BigUint::new(vec![u32::MAX, u32::MAX - 1]);
-        let decom = decompose_biguint_u64(&a, 3, 65);
+        let decom = decompose_biguint_u64::<Fp>(&a, 3, 65);
         // println!("decomposed a: {:?}", decom);
-        assert_eq!(decom[0], Fp::from(u64::MAX - 1));
+        assert_eq!(decom[0], Fp::from(0xffff_fffe_ffff_ffff));
+        assert_eq!(decom[1], Fp::zero());
     }
 
     #[test]
     fn test_decompose_bigInt_to_u16() {
         let b = BigUint::new(vec![u32::MAX, u32::MAX]);
-        let decom = decompose_bigInt_to_ubits(&b, 12, 16);
+        let decom = decompose_bigInt_to_ubits::<Fp>(&b, 12, 16);
         // println!("decompose_Fps: {:?}", decom);
         assert_eq!(decom[0], Fp::from(65535));
         assert_eq!(decom[1], Fp::from(65535));
@@ -92,4 +141,57 @@ mod tests {
         assert_eq!(decom[3], Fp::from(65535));
         assert_eq!(decom[4], Fp::zero());
     }
+
+    #[test]
+    fn test_decompose_biguint_u64_bn256() {
+        let low: u128 = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+        let high: u64 = 0xdead_beef;
+        let value = BigUint::from(high) << 128 | BigUint::from(low);
+
+        // full 128 bits limbs
+        let decom = decompose_biguint_u64::<Fr>(&value, 2, 128);
+        assert_eq!(decom, vec![Fr::from_u128(low), Fr::from(high)]);
+
+        // 100 bits limbs straddle the u64 digits
+        let decom = decompose_biguint_u64::<Fr>(&value, 2, 100);
+        let mask = (1u128 << 100) - 1;
+        assert_eq!(decom[0], Fr::from_u128(low & mask));
+        assert_eq!(decom[1], Fr::from_u128((high as u128) << 28 | low >> 100));
+    }
+
+    // recomposes little-endian limbs of `bit_len` bits
+    fn recompose(limbs: &[Fr], bit_len: usize) -> BigUint {
+        limbs.iter().rev().fold(BigUint::from(0u8), |acc, limb| {
+            (acc << bit_len) + BigUint::from_bytes_le(limb.to_repr().as_ref())
+        })
+    }
+
+    #[test]
+    fn test_decompose_biguint_general() {
+        let digits = [
+            0x0123_4567_89ab_cdef,
+            0xfedc_ba98_7654_3210,
+            0xdead_beef_cafe_babe,
+            0xffff_ffff_ffff_fffe,
+        ];
+        let value = digits
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u8), |acc, digit| (acc << 64) + *digit);
+        assert_eq!(value.bits(), 256);
+
+        // four 64 bits limbs
+        let decom = decompose_biguint_general::<Fr>(&value, 4, 64);
+        assert_eq!(decom, digits.map(Fr::from).to_vec());
+        assert_eq!(recompose(&decom, 64), value);
+
+        // 200 bits limbs straddle the u64 digits
+        let decom = decompose_biguint_general::<Fr>(&value, 2, 200);
+        assert_eq!(decom[1], Fr::from(digits[3] >> 8));
+        assert_eq!(recompose(&decom, 200), value);
+
+        // the limbs above the value are zero
+        let decom = decompose_biguint_general::<Fr>(&value, 6, 64);
+        assert_eq!(decom[4..], [Fr::zero(), Fr::zero()]);
+    }
 }
@@ -0,0 +1,132 @@
+use core::fmt;
+use halo2_proofs::plonk::Error;
+
+// Errors raised by the circuits when the witnesses passed as input are not consistent with each other.
+// Halo2 only accepts `plonk::Error` from `synthesize`, so every shape error becomes `Error::Synthesis` there
+#[derive(Debug)]
+pub enum ExperimentError {
+    Plonk(Error),
+    // the merkle path contains no level
+    EmptyPath,
+    // `name` contains `found` elements while the merkle path has `expected` levels
+    PathLengthMismatch {
+        name: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    // the leaf index doesn't fit into a merkle path of `levels` levels
+    IndexOutOfRange {
+        index: u64,
+        levels: usize,
+    },
+    // the srs supports circuits up to 2^found rows, while the circuit needs 2^expected rows
+    SrsTooSmall {
+        expected: u32,
+        found: u32,
+    },
+    // the srs file couldn't be read
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    // the instance column contains `found` values while the circuit exposes `expected` rows
+    InstanceLengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    // the verifier contract has `size` bytes, more than the `limit` of the EVM
+    ContractTooLarge {
+        size: usize,
+        limit: usize,
+    },
+    // the bytes of a proof bundle are truncated, or contain a value that is not a field element
+    InvalidProofBundle(&'static str),
+}
+
+impl fmt::Display for ExperimentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExperimentError::Plonk(err) => write!(f, "plonk error: {}", err),
+            ExperimentError::EmptyPath => write!(f, "the merkle path is empty"),
+            ExperimentError::PathLengthMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has {} elements, expected {} (one per level)",
+                name, found, expected
+            ),
+            ExperimentError::IndexOutOfRange { index, levels } => write!(
+                f,
+                "leaf index {} doesn't fit into a merkle path of {} levels",
+                index, levels
+            ),
+            ExperimentError::SrsTooSmall { expected, found } => write!(
+                f,
+                "the srs has k = {}, expected at least k = {}",
+                found, expected
+            ),
+            #[cfg(feature = "std")]
+            ExperimentError::Io(err) => write!(f, "failed to read the srs: {}", err),
+            ExperimentError::InstanceLengthMismatch { expected, found } => write!(
+                f,
+                "the instance column has {} values, expected {} (one per exposed row)",
+                found, expected
+            ),
+            ExperimentError::ContractTooLarge { size, limit } => write!(
+                f,
+                "the verifier contract has {} bytes, the EVM limit is {} bytes",
+                size, limit
+            ),
+            ExperimentError::InvalidProofBundle(reason) => {
+                write!(f, "invalid proof bundle: {}", reason)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ExperimentError {}
+
+impl From<Error> for ExperimentError {
+    fn from(err: Error) -> Self {
+        ExperimentError::Plonk(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ExperimentError {
+    fn from(err: std::io::Error) -> Self {
+        ExperimentError::Io(err)
+    }
+}
+
+impl From<ExperimentError> for Error {
+    fn from(err: ExperimentError) -> Self {
+        match err {
+            ExperimentError::Plonk(err) => err,
+            _ => Error::Synthesis,
+        }
+    }
+}
+
+// Checks that every (name, length) pair has one element per level of the path
+pub fn check_path_lengths(
+    levels: usize,
+    lengths: &[(&'static str, usize)],
+) -> Result<(), ExperimentError> {
+    if levels == 0 {
+        return Err(ExperimentError::EmptyPath);
+    }
+
+    for (name, found) in lengths {
+        if *found != levels {
+            return Err(ExperimentError::PathLengthMismatch {
+                name: *name,
+                expected: levels,
+                found: *found,
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use eth_types::Field;
+use halo2_proofs::circuit::Value;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum CsvError {
+    Csv(csv::Error),
+    // `line` is the line of the row inside the file, the header being line 1
+    MalformedRow { line: u64, reason: String },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(err) => write!(f, "csv error: {}", err),
+            CsvError::MalformedRow { line, reason } => {
+                write!(f, "malformed row at line {}: {}", line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<csv::Error> for CsvError {
+    fn from(err: csv::Error) -> Self {
+        CsvError::Csv(err)
+    }
+}
+
+// Converts a username into a field element.
+// A numeric username is used as it is, otherwise its utf-8 bytes are read as a big endian integer.
+// The bytes must fit into 31 bytes, so that the integer is always smaller than the field modulus
+pub fn username_to_field<F: Field>(username: &str) -> Option<F> {
+    if let Ok(number) = username.parse::<u64>() {
+        return Some(F::from(number));
+    }
+
+    let bytes = username.as_bytes();
+    if bytes.is_empty() || bytes.len() > 31 {
+        return None;
+    }
+
+    let mut le_bytes = [0u8; 64];
+    for (i, byte) in bytes.iter().rev().enumerate() {
+        le_bytes[i] = *byte;
+    }
+    Some(F::from_bytes_wide(&le_bytes))
+}
+
+// Loads the entries of a csv file with a `username,balance` header.
+// Returns the usernames and the balances as witnesses, in the same order as the rows of the file
+pub fn load_entries_from_csv<F: Field, P: AsRef<Path>>(
+    path: P,
+) -> Result<(Vec<Value<F>>, Vec<Value<F>>), CsvError> {
+    // a flexible reader returns the rows with a wrong number of fields, so they are reported as malformed rows
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_path(path)?;
+
+    let mut usernames = vec![];
+    let mut balances = vec![];
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+
+        if record.len() != 2 {
+            return Err(CsvError::MalformedRow {
+                line,
+                reason: format!("expected 2 fields, found {}", record.len()),
+            });
+        }
+
+        let username =
+            username_to_field::<F>(record[0].trim()).ok_or_else(|| CsvError::MalformedRow {
+                line,
+                reason: format!("invalid username {:?}", &record[0]),
+            })?;
+
+        let balance = record[1]
+            .trim()
+            .parse::<u64>()
+            .map_err(|err| CsvError::MalformedRow {
+                line,
+                reason: format!("invalid balance {:?}: {}", &record[1], err),
+            })?;
+
+        usernames.push(Value::known(username));
+        balances.push(Value::known(F::from(balance)));
+    }
+
+    Ok((usernames, balances))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_entries_from_csv, username_to_field, CsvError};
+    use halo2_proofs::halo2curves::bn256::Fr as Fp;
+    use std::io::Write;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/entries.csv");
+    const EXTRA_FIELD_FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/entries_extra_field.csv"
+    );
+
+    #[test]
+    fn test_load_entries_from_csv() {
+        let (usernames, balances) = load_entries_from_csv::<Fp, _>(FIXTURE).unwrap();
+
+        assert_eq!(usernames.len(), 5);
+        assert_eq!(balances.len(), 5);
+
+        balances[1].assert_if_known(|balance| *balance == Fp::from(67823));
+        // numeric usernames are used as they are
+        usernames[4].assert_if_known(|username| *username == Fp::from(12345));
+        // the bytes of "dxGaEAii" fit into a u64
+        usernames[0]
+            .assert_if_known(|username| *username == Fp::from(u64::from_be_bytes(*b"dxGaEAii")));
+        assert_eq!(
+            username_to_field::<Fp>("dxGaEAii"),
+            Some(Fp::from(u64::from_be_bytes(*b"dxGaEAii")))
+        );
+    }
+
+    #[test]
+    fn test_load_entries_from_malformed_csv() {
+        let path = std::env::temp_dir().join("halo2_experiments_malformed_entries.csv");
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, "username,balance\nalice,100\nbob,not_a_number").unwrap();
+
+        let result = load_entries_from_csv::<Fp, _>(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        match result {
+            Err(CsvError::MalformedRow { line, .. }) => assert_eq!(line, 3),
+            _ => panic!("expected a malformed row error"),
+        }
+    }
+
+    #[test]
+    fn test_load_entries_with_extra_field() {
+        match load_entries_from_csv::<Fp, _>(EXTRA_FIELD_FIXTURE) {
+            Err(CsvError::MalformedRow { line, reason }) => {
+                assert_eq!(line, 3);
+                assert_eq!(reason, "expected 2 fields, found 3");
+            }
+            _ => panic!("expected a malformed row error"),
+        }
+    }
+}
@@ -1,2 +1,34 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// `vec!` and `format!` come from alloc when std is disabled
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
 pub mod chips;
-pub mod circuits;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub mod circuits;
+#[allow(non_snake_case)]
+pub mod decompose_bigInt;
+pub mod error;
+#[cfg(feature = "std")]
+pub mod merkle_sum_tree;
+
+#[cfg(feature = "csv")]
+pub mod io;
+
+#[cfg(test)]
+mod test_support;
+
+// the main chips and configs, the other ones are reachable through `chips` and the circuits through `circuits`
+pub use chips::gadgets::{MuxChip, MuxConfig};
+pub use chips::is_zero::{IsZeroChip, IsZeroConfig};
+pub use chips::less_than_v2::{LessThanV2Chip, LessThanV2Config};
+#[cfg(feature = "std")]
+pub use chips::merkle_sum_tree::{MerkleSumTreeChip, MerkleSumTreeConfig};
+pub use chips::merkle_v3::{MerkleTreeV3Chip, MerkleTreeV3Config};
+pub use chips::poseidon::hash::{PoseidonChip, PoseidonConfig};
+#[cfg(feature = "std")]
+pub use chips::range_proof::{RangeProofChip, RangeProofConfig};
+#[cfg(feature = "std")]
+pub use chips::safe_accumulator::{SafeACcumulatorChip, SafeAccumulatorConfig};
+pub use error::ExperimentError;
@@ -0,0 +1,142 @@
+// Off-circuit Merkle Sum Tree, used to build the witness of `MerkleSumTreeCircuit`.
+// Each middle node is `Node { hash: H(left.hash, left.balance, right.hash, right.balance), balance: left.balance + right.balance }`
+// where H is the same Poseidon hash configured inside `MerkleSumTreeChip`.
+use crate::chips::merkle_sum_tree::{L, LEAF_TAG, RATE, WIDTH};
+use crate::chips::poseidon::spec::MySpec;
+use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub hash: Fp,
+    pub balance: Fp,
+}
+
+// Membership proof of a leaf, path_indices[i] is 0 if the node at level i is a left child, 1 otherwise
+#[derive(Debug, Clone)]
+pub struct MerkleProof {
+    pub leaf: Node,
+    pub path_elements: Vec<Node>,
+    pub path_indices: Vec<Fp>,
+    pub root: Node,
+}
+
+#[derive(Debug, Clone)]
+pub struct MerkleSumTree {
+    // nodes[0] are the leaves, nodes[depth] only contains the root
+    nodes: Vec<Vec<Node>>,
+    depth: usize,
+}
+
+pub fn hash_nodes(left: &Node, right: &Node) -> Node {
+    let hash = poseidon::Hash::<_, MySpec<Fp, WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init()
+        .hash([left.hash, left.balance, right.hash, right.balance]);
+
+    Node {
+        hash,
+        balance: left.balance + right.balance,
+    }
+}
+
+// Leaf node of a user, the hash is the same as the one computed by `MerkleSumTreeChip::assign_and_hash_leaf`
+pub fn hash_leaf(username: Fp, balance: Fp) -> Node {
+    let hash = poseidon::Hash::<_, MySpec<Fp, WIDTH, RATE>, ConstantLength<3>, WIDTH, RATE>::init()
+        .hash([Fp::from(LEAF_TAG), username, balance]);
+
+    Node { hash, balance }
+}
+
+// Sum of the balances of all the leaves, namely the liabilities of the custodian towards its users.
+// It is the balance of the root, and the `assets_sum` of a solvency proof must be greater than it.
+// Note that the leaf balance plus the balances of the path elements of any leaf gives the same total,
+// since every path element carries the sum of a whole subtree
+pub fn total_liabilities(leaves: &[Node]) -> Fp {
+    leaves
+        .iter()
+        .fold(Fp::zero(), |acc, leaf| acc + leaf.balance)
+}
+
+impl MerkleSumTree {
+    pub fn new(leaves: Vec<Node>) -> Self {
+        assert!(
+            leaves.len().is_power_of_two(),
+            "the number of leaves must be a power of two"
+        );
+
+        let depth = leaves.len().trailing_zeros() as usize;
+        let mut nodes = vec![leaves];
+
+        for level in 0..depth {
+            let next_level = nodes[level]
+                .chunks(2)
+                .map(|pair| hash_nodes(&pair[0], &pair[1]))
+                .collect::<Vec<Node>>();
+            nodes.push(next_level);
+        }
+
+        Self { nodes, depth }
+    }
+
+    pub fn root(&self) -> &Node {
+        &self.nodes[self.depth][0]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn leaves(&self) -> &[Node] {
+        &self.nodes[0]
+    }
+
+    pub fn generate_proof(&self, index: usize) -> MerkleProof {
+        assert!(index < self.nodes[0].len(), "leaf index out of bounds");
+
+        let mut path_elements = vec![];
+        let mut path_indices = vec![];
+        let mut current_index = index;
+
+        for level in 0..self.depth {
+            let sibling_index = current_index ^ 1;
+            path_elements.push(self.nodes[level][sibling_index].clone());
+            path_indices.push(Fp::from((current_index % 2) as u64));
+            current_index /= 2;
+        }
+
+        MerkleProof {
+            leaf: self.nodes[0][index].clone(),
+            path_elements,
+            path_indices,
+            root: self.root().clone(),
+        }
+    }
+
+    // Replaces the leaf at `index` and recomputes only the nodes along its path, so the cost is O(depth).
+    // Returns the new root and the membership proof of the updated leaf.
+    pub fn update_leaf(
+        &mut self,
+        index: usize,
+        new_hash: Fp,
+        new_balance: Fp,
+    ) -> (Node, MerkleProof) {
+        assert!(index < self.nodes[0].len(), "leaf index out of bounds");
+
+        self.nodes[0][index] = Node {
+            hash: new_hash,
+            balance: new_balance,
+        };
+
+        let mut current_index = index;
+        for level in 0..self.depth {
+            let parent_index = current_index / 2;
+            let parent = hash_nodes(
+                &self.nodes[level][parent_index * 2],
+                &self.nodes[level][parent_index * 2 + 1],
+            );
+            self.nodes[level + 1][parent_index] = parent;
+            current_index = parent_index;
+        }
+
+        (self.root().clone(), self.generate_proof(index))
+    }
+}
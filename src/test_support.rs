@@ -0,0 +1,129 @@
+// Plain-Rust models of the chips, used to cross-check the values exposed by the circuits in tests.
+
+use rand::Rng;
+
+// Off-circuit model of `SafeACcumulatorChip`.
+//
+// `init` and the returned accumulator follow the column order of the chip, so index 0 is the left most
+// (most significant) limb. The overflow flag is set when a value doesn't fit into `max_bits`, or when the
+// left most limb becomes non-zero after any addition. Those are the cases where the chip can't be satisfied.
+pub fn accumulate_reference<const ACC_COLS: usize>(
+    values: &[u64],
+    init: [u64; ACC_COLS],
+    max_bits: u8,
+) -> ([u64; ACC_COLS], bool) {
+    let mask = (1u128 << max_bits) - 1;
+    let total_bits = max_bits as u32 * ACC_COLS as u32;
+
+    let mut total = init
+        .iter()
+        .fold(0u128, |acc, limb| (acc << max_bits) + *limb as u128);
+    let mut accumulated = init;
+    let mut overflow = false;
+
+    for value in values {
+        if *value as u128 > mask {
+            overflow = true;
+        }
+        total += *value as u128;
+
+        for (idx, limb) in accumulated.iter_mut().enumerate() {
+            let shift_bits = max_bits as u32 * (ACC_COLS - 1 - idx) as u32;
+            *limb = ((total >> shift_bits) & mask) as u64;
+        }
+
+        // the chip requires the left most limb to be zero after every update
+        if accumulated[0] != 0 || total.checked_shr(total_bits).unwrap_or(0) != 0 {
+            overflow = true;
+        }
+    }
+
+    (accumulated, overflow)
+}
+
+// Splits `total` into ACC_COLS limbs of `max_bits`, the left most limb being the most significant one
+fn to_limbs<const ACC_COLS: usize>(total: u128, max_bits: u8) -> [u64; ACC_COLS] {
+    let mask = (1u128 << max_bits) - 1;
+    let mut limbs = [0u64; ACC_COLS];
+    for (idx, limb) in limbs.iter_mut().enumerate() {
+        let shift_bits = max_bits as u32 * (ACC_COLS - 1 - idx) as u32;
+        *limb = ((total >> shift_bits) & mask) as u64;
+    }
+    limbs
+}
+
+// Picks a value up to `max`, a quarter of the times exactly `max` so the edge cases are hit more often
+fn gen_up_to<R: Rng>(rng: &mut R, max: u128) -> u128 {
+    if rng.gen_ratio(1, 4) {
+        max
+    } else {
+        rng.gen_range(0..=max)
+    }
+}
+
+// Random inputs of `SafeACcumulatorChip` that must verify: every value fits into `max_bits` and the left most
+// limb stays zero after each addition. Returns the values, the initial accumulator and the expected accumulator
+pub fn random_accumulator_witness<R: Rng, const ACC_COLS: usize>(
+    rng: &mut R,
+    max_bits: u8,
+) -> (Vec<u64>, [u64; ACC_COLS], [u64; ACC_COLS]) {
+    let mask = (1u128 << max_bits) - 1;
+    let capacity = (1u128 << (max_bits as u32 * (ACC_COLS - 1) as u32)) - 1;
+
+    let init_total = gen_up_to(rng, capacity);
+    let mut remaining = capacity - init_total;
+    let values = (0..rng.gen_range(1..4))
+        .map(|_| {
+            let value = gen_up_to(rng, mask.min(remaining));
+            remaining -= value;
+            value as u64
+        })
+        .collect::<Vec<_>>();
+
+    let init = to_limbs(init_total, max_bits);
+    let (expected, overflow) = accumulate_reference(&values, init, max_bits);
+    assert!(!overflow);
+
+    (values, init, expected)
+}
+
+// Random inputs of `SafeACcumulatorChip` that must fail, either because a value doesn't fit into `max_bits`
+// or because the last value carries into the left most limb. The expected accumulator is the one of
+// `accumulate_reference`, truncated to ACC_COLS limbs
+pub fn random_overflow_accumulator_witness<R: Rng, const ACC_COLS: usize>(
+    rng: &mut R,
+    max_bits: u8,
+) -> (Vec<u64>, [u64; ACC_COLS], [u64; ACC_COLS]) {
+    let mask = (1u128 << max_bits) - 1;
+    let capacity = (1u128 << (max_bits as u32 * (ACC_COLS - 1) as u32)) - 1;
+
+    let (values, init_total) = if rng.gen() {
+        // a value out of range, the other values and the initial accumulator are valid
+        let init_total = rng.gen_range(0..=capacity);
+        let mut values = (0..rng.gen_range(0..3))
+            .map(|_| rng.gen_range(0..=mask) as u64)
+            .collect::<Vec<_>>();
+        let position = rng.gen_range(0..=values.len());
+        values.insert(position, rng.gen_range(mask + 1..=2 * mask + 1) as u64);
+        (values, init_total)
+    } else {
+        // the accumulator is less than 2^max_bits away from the capacity, the last value goes over it
+        let init_total = capacity - rng.gen_range(0..mask);
+        let mut remaining = capacity - init_total;
+        let mut values = (0..rng.gen_range(0..3))
+            .map(|_| {
+                let value = rng.gen_range(0..=remaining);
+                remaining -= value;
+                value as u64
+            })
+            .collect::<Vec<_>>();
+        values.push(rng.gen_range(remaining + 1..=mask) as u64);
+        (values, init_total)
+    };
+
+    let init = to_limbs(init_total, max_bits);
+    let (expected, overflow) = accumulate_reference(&values, init, max_bits);
+    assert!(overflow);
+
+    (values, init, expected)
+}
@@ -0,0 +1,79 @@
+// The circuits are built from outside the crate, the same way a downstream user would do it
+#![cfg(feature = "std")]
+
+use halo2_experiments::circuits::utils::{gen_proof, gen_srs, run_checked, verify_kzg_proof};
+use halo2_experiments::circuits::{Hash2Circuit, MerkleSumTreeCircuit, RangeProofCircuit};
+use halo2_experiments::merkle_sum_tree::{MerkleSumTree, Node};
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    halo2curves::bn256::Fr as Fp,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+#[test]
+fn test_merkle_sum_tree_circuit() {
+    let leaves = (1..=8u64)
+        .map(|i| Node {
+            hash: Fp::from(i),
+            balance: Fp::from(i * 100),
+        })
+        .collect::<Vec<Node>>();
+    let proof = MerkleSumTree::new(leaves).generate_proof(5);
+
+    // greater than the liabilities sum (3600)
+    let circuit = MerkleSumTreeCircuit::new(
+        proof.leaf.hash,
+        proof.leaf.balance,
+        proof.path_elements.iter().map(|node| node.hash).collect(),
+        proof
+            .path_elements
+            .iter()
+            .map(|node| node.balance)
+            .collect(),
+        proof.path_indices,
+        Fp::from(4000u64),
+    );
+
+    let public_input = circuit.public_inputs();
+    assert_eq!(public_input[2], proof.root.hash);
+
+    let prover = run_checked(10, &circuit, vec![public_input]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+fn test_range_proof_circuit() {
+    let circuit = RangeProofCircuit::<Fp>::new(50, 10, 100);
+
+    let prover = MockProver::run(9, &circuit, vec![vec![Fp::from(10), Fp::from(100)]]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+fn test_hash_2_kzg_proof() {
+    let k = 4;
+    let circuit = Hash2Circuit {
+        a: Value::known(Fp::from(2)),
+        b: Value::known(Fp::from(7)),
+    };
+    let public_input = vec![Fp::from(9)];
+
+    let params = gen_srs(k);
+    let vk = keygen_vk(&params, &circuit).unwrap();
+    let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+    let proof = gen_proof(&params, &pk, circuit, &public_input);
+    assert!(verify_kzg_proof(
+        &params,
+        pk.get_vk(),
+        &[public_input],
+        &proof
+    ));
+    assert!(!verify_kzg_proof(
+        &params,
+        pk.get_vk(),
+        &[vec![Fp::from(8)]],
+        &proof
+    ));
+}
@@ -0,0 +1,151 @@
+// Composes chips coming from different experiments in a single circuit under one `eth_types::Field` bound:
+// the value is decomposed into 16 bits by `OverflowChipV2`, then `IsZeroChip` and `LtChip` check that
+// 0 < value < bound on the same row
+#![cfg(feature = "std")]
+
+use eth_types::Field;
+use gadgets::less_than::{LtChip, LtConfig, LtInstruction};
+use halo2_experiments::chips::is_zero::{IsZeroChip, IsZeroConfig};
+use halo2_experiments::chips::overflow_check_v2::{OverflowCheckV2Config, OverflowChipV2};
+use halo2_proofs::{
+    circuit::*, dev::MockProver, halo2curves::bn256::Fr as Fp, plonk::*, poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+struct ComposedConfig<F: Field> {
+    value: Column<Advice>,
+    bound: Column<Advice>,
+    selector: Selector,
+    overflow_config: OverflowCheckV2Config<4, 4, 4>,
+    is_zero_config: IsZeroConfig<F>,
+    lt_config: LtConfig<F, 2>,
+}
+
+// | value | value_inv | bound | lt diff | selector |
+// | --    | --        | --    | --      | --       |
+// | v     | 1/v       | b     | ...     | 1        |
+#[derive(Default)]
+struct ComposedCircuit<F: Field> {
+    value: Value<F>,
+    bound: F,
+}
+
+impl<F: Field> Circuit<F> for ComposedCircuit<F> {
+    type Config = ComposedConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+
+        let decomposed_value = meta.advice_column();
+        let limbs = [(); 4].map(|_| meta.advice_column());
+        let range = meta.fixed_column();
+        let overflow_selector = meta.selector();
+        let overflow_config = OverflowChipV2::<4, 4, 4, F>::configure(
+            meta,
+            decomposed_value,
+            limbs,
+            range,
+            instance,
+            overflow_selector,
+        );
+
+        let value = meta.advice_column();
+        let value_inv = meta.advice_column();
+        let bound = meta.advice_column();
+        let selector = meta.selector();
+        meta.enable_equality(value);
+
+        let is_zero_config = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+        );
+
+        let lt_config = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            |meta| meta.query_advice(bound, Rotation::cur()),
+        );
+
+        // the value is not zero and it is less than the bound
+        meta.create_gate("0 < value < bound", |meta| {
+            let s = meta.query_selector(selector);
+            vec![
+                s.clone() * is_zero_config.expr(),
+                s * (lt_config.is_lt(meta, None) - Expression::Constant(F::one())),
+            ]
+        });
+
+        ComposedConfig {
+            value,
+            bound,
+            selector,
+            overflow_config,
+            is_zero_config,
+            lt_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let overflow_chip = OverflowChipV2::construct(config.overflow_config);
+        let is_zero_chip = IsZeroChip::construct(config.is_zero_config);
+        let lt_chip = LtChip::construct(config.lt_config);
+
+        overflow_chip.load(&mut layouter)?;
+        lt_chip.load(&mut layouter)?;
+
+        let (value_cell, _) =
+            overflow_chip.assign(layouter.namespace(|| "decompose"), self.value)?;
+
+        layouter.assign_region(
+            || "0 < value < bound",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                let value_cell =
+                    value_cell.copy_advice(|| "copy value", &mut region, config.value, 0)?;
+                region.assign_advice(|| "bound", config.bound, 0, || Value::known(self.bound))?;
+
+                is_zero_chip.assign(&mut region, 0, value_cell.value().copied())?;
+
+                let mut value = F::zero();
+                value_cell.value().map(|v| value = *v);
+                lt_chip.assign(&mut region, 0, value, self.bound)
+            },
+        )?;
+
+        overflow_chip.expose_public(layouter.namespace(|| "public value"), &value_cell, 0)
+    }
+}
+
+fn verify(value: u64, bound: u64) -> bool {
+    let circuit = ComposedCircuit {
+        value: Value::known(Fp::from(value)),
+        bound: Fp::from(bound),
+    };
+    MockProver::run(9, &circuit, vec![vec![Fp::from(value)]])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+#[test]
+fn test_chips_under_one_field_bound() {
+    assert!(verify(1000, 2000));
+
+    // zero value
+    assert!(!verify(0, 2000));
+    // value not less than the bound
+    assert!(!verify(2000, 2000));
+}
@@ -0,0 +1,98 @@
+// Uses `IsZeroChip` from outside the crate to check that a value is equal to zero
+use halo2_experiments::chips::is_zero::{IsZeroChip, IsZeroConfig};
+use halo2_proofs::{
+    circuit::*, dev::MockProver, halo2curves::bn256::Fr as Fp, plonk::*, poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+struct IsZeroCircuitConfig {
+    value: Column<Advice>,
+    is_zero: Column<Advice>,
+    selector: Selector,
+    is_zero_config: IsZeroConfig<Fp>,
+}
+
+// | value | value_inv | is_zero | selector |
+// | --    | --        | --      | --       |
+// | x     | 1/x or 0  | 0 or 1  | 1        |
+#[derive(Default)]
+struct IsZeroCircuit {
+    value: Value<Fp>,
+    is_zero: Value<Fp>,
+}
+
+impl Circuit<Fp> for IsZeroCircuit {
+    type Config = IsZeroCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let value = meta.advice_column();
+        let value_inv = meta.advice_column();
+        let is_zero = meta.advice_column();
+        let selector = meta.selector();
+
+        let is_zero_config = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+        );
+
+        meta.create_gate("is zero output", |meta| {
+            let s = meta.query_selector(selector);
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+
+            vec![s * (is_zero - is_zero_config.expr())]
+        });
+
+        IsZeroCircuitConfig {
+            value,
+            is_zero,
+            selector,
+            is_zero_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = IsZeroChip::construct(config.is_zero_config);
+
+        layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", config.value, 0, || self.value)?;
+                region.assign_advice(|| "is zero", config.is_zero, 0, || self.is_zero)?;
+                chip.assign(&mut region, 0, self.value)
+            },
+        )
+    }
+}
+
+fn verify(value: u64, is_zero: u64) -> bool {
+    let circuit = IsZeroCircuit {
+        value: Value::known(Fp::from(value)),
+        is_zero: Value::known(Fp::from(is_zero)),
+    };
+    MockProver::run(4, &circuit, vec![])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+#[test]
+fn test_is_zero() {
+    assert!(verify(0, 1));
+    assert!(verify(5, 0));
+
+    // wrong outputs
+    assert!(!verify(0, 0));
+    assert!(!verify(5, 1));
+}